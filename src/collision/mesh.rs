@@ -2,7 +2,10 @@ use std::fmt::Display;
 
 use gl_matrix::common::Vec3;
 
-use crate::{collision::hit_attribute::HitAttribute, macros::md_read};
+use crate::{
+    collision::hit_attribute::HitAttribute,
+    mono_data::{MonoDataError, MonoDataReader},
+};
 
 use super::aabb::Aabb;
 
@@ -83,28 +86,28 @@ impl Display for Mesh {
 }
 
 impl Mesh {
-    /// Parse a triangle mesh from mono data.
-    /// The argument `mono_data` should point to the first byte of the triangle mesh.
-    pub unsafe fn from_raw(mono_data: *const u8) -> Mesh {
+    /// Parse a triangle mesh from the mono data at `offset`, which should be the first byte
+    /// of the triangle mesh.
+    pub fn parse(md: &MonoDataReader, offset: usize) -> Result<Mesh, MonoDataError> {
         // parse the number of sectors (mesh offset 0)
-        let num_sectors = md_read!(mono_data, u8, 0);
+        let num_sectors = md.read_u8(offset)?;
 
         // for each sector:
         let mut sectors = vec![];
-        for sector_idx in 0..num_sectors as isize {
+        for sector_idx in 0..num_sectors as usize {
             // parse the offset where sector starts
-            let sector_offset = md_read!(mono_data, u32, 4) as isize;
+            let sector_offset = md.read_u32(offset + 4)? as usize;
 
             // parse the sector's AABB
-            let aabb_offset = sector_offset + 8 + sector_idx * 0x18;
+            let aabb_offset = offset + sector_offset + 8 + sector_idx * 0x18;
             let mut aabb = Aabb {
-                min: md_read!(mono_data, Vec3, aabb_offset),
-                max: md_read!(mono_data, Vec3, aabb_offset + 12),
+                min: md.read_vec3(aabb_offset)?,
+                max: md.read_vec3(aabb_offset + 12)?,
             };
             aabb.negate_coords();
 
             // parse the offset of the first triangle group
-            let mut tri_group_offset = md_read!(mono_data, u32, sector_idx * 4 + 8) as isize;
+            let mut tri_group_offset = offset + md.read_u32(offset + sector_idx * 4 + 8)? as usize;
 
             // parse the contiguous list of triangle groups in the sector
             let mut tri_groups = vec![];
@@ -112,7 +115,7 @@ impl Mesh {
                 // parse the 1-byte header before the vertex list, which encodes:
                 //   - whether the vertex list is a triangle strip or not
                 //   - the number of encoded vertices following the header
-                let vertex_header: u32 = md_read!(mono_data, u8, tri_group_offset).into();
+                let vertex_header: u32 = md.read_u8(tri_group_offset)?.into();
 
                 let is_tri_strip = vertex_header & 0x80 != 0;
                 let num_vertices = if is_tri_strip {
@@ -124,10 +127,10 @@ impl Mesh {
                 // parse the list of vertices
                 let mut vertices = vec![];
                 let mut vertex_offset = tri_group_offset + 8;
-                for _ in 0..num_vertices as isize {
+                for _ in 0..num_vertices {
                     vertices.push(TriVertex {
-                        point: md_read!(mono_data, Vec3, vertex_offset),
-                        metadata: md_read!(mono_data, u32, vertex_offset + 0xc),
+                        point: md.read_vec3(vertex_offset)?,
+                        metadata: md.read_u32(vertex_offset + 0xc)?,
                     });
                     vertex_offset += 0x10;
                 }
@@ -139,10 +142,10 @@ impl Mesh {
                 });
 
                 // update the triangle group offset to the beginning of the next group
-                tri_group_offset += 0xc + 0x10 * (num_vertices as isize);
+                tri_group_offset += 0xc + 0x10 * (num_vertices as usize);
 
                 // parse the vifcode to detect the end of the sector
-                let vif = md_read!(mono_data, u8, tri_group_offset - 1);
+                let vif = md.read_u8(tri_group_offset - 1)?;
                 if vif == 0x97 {
                     break;
                 }
@@ -151,6 +154,6 @@ impl Mesh {
             sectors.push(MeshSector { aabb, tri_groups });
         }
 
-        Mesh { sectors }
+        Ok(Mesh { sectors })
     }
 }
@@ -3,3 +3,4 @@ pub mod hit_attribute;
 pub mod mesh;
 pub mod raycast_state;
 pub mod util;
+pub mod world_collider;
@@ -8,7 +8,7 @@ use gl_matrix::{
 use crate::{
     collision::mesh::TriGroup,
     constants::{UNITY_TO_SIM_SCALE, VEC3_Y_POS},
-    debug::DEBUG_CONFIG,
+    debug::debug_config,
     delegates::{has_delegates::HasDelegates, Delegates},
     macros::{debug_log, panic_log, vec3_from},
     math::{vec3_inplace_normalize, vec3_inplace_zero_small},
@@ -18,6 +18,7 @@ use crate::{
 use super::{
     hit_attribute::HitAttribute,
     mesh::{Mesh, MeshSector, TriVertex},
+    world_collider::WorldCollider,
 };
 
 const IMPACT_EPS: f32 = 0.0001;
@@ -190,6 +191,7 @@ impl RaycastState {
     }
 
     /// Use the unity delegates to find the nearest map hit along the cached ray.
+    /// If a `WorldCollider` is set on the delegates, it's used instead of unity.
     pub fn find_nearest_unity_hit(
         &mut self,
         call_type: RaycastCallType,
@@ -199,6 +201,18 @@ impl RaycastState {
 
         self.tri_hits.clear();
 
+        let world_collider = self
+            .delegates
+            .as_ref()
+            .and_then(|delegates| delegates.borrow().world_collider.clone());
+        if let Some(world_collider) = world_collider {
+            return self.find_nearest_world_hit(
+                world_collider.as_ref(),
+                call_type,
+                include_objects,
+            );
+        }
+
         let delegates = self
             .delegates
             .as_ref()
@@ -291,6 +305,41 @@ impl RaycastState {
         false
     }
 
+    /// The same as `find_nearest_unity_hit`, but the hits come from `world_collider`
+    /// rather than from the unity delegates.
+    fn find_nearest_world_hit(
+        &mut self,
+        world_collider: &dyn WorldCollider,
+        call_type: RaycastCallType,
+        include_objects: bool,
+    ) -> bool {
+        let hits = world_collider.raycast(&self.point0, &self.point1, call_type, include_objects);
+
+        for hit in hits {
+            // skip `KingWarp` and `Jump` hits, as with unity hits
+            let hit_attr: HitAttribute = hit.metadata.into();
+            if hit_attr == HitAttribute::KingWarp || hit_attr == HitAttribute::Jump {
+                continue;
+            }
+
+            self.closest_hit_idx = Some(0);
+            self.ray_len = vec3::distance(&self.point0, &self.point1);
+            self.tri_hits.push(hit);
+
+            return hit_attr != HitAttribute::WaterSurface;
+        }
+
+        false
+    }
+
+    /// Store the hits `hits` of a world collider raycast along the loaded ray, which are
+    /// sorted by increasing distance (so the closest hit is the first one).
+    pub fn set_world_hits(&mut self, hits: Vec<RaycastTriHit>) {
+        self.ray_len = vec3::distance(&self.point0, &self.point1);
+        self.closest_hit_idx = if hits.is_empty() { None } else { Some(0) };
+        self.tri_hits = hits;
+    }
+
     /// If this raycast's state cached ray hits the triangle `triangle`
     /// Adapted from https://en.m.wikipedia.org/wiki/M%C3%B6ller%E2%80%93Trumbore_intersection_algorithm
     /// offset: 0x11d70
@@ -430,7 +479,7 @@ impl RaycastState {
 
             // if there was an aabb hit, attempt to do some debug collision drawing
             // for the prop mesh that was hit
-            if DEBUG_CONFIG.draw_collided_prop_aabb_hits {
+            if debug_config().draw_collided_prop_aabb_hits {
                 self.debug_draw_collided_aabb_hits(&aabb_collision_out, transform);
             }
         }
@@ -483,7 +532,7 @@ impl RaycastState {
                         if let Some(tri_hit_dist) = tri_hit_result {
                             // if we hit the triangle:
 
-                            if DEBUG_CONFIG.draw_collided_prop_tris {
+                            if debug_config().draw_collided_prop_tris {
                                 self.debug_draw_collided_tri_hit(
                                     vertices.try_into().unwrap(),
                                     transform,
@@ -19,3 +19,20 @@ pub fn max_transformed_y(vertices: &Vec<Vec3>, transform: &Mat4) -> f32 {
 
     max_y
 }
+
+/// Computes the lowest y coordinate among all points in `vertices`
+/// after they are transformed by `transform`.
+pub fn min_transformed_y(vertices: &[Vec3], transform: &Mat4) -> f32 {
+    let mut min_y = f32::INFINITY;
+    let mut trans_pt = [0.0; 3];
+
+    for aabb_pt in vertices.iter() {
+        // apply the `transform` to the `aabb_pt`
+        vec3::transform_mat4(&mut trans_pt, aabb_pt, transform);
+        if trans_pt[1] < min_y {
+            min_y = trans_pt[1];
+        }
+    }
+
+    min_y
+}
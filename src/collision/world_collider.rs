@@ -0,0 +1,360 @@
+use std::{fmt::Debug, io, path::Path, rc::Rc};
+
+use gl_matrix::{common::Vec3, vec3};
+
+use crate::{
+    collision::{aabb::Aabb, hit_attribute::HitAttribute, raycast_state::RaycastTriHit},
+    constants::UNITY_TO_SIM_SCALE,
+    macros::{max, min, vec3_from},
+    math::vec3_inplace_normalize,
+};
+
+use super::raycast_state::RaycastCallType;
+
+/// A source of world (i.e. stage) collision that can stand in for the Unity raycast
+/// delegates (`do_hit`, `get_hit_count`, `get_impact_point`, `get_impact_normal` and
+/// `get_hit_attribute`). When a world collider is set on the `Delegates`, every world
+/// raycast is answered by it instead of by Unity, which lets the simulation run headless.
+pub trait WorldCollider: Debug {
+    /// Cast the ray from `point0` to `point1` (in simulation coordinates) against the world.
+    /// Hits must be sorted by increasing distance from `point0`, which is how Unity's
+    /// `do_hit` orders them. The `metadata` of each hit is its `HitAttribute`.
+    fn raycast(
+        &self,
+        point0: &Vec3,
+        point1: &Vec3,
+        call_type: RaycastCallType,
+        include_objects: bool,
+    ) -> Vec<RaycastTriHit>;
+}
+
+pub type WorldColliderRef = Rc<dyn WorldCollider>;
+
+/// A single triangle of a `StageMeshCollider`.
+#[derive(Debug, Clone)]
+struct StageTri {
+    points: [Vec3; 3],
+    normal_unit: Vec3,
+    hit_attr: HitAttribute,
+}
+
+/// A group of stage triangles contained in an AABB, so that most rays can skip
+/// the whole group after a single AABB test.
+#[derive(Debug, Default, Clone)]
+struct StageSector {
+    aabb: Aabb,
+    tris: Vec<StageTri>,
+}
+
+impl StageSector {
+    /// Returns `true` if the bounding box of the segment from `p0` to `p1` meets this
+    /// sector's AABB.
+    fn maybe_hit_by(&self, p0: &Vec3, p1: &Vec3) -> bool {
+        (0..3).all(|i| {
+            min!(p0[i], p1[i]) <= self.aabb.max[i] && max!(p0[i], p1[i]) >= self.aabb.min[i]
+        })
+    }
+}
+
+/// The built-in `WorldCollider`, which raycasts against stage triangle meshes.
+/// Triangles are one-sided: a ray only hits a triangle from the side its normal
+/// points towards, as with Unity mesh colliders.
+#[derive(Debug, Default, Clone)]
+pub struct StageMeshCollider {
+    sectors: Vec<StageSector>,
+}
+
+impl StageMeshCollider {
+    /// Start a new sector. Triangles added after this call will be grouped into it.
+    pub fn begin_sector(&mut self) {
+        self.sectors.push(StageSector {
+            aabb: Aabb {
+                min: [f32::MAX; 3],
+                max: [f32::MIN; 3],
+            },
+            tris: vec![],
+        });
+    }
+
+    /// Add a triangle (in simulation coordinates) to the current sector. The triangle's
+    /// front face is the one from which its points appear in counterclockwise order.
+    pub fn add_tri(&mut self, points: [Vec3; 3], hit_attr: HitAttribute) {
+        if self.sectors.is_empty() {
+            self.begin_sector();
+        }
+
+        let edge1 = vec3_from!(-, points[1], points[0]);
+        let edge2 = vec3_from!(-, points[2], points[0]);
+        let mut normal_unit = vec3::create();
+        vec3::cross(&mut normal_unit, &edge1, &edge2);
+        vec3_inplace_normalize(&mut normal_unit);
+
+        let sector = self.sectors.last_mut().unwrap();
+        for point in points.iter() {
+            for (i, coord) in point.iter().enumerate() {
+                sector.aabb.min[i] = min!(sector.aabb.min[i], *coord);
+                sector.aabb.max[i] = max!(sector.aabb.max[i], *coord);
+            }
+        }
+
+        sector.tris.push(StageTri {
+            points,
+            normal_unit,
+            hit_attr,
+        });
+    }
+
+    /// The total number of triangles in the collider.
+    pub fn num_tris(&self) -> usize {
+        self.sectors.iter().map(|sector| sector.tris.len()).sum()
+    }
+
+    /// Load a collider from a Wavefront OBJ file whose coordinates are Unity coordinates.
+    /// See `StageMeshCollider::from_obj_str` for the supported subset of the format.
+    pub fn from_obj_file(path: &Path) -> io::Result<Self> {
+        Self::from_obj_str(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse a collider from the text of a Wavefront OBJ file whose coordinates are Unity
+    /// coordinates. Only `v`, `f`, `o`, `g` and `usemtl` lines are read:
+    ///   - each `o` or `g` line starts a new sector,
+    ///   - polygonal faces are triangulated as fans,
+    ///   - a `usemtl hit_attr_<n>` line assigns the hit attribute `n` to subsequent faces
+    ///     (any other material name resets the hit attribute to `HitAttribute::None`).
+    pub fn from_obj_str(obj: &str) -> io::Result<Self> {
+        let invalid = |line_idx: usize, msg: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: {msg}", line_idx + 1),
+            )
+        };
+
+        let mut result = Self::default();
+        let mut vertices: Vec<Vec3> = vec![];
+        let mut hit_attr = HitAttribute::None;
+
+        for (line_idx, line) in obj.lines().enumerate() {
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("v") => {
+                    let mut point = [0.0; 3];
+                    for coord in point.iter_mut() {
+                        *coord = tokens
+                            .next()
+                            .and_then(|t| t.parse::<f32>().ok())
+                            .ok_or_else(|| invalid(line_idx, "bad vertex"))?
+                            * UNITY_TO_SIM_SCALE;
+                    }
+                    vertices.push(point);
+                }
+                Some("f") => {
+                    let mut face = vec![];
+                    for token in tokens {
+                        // faces may be written as `v`, `v/vt`, `v//vn` or `v/vt/vn`
+                        let idx = token
+                            .split('/')
+                            .next()
+                            .and_then(|t| t.parse::<i64>().ok())
+                            .ok_or_else(|| invalid(line_idx, "bad face index"))?;
+
+                        // obj indices are 1-based, and negative indices count from the end
+                        let idx = if idx < 0 {
+                            vertices.len() as i64 + idx
+                        } else {
+                            idx - 1
+                        };
+                        let point = usize::try_from(idx)
+                            .ok()
+                            .and_then(|idx| vertices.get(idx))
+                            .ok_or_else(|| invalid(line_idx, "face index out of range"))?;
+                        face.push(*point);
+                    }
+
+                    if face.len() < 3 {
+                        return Err(invalid(line_idx, "face with fewer than 3 vertices"));
+                    }
+
+                    for i in 1..face.len() - 1 {
+                        result.add_tri([face[0], face[i], face[i + 1]], hit_attr);
+                    }
+                }
+                Some("o") | Some("g") => result.begin_sector(),
+                Some("usemtl") => {
+                    hit_attr = match tokens
+                        .next()
+                        .and_then(|name| name.strip_prefix("hit_attr_"))
+                    {
+                        Some(value) => match value.parse::<i32>() {
+                            Ok(value @ 0..=27) => value.into(),
+                            _ => return Err(invalid(line_idx, "unknown hit attribute")),
+                        },
+                        None => HitAttribute::None,
+                    };
+                }
+                _ => (),
+            }
+        }
+
+        // drop sectors that never received any triangles
+        result.sectors.retain(|sector| !sector.tris.is_empty());
+
+        Ok(result)
+    }
+
+    /// Returns the distance along the ray `p0 -> p0 + ray` at which it hits the front face
+    /// of `tri`, as a ratio of the ray's length.
+    /// (Uses the Möller-Trumbore algorithm, as in `RaycastState::ray_hits_triangle`.)
+    fn ray_hits_tri(p0: &Vec3, ray: &Vec3, tri: &StageTri) -> Option<f32> {
+        const EPS: f32 = 0.000001;
+
+        if vec3::dot(&tri.normal_unit, ray) > 0.0 {
+            return None;
+        }
+
+        let [t0, t1, t2] = &tri.points;
+        let edge1 = vec3_from!(-, t1, t0);
+        let edge2 = vec3_from!(-, t2, t0);
+
+        let mut h = vec3::create();
+        vec3::cross(&mut h, ray, &edge2);
+        let a = vec3::dot(&edge1, &h);
+        if a > -EPS && a < EPS {
+            return None;
+        }
+
+        let f = 1.0 / a;
+        let s = vec3_from!(-, p0, t0);
+        let u = f * vec3::dot(&s, &h);
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let mut q = vec3::create();
+        vec3::cross(&mut q, &s, &edge1);
+        let v = f * vec3::dot(ray, &q);
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = f * vec3::dot(&edge2, &q);
+        if t > EPS && t < 1.0 {
+            Some(t)
+        } else {
+            None
+        }
+    }
+}
+
+impl WorldCollider for StageMeshCollider {
+    fn raycast(
+        &self,
+        point0: &Vec3,
+        point1: &Vec3,
+        call_type: RaycastCallType,
+        _include_objects: bool,
+    ) -> Vec<RaycastTriHit> {
+        let ray = vec3_from!(-, point1, point0);
+        let ray_len = vec3::length(&ray);
+        let water_only = matches!(call_type, RaycastCallType::Water);
+
+        let mut hits = vec![];
+        for sector in self.sectors.iter() {
+            if !sector.maybe_hit_by(point0, point1) {
+                continue;
+            }
+
+            for tri in sector.tris.iter() {
+                if water_only && tri.hit_attr != HitAttribute::WaterSurface {
+                    continue;
+                }
+
+                if let Some(t) = Self::ray_hits_tri(point0, &ray, tri) {
+                    let mut impact_point = vec3::create();
+                    vec3::scale_and_add(&mut impact_point, point0, &ray, t);
+
+                    hits.push(RaycastTriHit {
+                        impact_point,
+                        normal_unit: tri.normal_unit,
+                        tri: tri.points,
+                        metadata: tri.hit_attr.into(),
+                        impact_dist_ratio: t,
+                        impact_dist: t * ray_len,
+                    });
+                }
+            }
+        }
+
+        hits.sort_by(|a, b| a.impact_dist.total_cmp(&b.impact_dist));
+        hits
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::collision::{hit_attribute::HitAttribute, raycast_state::RaycastCallType};
+
+    use super::{StageMeshCollider, WorldCollider};
+
+    // a 2x2 unity-unit floor at y=0 with a water surface 1 unity unit above it
+    const FLOOR_AND_WATER_OBJ: &str = "
+o floor
+v -1 0 -1
+v -1 0 1
+v 1 0 1
+v 1 0 -1
+f 1 2 3 4
+o water
+usemtl hit_attr_1
+v -1 1 -1
+v -1 1 1
+v 1 1 1
+f -3 -2 -1
+";
+
+    #[test]
+    fn test_stage_mesh_collider_hits() {
+        let collider = StageMeshCollider::from_obj_str(FLOOR_AND_WATER_OBJ).unwrap();
+        assert_eq!(collider.num_tris(), 3);
+
+        // a ray straight down hits the water surface first, then the floor
+        let hits = collider.raycast(
+            &[-50.0, 200.0, 0.0],
+            &[-50.0, -100.0, 0.0],
+            RaycastCallType::Objects,
+            false,
+        );
+        assert_eq!(hits.len(), 2);
+        assert_eq!(
+            HitAttribute::from(hits[0].metadata),
+            HitAttribute::WaterSurface
+        );
+        assert_eq!(hits[1].metadata, 0);
+        assert!((hits[1].impact_point[1]).abs() < 0.001);
+        assert!((hits[1].normal_unit[1] - 1.0).abs() < 0.001);
+        assert!((hits[1].impact_dist - 200.0).abs() < 0.001);
+
+        // water raycasts only see water surfaces
+        let water_hits = collider.raycast(
+            &[-50.0, 200.0, 0.0],
+            &[-50.0, -100.0, 0.0],
+            RaycastCallType::Water,
+            false,
+        );
+        assert_eq!(water_hits.len(), 1);
+
+        // a ray straight up hits the back faces, so it doesn't hit anything
+        let back_hits = collider.raycast(
+            &[-50.0, -100.0, 0.0],
+            &[-50.0, 200.0, 0.0],
+            RaycastCallType::Objects,
+            false,
+        );
+        assert!(back_hits.is_empty());
+    }
+
+    #[test]
+    fn test_stage_mesh_collider_bad_obj() {
+        assert!(StageMeshCollider::from_obj_str("f 1 2 3").is_err());
+        assert!(StageMeshCollider::from_obj_str("usemtl hit_attr_99").is_err());
+    }
+}
@@ -1,5 +1,11 @@
 pub mod draw;
 
+use std::{cell::Cell, path::Path};
+
+/// Flags which turn on debug logging and drawing. The current config is held in runtime state
+/// (see `debug_config`), and can be changed with the `SetDebugFlag` API function or with a
+/// JSON config file read at `Init` (see `load_debug_config_file`).
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct DebugConfig {
     /// If `false`, the `crate::util::debug_log` function becomes a no-op.
     pub allow_debug_logs: bool,
@@ -31,7 +37,6 @@ pub struct DebugConfig {
     pub kat_draw_shell_rays: bool,
 }
 
-// TODO_DEBUG: this should be made editable mid-execution.
 pub const DEV_CONFIG: DebugConfig = DebugConfig {
     allow_debug_logs: true,
     log_tick: false,
@@ -57,7 +62,140 @@ pub const RELEASE_CONFIG: DebugConfig = DebugConfig {
 };
 
 #[cfg(debug_assertions)]
-pub const DEBUG_CONFIG: DebugConfig = DEV_CONFIG;
+pub const DEFAULT_DEBUG_CONFIG: DebugConfig = DEV_CONFIG;
 
 #[cfg(not(debug_assertions))]
-pub const DEBUG_CONFIG: DebugConfig = RELEASE_CONFIG;
+pub const DEFAULT_DEBUG_CONFIG: DebugConfig = RELEASE_CONFIG;
+
+/// The path of the debug config file read at `Init`.
+pub const DEBUG_CONFIG_PATH: &str =
+    "C:\\Program Files (x86)\\Steam\\steamapps\\common\\Katamari Damacy REROLL\\debug_config.json";
+
+thread_local! {
+    static DEBUG_CONFIG: Cell<DebugConfig> = const { Cell::new(DEFAULT_DEBUG_CONFIG) };
+}
+
+impl Default for DebugConfig {
+    fn default() -> Self {
+        DEFAULT_DEBUG_CONFIG
+    }
+}
+
+impl DebugConfig {
+    /// Set the flag with the field name `name` to `value`.
+    /// Returns `false` if there is no flag named `name`.
+    pub fn set_flag(&mut self, name: &str, value: bool) -> bool {
+        let flag = match name {
+            "allow_debug_logs" => &mut self.allow_debug_logs,
+            "log_tick" => &mut self.log_tick,
+            "log_nonzero_hit_attribute_hits" => &mut self.log_nonzero_hit_attribute_hits,
+            "log_destroyed_props" => &mut self.log_destroyed_props,
+            "draw_collided_prop_aabb_hits" => &mut self.draw_collided_prop_aabb_hits,
+            "draw_collided_prop_mesh" => &mut self.draw_collided_prop_mesh,
+            "draw_collided_prop_tris" => &mut self.draw_collided_prop_tris,
+            "kat_draw_collision_rays" => &mut self.kat_draw_collision_rays,
+            "kat_draw_shell_rays" => &mut self.kat_draw_shell_rays,
+            _ => return false,
+        };
+
+        *flag = value;
+        true
+    }
+
+    /// Set the flags in a JSON object of flags (e.g. `{ "log_tick": true }`), keeping the
+    /// values of flags which aren't in the object. Nothing is changed if the object has a
+    /// value that isn't a bool, or a name that isn't a flag.
+    pub fn merge_json(&mut self, json: &str) -> Result<(), String> {
+        let flags: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+        let mut merged = *self;
+        for (name, value) in flags.iter() {
+            let Some(value) = value.as_bool() else {
+                return Err(format!("debug flag `{name}` isn't a bool"));
+            };
+            if !merged.set_flag(name, value) {
+                return Err(format!("unknown debug flag `{name}`"));
+            }
+        }
+
+        *self = merged;
+        Ok(())
+    }
+}
+
+/// Returns the current debug config.
+pub fn debug_config() -> DebugConfig {
+    DEBUG_CONFIG.with(Cell::get)
+}
+
+/// Replace the current debug config.
+pub fn set_debug_config(config: DebugConfig) {
+    DEBUG_CONFIG.with(|current| current.set(config));
+}
+
+/// Set the flag named `name` in the current debug config (see `DebugConfig::set_flag`).
+pub fn set_debug_flag(name: &str, value: bool) -> bool {
+    DEBUG_CONFIG.with(|current| {
+        let mut config = current.get();
+        let result = config.set_flag(name, value);
+        current.set(config);
+        result
+    })
+}
+
+/// Set the flags in the JSON file at `path` in the current debug config (see
+/// `DebugConfig::merge_json`), so that flags set with `SetDebugFlag` which aren't in the
+/// file are kept. If there is no file at `path`, the current debug config is kept.
+pub fn load_debug_config_file(path: &Path) -> Result<(), String> {
+    let json = match std::fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let mut config = debug_config();
+    config.merge_json(&json)?;
+    set_debug_config(config);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_debug_config() {
+        let initial_config = debug_config();
+
+        let mut config = DebugConfig::default();
+        config.merge_json(r#"{ "log_tick": true }"#).unwrap();
+        assert!(config.log_tick);
+        assert_eq!(
+            config.kat_draw_shell_rays,
+            DEFAULT_DEBUG_CONFIG.kat_draw_shell_rays
+        );
+        assert!(config.merge_json(r#"{ "log_tick": 1 }"#).is_err());
+        assert!(config.merge_json(r#"[true]"#).is_err());
+
+        assert!(!set_debug_flag("not_a_flag", true));
+        assert!(set_debug_flag("kat_draw_collision_rays", false));
+        assert!(!debug_config().kat_draw_collision_rays);
+        assert!(set_debug_flag("kat_draw_collision_rays", true));
+        assert!(debug_config().kat_draw_collision_rays);
+
+        // merging a config file keeps the flags that aren't in it
+        let mut config = debug_config();
+        config.log_destroyed_props = true;
+        config.merge_json(r#"{ "log_tick": true }"#).unwrap();
+        assert!(config.log_tick);
+        assert!(config.log_destroyed_props);
+        assert!(config
+            .merge_json(r#"{ "log_tick": false, "nope": true }"#)
+            .is_err());
+        assert!(config.merge_json(r#"{ "log_tick": 0 }"#).is_err());
+        assert!(config.log_tick);
+
+        set_debug_config(initial_config);
+    }
+}
@@ -17,6 +17,19 @@ pub trait HasDelegates {
         }
     }
 
+    // Convenience wrapper around requesting a king comment about the prop `ctrl_idx`
+    // (only if king comment requests are enabled).
+    fn message_request(&self, ctrl_idx: u16) {
+        if let Some(delegates_ref) = self.get_delegates_ref() {
+            let delegates = delegates_ref.borrow();
+            if let (true, Some(message_request)) =
+                (delegates.request_king_comments, delegates.message_request)
+            {
+                message_request(ctrl_idx as i32);
+            }
+        }
+    }
+
     // Convenience wrapper around playing VFX.
     fn play_vfx(
         &self,
@@ -7,7 +7,10 @@ use std::{cell::RefCell, rc::Rc};
 use gl_matrix::common::Vec3;
 
 use crate::{
-    collision::raycast_state::RaycastCallType,
+    collision::{
+        raycast_state::{RaycastCallType, RaycastRef},
+        world_collider::WorldColliderRef,
+    },
     debug::draw::{DebugDrawBus, DebugDrawType},
 };
 
@@ -87,6 +90,15 @@ pub struct Delegates {
 
     // delegates unique to the open simulation
     pub debug_draw: DebugDrawBus,
+
+    /// If set, world raycasts are answered by this collider instead of the unity
+    /// `do_hit` family of delegates.
+    pub world_collider: Option<WorldColliderRef>,
+
+    /// If set, king comments are requested via `message_request` when comment props are
+    /// collected. Off by default, since unity tracks comment groups and plays the comments
+    /// itself, so it would play each comment twice.
+    pub request_king_comments: bool,
 }
 
 pub type DelegatesRef = Rc<RefCell<Delegates>>;
@@ -100,15 +112,25 @@ impl core::fmt::Debug for Delegates {
 
 impl Delegates {
     /// Convenience wrapper around the `do_hit` delegate that allows vector inputs.
+    /// If a world collider answers the raycast, its hits are written to `raycasts` (where
+    /// unity's hits would be read from).
     /// offset: 0x6070
     pub fn call_do_hit(
         &self,
+        raycasts: Option<&RaycastRef>,
         point0: &Vec3,
         point1: &Vec3,
         call_type: RaycastCallType,
         include_objects: bool,
     ) -> i32 {
-        if let Some(do_hit) = self.do_hit {
+        if let Some(world_collider) = &self.world_collider {
+            let hits = world_collider.raycast(point0, point1, call_type, include_objects);
+            let num_hits = hits.len() as i32;
+            if let Some(raycasts) = raycasts {
+                raycasts.borrow_mut().set_world_hits(hits);
+            }
+            num_hits
+        } else if let Some(do_hit) = self.do_hit {
             do_hit(
                 point0[0],
                 point0[1],
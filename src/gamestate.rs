@@ -3,20 +3,35 @@ use std::{cell::RefCell, rc::Rc};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    collision::raycast_state::{RaycastRef, RaycastState},
-    debug::DEBUG_CONFIG,
+    collision::{
+        raycast_state::{RaycastRef, RaycastState},
+        world_collider::WorldColliderRef,
+    },
+    debug::debug_config,
     delegates::{has_delegates::HasDelegates, Delegates, DelegatesRef},
-    global::GlobalState,
+    global::{tick_rate::TickRate, GlobalState},
     macros::{debug_log, panic_log},
-    mission::{state::MissionState, vsmode::VsModeState, GameMode},
-    mono_data::MonoData,
+    mission::{state::MissionState, vsmode::VsModeState, GameMode, Mission},
+    mono_data::{MonoData, MonoDataError},
     player::{Player, PlayersState},
     props::{
         prop::{AddPropArgs, PropRef},
+        random::layout::random_layout_override,
         PropsState,
     },
 };
 
+/// The arguments of the `MonoInitStart` API function, other than the mono data itself.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MonoInitStartArgs {
+    pub mission: u8,
+    pub area: u8,
+    pub stage: u8,
+    pub kadai_flag: bool,
+    pub clear_flag: bool,
+    pub end_flag: bool,
+}
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct GameState {
     /// State unique to each player.
@@ -73,6 +88,12 @@ impl GameState {
         self.mono_data = Rc::new(MonoData::default());
     }
 
+    /// Answer world raycasts with `world_collider` instead of the unity delegates
+    /// (or go back to using the unity delegates if `world_collider` is `None`).
+    pub fn set_world_collider(&mut self, world_collider: Option<WorldColliderRef>) {
+        self.delegates.borrow_mut().world_collider = world_collider;
+    }
+
     pub fn get_player(&self, player_idx: usize) -> &Player {
         self.players.get(player_idx).unwrap()
     }
@@ -105,11 +126,13 @@ impl GameState {
         cam_z: f32,
     ) {
         self.global.royal_warp_plane_y = limit_y * 100.0;
-        self.players[0]
-            .katamari
-            .set_speed(forw_s, side_s, back_s, boost_s);
-        self.players[0].prince.set_global_turn_speed(rot_s);
-        self.players[0].camera.set_delay(cam_x, cam_y, cam_z);
+
+        // the speeds apply to both players in vs mode
+        for player in self.players.iter_mut() {
+            player.katamari.set_speed(forw_s, side_s, back_s, boost_s);
+            player.prince.set_global_turn_speed(rot_s);
+            player.camera.set_delay(cam_x, cam_y, cam_z);
+        }
     }
 
     /// Mimicks the `SetGameTime` API function.
@@ -212,29 +235,43 @@ impl GameState {
         self.props.get_attach_statuses(out, kat_diam_int)
     }
 
-    // Mimicks the `MonoInitStart` API function.
-    pub unsafe fn mono_init_start(
+    /// Mimicks the `MonoInitStart` API function.
+    /// Returns an error (without changing the game state) if `mono_data` can't be parsed.
+    pub fn mono_init_start(
         &mut self,
-        mono_data: *const u8,
+        mono_data: &[u8],
+        args: &MonoInitStartArgs,
+    ) -> Result<(), MonoDataError> {
+        // parse the mission's `MonoData` before changing anything, in case it's invalid.
+        let mono_data = MonoData::parse(mono_data)?;
+
+        self.mono_init_start_parsed(mono_data, args.mission, args.area, args.stage);
+        Ok(())
+    }
+
+    /// Mimicks the `MonoInitStart` API function, with mono data that was already parsed.
+    pub fn mono_init_start_parsed(
+        &mut self,
+        mono_data: MonoData,
         mission: u8,
         area: u8,
         stage: u8,
-        _kadai_flag: bool,
-        _clear_flag: bool,
-        _end_flag: bool,
     ) {
         self.global.mono_init_start();
         self.mission_state.mono_init_start(mission, area, stage);
 
-        // read the mission's `MonoData` data from the `mono_data` raw pointer.
-        self.mono_data = MonoData::from_raw(mono_data);
+        self.mono_data = Rc::new(mono_data);
         self.raycast
             .borrow_mut()
             .set_zone_mesh(self.mono_data.zone_mesh.clone());
 
-        // TODO_PROPS: init subobjects
         self.props.comments.reset();
         // TODO_PROPS: init random prop groups
+        if let Some(layout_override) = random_layout_override(self.mission_state.mission) {
+            self.props
+                .random
+                .apply_layout_override(&mut self.global, &layout_override);
+        }
         // TODO_PROPS: init generated props
     }
 
@@ -290,9 +327,12 @@ impl GameState {
 
     /// Mimicks the `MonoInitEnd` API function.
     pub fn mono_init_end(&mut self) {
-        // TODO_PROPS: init_cache_gemini_twins();
+        if self.mission_state.mission == Mission::Gemini {
+            self.props.init_cache_gemini_twins(&mut self.global);
+        }
         self.props.gps.init();
         self.global.props_initialized = true;
+        self.props.save_initial_state();
 
         // This seems like a good of a place as any to initialize prop link references
         // (which isn't something the original simulation needs to do).
@@ -303,6 +343,53 @@ impl GameState {
         // }
     }
 
+    /// Reset the mission to how it was after `Init` without reloading it from unity: every prop
+    /// is restored to how it was after `MonoInitEnd`, and each player's katamari, prince, and
+    /// camera are reinitialized at the mission's starting position. The rng isn't reset, and
+    /// unity should call `SetGameStart` again as it does after `Init`.
+    /// Returns `false` (without changing anything) if the mission hasn't been initialized.
+    pub fn reset_attempt(&mut self) -> bool {
+        if !self.global.props_initialized {
+            return false;
+        }
+        if !self.props.reset_attempt(&self.mono_data) {
+            return false;
+        }
+
+        self.global.reset_attempt();
+        self.mission_state.reset_attempt();
+
+        let num_players = if self.mission_state.is_vs_mode { 2 } else { 1 };
+        for (player_idx, player) in self.players.iter_mut().take(num_players).enumerate() {
+            player.reset_attempt(
+                player_idx as u8,
+                &self.delegates,
+                &self.mission_state,
+                self.raycast.clone(),
+            );
+        }
+
+        true
+    }
+
+    /// Set the rate at which the simulation is ticked, in ticks per second. Returns `false`
+    /// and leaves the tick rate unchanged if the rate isn't supported (see `TickRate::new`).
+    /// Note that the `delta` passed to `Tick` is still ignored: the tick rate has to be set
+    /// explicitly so that the simulation stays deterministic. Durations which are already
+    /// counting down when the tick rate changes aren't rescaled, so it should be set before `Init`.
+    pub fn set_tick_rate(&mut self, ticks_per_second: u16) -> bool {
+        let Some(tick_rate) = TickRate::new(ticks_per_second) else {
+            return false;
+        };
+
+        self.global.tick_rate = tick_rate;
+        for player in self.players.iter_mut() {
+            player.set_tick_rate(tick_rate);
+        }
+
+        true
+    }
+
     /// Mimicks the `SetStoreFlag` API function.
     pub fn set_store_flag(&mut self, store_flag: bool) {
         self.global.store_flag = store_flag;
@@ -352,6 +439,7 @@ impl GameState {
             .compute_destroy_invis_diam_ratio(&mission_config);
 
         // initialize the player (katamari, prince, camera)
+        self.players[player_idx].set_tick_rate(self.global.tick_rate);
         self.players[player_idx].init(
             player_idx as u8,
             &self.delegates,
@@ -376,8 +464,7 @@ impl GameState {
         }
 
         if mission_state.is_vs_mode {
-            // TODO_VS: initialize the vs mode state, presumably
-            self.mission_state.vsmode = VsModeState { timer_0x10bf10: 0 };
+            self.mission_state.vsmode = VsModeState::default();
         } else {
             // TODO: `init_simulation`:333-366, initialize somethings coming callbacks?
         }
@@ -392,14 +479,18 @@ impl GameState {
 
         self.global.ticks += 1;
 
-        if DEBUG_CONFIG.log_tick {
+        if debug_config().log_tick {
             debug_log!("tick {}", self.global.ticks);
         }
 
         // TODO_STOREFLAG: `update_game:23-89` (if store flag is on)
         self.global.updating_player = 0;
-        self.props
-            .update(&self.players[0], &self.mission_state, &mut self.global);
+        let num_players = if is_vs_mode { 2 } else { 1 };
+        self.props.update(
+            &self.players[..num_players],
+            &self.mission_state,
+            &mut self.global,
+        );
         // TODO: `update_game:93-101` (but put this in `props_update`)
         // TODO_TUTORIAL: `tutorial_update_flags`
 
@@ -410,7 +501,7 @@ impl GameState {
             // if vs mode, update the second player, then update vsmode-specific stuff
             self.global.updating_player = 1;
             self.update_prince_and_kat(1);
-            // TODO: `vsmode_update()`
+            self.update_vs_mode();
         }
 
         // TODO: `camera_update_transforms()`
@@ -440,12 +531,208 @@ impl GameState {
 
         if !is_vs_mode {
             // TODO: `update_game:176-255` (selectively update props based on their alpha)
-            self.props.update_prop_alphas();
+            self.props.update_prop_alphas(&self.players[0].katamari);
         } else {
             // TODO: `update_game:258-267` (idk vs mode crap)
         }
 
         // TODO: `update_game:269` (keep separate running global count of # of attached props, because reasons)
         //                         (don't do this)
+
+        self.props.update_king_comments(&self.global);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::{
+        collision::world_collider::StageMeshCollider, props::prop::AddPropArgs, trace::FrameTrace,
+    };
+
+    use super::{GameState, MonoInitStartArgs};
+
+    static MISSION_1_MONO_DATA: &[u8] = include_bytes!("./bin/monodata/mission1.bin");
+    static MISSION_31_MONO_DATA: &[u8] = include_bytes!("./bin/monodata/mission31.bin");
+
+    #[test]
+    fn test_headless_tick_with_world_collider() {
+        let mut state = GameState::new();
+        state.reset();
+        state
+            .mono_init_start(
+                MISSION_1_MONO_DATA,
+                &MonoInitStartArgs {
+                    mission: 1,
+                    area: 0,
+                    stage: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        state.mono_init_end();
+        state.init(0, -1.0, 1);
+        state.global.set_gravity(0.0, -1.0, 0.0);
+        state.set_katamari_speed(
+            1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, -100.0, 1.0, 1.0, 1.0,
+        );
+        state.set_game_start(0, 0);
+
+        // put a floor (in unity coordinates) a bit below the katamari's initial position
+        let init_pos = state.get_player(0).katamari.get_center();
+        let floor_y = (init_pos[1] - 10.0) / 100.0;
+        let floor_obj = format!(
+            "v -100 {floor_y} -100\nv -100 {floor_y} 100\nv 100 {floor_y} 100\nv 100 {floor_y} -100\nf 1 2 3 4"
+        );
+        let collider = StageMeshCollider::from_obj_str(&floor_obj).unwrap();
+        state.set_world_collider(Some(Rc::new(collider)));
+
+        for _ in 0..30 {
+            state.tick(1.0 / 30.0);
+        }
+
+        // the katamari should have fallen onto the floor and come to rest there
+        let katamari = &state.get_player(0).katamari;
+        let bottom_y = katamari.get_center()[1] - katamari.get_radius();
+        assert!(katamari.physics_flags.contacts_floor);
+        assert!((bottom_y - floor_y * 100.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_reset_attempt() {
+        let mut state = GameState::new();
+        state.reset();
+        assert!(!state.reset_attempt());
+
+        state
+            .mono_init_start(
+                MISSION_1_MONO_DATA,
+                &MonoInitStartArgs {
+                    mission: 1,
+                    area: 0,
+                    stage: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let mut prop_args = AddPropArgs {
+            pos_x: 0.0,
+            pos_y: 0.0,
+            pos_z: 1.0,
+            rot_x: 0.0,
+            rot_y: 0.0,
+            rot_z: 0.0,
+            rot_w: 1.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            scale_z: 1.0,
+            name_idx: 782,
+            loc_pos_type: 0,
+            random_group_id: 0xffff,
+            mono_move_type: 0xffff,
+            mono_hit_on_area: 0xffff,
+            link_action: 0xffff,
+            extra_action_type: 0xffff,
+            unique_name_id: 0xffff,
+            disp_off_area_no: 0xffff,
+            vs_drop_flag: 0,
+            comment_id: 0xffff,
+            comment_group_id: 0xffff,
+            twin_id: 0xffff,
+            shake_off_flag: 0,
+        };
+        prop_args.transform_coords_to_sim();
+        state.add_prop(&prop_args);
+        state.mono_init_end();
+        state.init(0, -1.0, 1);
+        state.global.set_gravity(0.0, -1.0, 0.0);
+        state.set_katamari_speed(
+            1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, -100.0, 1.0, 1.0, 1.0,
+        );
+        state.set_game_start(0, 0);
+
+        let collider = StageMeshCollider::from_obj_str(
+            "v -100 -1 -100\nv -100 -1 100\nv 100 -1 100\nv 100 -1 -100\nf 1 2 3 4",
+        )
+        .unwrap();
+        state.set_world_collider(Some(Rc::new(collider)));
+        state
+            .get_player_mut(0)
+            .input
+            .set_stick_state(0.0, 1.0, 0.0, 1.0, false, false, false, false);
+
+        let initial = FrameTrace::new(&state);
+        for _ in 0..60 {
+            state.tick(1.0 / 30.0);
+        }
+        assert_ne!(
+            state.get_player(0).katamari.get_center(),
+            &initial.kat_center
+        );
+
+        state.change_next_area();
+        assert_eq!(state.mission_state.stage_area, 1);
+
+        // after a reset, everything but the rng should be back to how it was after `Init`
+        assert!(state.reset_attempt());
+        assert_eq!(state.mission_state.area, 0);
+        assert_eq!(state.mission_state.stage_area, 0);
+        state.set_game_start(0, 0);
+        let mut reset = FrameTrace::new(&state);
+        reset.rng = initial.rng;
+        assert_eq!(reset.find_divergence(&initial, 0.0), None);
+    }
+
+    #[test]
+    fn test_vs_mode_tick() {
+        let mut state = GameState::new();
+        state.reset();
+        state
+            .mono_init_start(
+                MISSION_31_MONO_DATA,
+                &MonoInitStartArgs {
+                    mission: 31,
+                    area: 0,
+                    stage: 0,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        state.mono_init_end();
+        state.init(0, -1.0, 31);
+        state.init(1, -1.0, 31);
+        state.global.set_gravity(0.0, -1.0, 0.0);
+        state.set_katamari_speed(
+            1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, -100.0, 1.0, 1.0, 1.0,
+        );
+        state.set_game_start(0, 0);
+        state.set_game_start(1, 0);
+
+        let collider = StageMeshCollider::from_obj_str(
+            "v -100 -1 -100\nv -100 -1 100\nv 100 -1 100\nv 100 -1 -100\nf 1 2 3 4",
+        )
+        .unwrap();
+        state.set_world_collider(Some(Rc::new(collider)));
+
+        let init_centers = [0, 1].map(|idx| *state.get_player(idx).katamari.get_center());
+
+        // the two princes start facing away from each other, so push both katamaris backwards
+        for player_idx in 0..2 {
+            state
+                .get_player_mut(player_idx)
+                .input
+                .set_stick_state(0.0, -1.0, 0.0, -1.0, false, false, false, false);
+        }
+
+        for _ in 0..30 {
+            state.tick(1.0 / 30.0);
+        }
+
+        // both players are updated in vs mode
+        for (player_idx, init_center) in init_centers.iter().enumerate() {
+            let center = state.get_player(player_idx).katamari.get_center();
+            assert_ne!(center, init_center);
+        }
     }
 }
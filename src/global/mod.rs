@@ -1,4 +1,5 @@
 pub mod rng;
+pub mod tick_rate;
 
 use std::fmt::Display;
 
@@ -6,7 +7,7 @@ use gl_matrix::common::{Vec3, Vec4};
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 
-use self::rng::RngState;
+use self::{rng::RngState, tick_rate::TickRate};
 
 lazy_static! {
     static ref DEFAULT_GLOBAL_STATE: GlobalState = GlobalState::default();
@@ -18,6 +19,9 @@ pub struct GlobalState {
     /// RNG state, which contains two RNG values.
     pub rng: RngState,
 
+    /// The rate at which the simulation is ticked. Set by `SetTickRate`.
+    pub tick_rate: TickRate,
+
     /// Set to true after `MonoInitStart` is called.
     /// offset: 0xff0f0
     pub did_init_start: bool,
@@ -65,6 +69,10 @@ pub struct GlobalState {
     /// offset: 0x10eadb
     pub detaching_props_from_stuck_kat: bool,
 
+    /// The gravity applied to airborne props, which is set whenever a prop is sent on
+    /// a trajectory (from the stage's airborne prop gravity at the katamari's size).
+    pub airborne_prop_gravity: f32,
+
     /// Props with a diameter ratio to the player less
     /// than this value will be destroyed when they reach an alpha of 0.
     /// offset: 0x1339fc
@@ -141,10 +149,28 @@ impl Display for GlobalState {
 
 impl GlobalState {
     pub fn reset(&mut self) {
-        // reset everything in the global state except the rng
+        // reset everything in the global state except the rng and the tick rate
         let old_rng = self.rng;
+        let old_tick_rate = self.tick_rate;
         *self = *DEFAULT_GLOBAL_STATE;
         self.rng = old_rng;
+        self.tick_rate = old_tick_rate;
+    }
+
+    /// Reset the parts of the global state that change during a mission attempt to how they
+    /// were after `Init`, keeping the rng and everything set by unity or mission initialization.
+    pub fn reset_attempt(&mut self) {
+        self.updating_player = 0;
+        self.kat_diam_int_on_store_flag = 0;
+        self.freeze = false;
+        self.map_change_mode = false;
+        self.ticks = 0;
+        self.store_flag = false;
+        self.detaching_props_from_stuck_kat = false;
+        self.airborne_prop_gravity = 0.0;
+        self.game_time_ms = 0;
+        self.catch_count_b = 0;
+        self.map_loop_rate = 0.0;
     }
 
     pub fn set_gravity(&mut self, x: f32, y: f32, z: f32) {
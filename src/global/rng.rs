@@ -37,6 +37,15 @@ impl Default for RngState {
 }
 
 impl RngState {
+    /// An rng state with the values `rng1` and `rng2`, as if neither had been advanced yet.
+    pub fn new(rng1: u32, rng2: u32) -> Self {
+        Self {
+            rng1: Wrapping(rng1),
+            rng1_calls: 0,
+            rng2,
+        }
+    }
+
     pub fn get_rng1(&mut self) -> u32 {
         // save original rng1 state to return
         let result = self.rng1;
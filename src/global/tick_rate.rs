@@ -0,0 +1,186 @@
+use gl_matrix::common::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// The tick rate of the original simulation, which every frame-based duration, speed,
+/// and acceleration is tuned for.
+pub const BASE_TICKS_PER_SECOND: u16 = 30;
+
+/// The fastest tick rate the simulation can be run at. (This keeps the longest scaled
+/// durations within the range of the integer timers that count them down.)
+pub const MAX_TICKS_PER_SECOND: u16 = 240;
+
+/// A quantity measured in frames of the original 30 Hz simulation which can be converted
+/// to a number of ticks at another tick rate.
+pub trait FrameDuration: Copy {
+    fn scale(self, ticks_per_frame: f32) -> Self;
+}
+
+macro_rules! impl_frame_duration {
+    ($($t:ty),*) => {
+        $(
+            impl FrameDuration for $t {
+                fn scale(self, ticks_per_frame: f32) -> Self {
+                    (self as f32 * ticks_per_frame).round() as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_frame_duration!(u8, u16, u32, i16, i32);
+
+impl FrameDuration for f32 {
+    fn scale(self, ticks_per_frame: f32) -> Self {
+        self * ticks_per_frame
+    }
+}
+
+/// The rate at which the physics simulation is ticked.
+///
+/// Velocities are always expressed in distance per 30 Hz frame, as they are in the original
+/// simulation. At other tick rates, durations are converted to ticks with `duration`, and
+/// anything which is applied once per tick (a velocity moving a position, or an acceleration
+/// changing a velocity) is scaled down by `per_tick`. At the base tick rate both conversions
+/// return their input unchanged, so the 30 Hz simulation isn't affected at all.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TickRate {
+    ticks_per_second: u16,
+
+    /// The number of ticks in one 30 Hz frame.
+    ticks_per_frame: f32,
+
+    /// The number of 30 Hz frames in one tick.
+    frames_per_tick: f32,
+}
+
+impl Default for TickRate {
+    fn default() -> Self {
+        Self {
+            ticks_per_second: BASE_TICKS_PER_SECOND,
+            ticks_per_frame: 1.0,
+            frames_per_tick: 1.0,
+        }
+    }
+}
+
+impl TickRate {
+    /// Returns `None` if `ticks_per_second` is slower than the base tick rate or faster
+    /// than `MAX_TICKS_PER_SECOND`.
+    pub fn new(ticks_per_second: u16) -> Option<Self> {
+        if !(BASE_TICKS_PER_SECOND..=MAX_TICKS_PER_SECOND).contains(&ticks_per_second) {
+            return None;
+        }
+
+        let ticks_per_frame = ticks_per_second as f32 / BASE_TICKS_PER_SECOND as f32;
+
+        Some(Self {
+            ticks_per_second,
+            ticks_per_frame,
+            frames_per_tick: 1.0 / ticks_per_frame,
+        })
+    }
+
+    pub fn get_ticks_per_second(&self) -> u16 {
+        self.ticks_per_second
+    }
+
+    /// Returns `true` if this is the tick rate of the original simulation.
+    pub fn is_base(&self) -> bool {
+        self.ticks_per_second == BASE_TICKS_PER_SECOND
+    }
+
+    /// Convert a duration of `frames` 30 Hz frames to the number of ticks lasting as long.
+    pub fn duration<T: FrameDuration>(&self, frames: T) -> T {
+        if self.is_base() {
+            frames
+        } else {
+            frames.scale(self.ticks_per_frame)
+        }
+    }
+
+    /// Convert a change applied once per 30 Hz frame to the change applied once per tick.
+    pub fn per_tick(&self, per_frame: f32) -> f32 {
+        if self.is_base() {
+            per_frame
+        } else {
+            per_frame * self.frames_per_tick
+        }
+    }
+
+    /// Convert a multiplier applied once per 30 Hz frame (e.g. a drag) to the multiplier
+    /// applied once per tick, which compounds to the same multiplier over a frame.
+    pub fn per_tick_mult(&self, per_frame: f32) -> f32 {
+        if self.is_base() {
+            per_frame
+        } else {
+            per_frame.powf(self.frames_per_tick)
+        }
+    }
+
+    /// Convert the ratio of a remaining distance covered once per 30 Hz frame (e.g. an easing
+    /// speed, or a friction proportional to speed) to the ratio covered once per tick, so that
+    /// the distance left over compounds to the same amount over a frame.
+    pub fn per_tick_ratio(&self, per_frame: f32) -> f32 {
+        if self.is_base() {
+            per_frame
+        } else {
+            1.0 - self.per_tick_mult(1.0 - per_frame)
+        }
+    }
+
+    /// Convert a vector change applied once per 30 Hz frame to the change applied once per tick.
+    pub fn per_tick_vec3(&self, per_frame: &Vec3) -> Vec3 {
+        if self.is_base() {
+            *per_frame
+        } else {
+            [
+                per_frame[0] * self.frames_per_tick,
+                per_frame[1] * self.frames_per_tick,
+                per_frame[2] * self.frames_per_tick,
+            ]
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_base_tick_rate_is_identity() {
+        let base = TickRate::default();
+        assert!(base.is_base());
+        assert_eq!(TickRate::new(30), Some(base));
+
+        // bit-for-bit identity, even for values that don't survive a round trip through `f32`
+        assert_eq!(base.duration(u32::MAX), u32::MAX);
+        assert_eq!(base.duration(-7i16), -7);
+        assert_eq!(base.per_tick(0.1).to_bits(), 0.1f32.to_bits());
+        assert_eq!(base.per_tick_mult(0.9).to_bits(), 0.9f32.to_bits());
+        assert_eq!(base.per_tick_ratio(0.3).to_bits(), 0.3f32.to_bits());
+        assert_eq!(base.duration(0.3f32).to_bits(), 0.3f32.to_bits());
+    }
+
+    #[test]
+    fn test_scaled_tick_rate() {
+        assert_eq!(TickRate::new(15), None);
+        assert_eq!(TickRate::new(241), None);
+
+        let rate = TickRate::new(60).unwrap();
+        assert_eq!(rate.get_ticks_per_second(), 60);
+        assert_eq!(rate.duration(10u8), 20);
+        assert_eq!(rate.duration(0xf0u16), 0x1e0);
+        assert_eq!(rate.duration(70.0f32), 140.0);
+        assert_eq!(rate.per_tick(0.5), 0.25);
+        assert_eq!(rate.per_tick_vec3(&[1.0, -2.0, 0.5]), [0.5, -1.0, 0.25]);
+        assert_eq!(rate.per_tick_mult(0.25), 0.5);
+        assert_eq!(rate.per_tick_ratio(0.75), 0.5);
+
+        let rate = TickRate::new(45).unwrap();
+        assert_eq!(rate.duration(5u8), 8);
+        assert_eq!(rate.duration(0u16), 0);
+
+        let rate = TickRate::new(240).unwrap();
+        assert_eq!(rate.duration(0xfu8), 120);
+    }
+}
@@ -13,9 +13,11 @@ mod global;
 mod math;
 mod mission;
 mod mono_data;
+mod movie;
 mod player;
 mod props;
 mod savestate;
+mod trace;
 mod util;
 
 use backtrace::Backtrace;
@@ -25,22 +27,47 @@ use delegates::*;
 use gamestate::GameState;
 use gl_matrix::common::Mat4;
 
-use macros::temp_debug_log;
+use mono_data::{MonoData, MAX_MONO_DATA_LEN};
+use movie::{MovieEvent, MovieRecorder};
 use player::prince::OujiState;
 use props::{
     config::NamePropConfig,
     prop::{AddPropArgs, Prop},
 };
-use std::cell::RefCell;
+use std::{
+    cell::{Cell, RefCell},
+    ffi::CStr,
+    os::raw::c_char,
+    path::Path,
+};
 
 use crate::{
     delegates::has_delegates::HasDelegates,
     macros::{log, panic_log},
-    savestate::Hydrate,
+    savestate::{encode_savestate, SavestateError, Savestates},
 };
 
 thread_local! {
     static STATE: RefCell<GameState> = RefCell::new(GameState::new());
+
+    /// The savestate slots.
+    static SAVESTATES: RefCell<Savestates> = RefCell::new(Savestates::default());
+
+    /// The error code of the last savestate API call.
+    static SAVESTATE_ERROR: Cell<i32> = const { Cell::new(0) };
+
+    /// Records API calls to a movie file, if a recording was requested.
+    static MOVIE_RECORDER: RefCell<Option<MovieRecorder>> = const { RefCell::new(None) };
+}
+
+/// Helper function to record an API call to the active movie recording (if there is one).
+/// This needs to be called before the API call is applied to the game state.
+fn record_movie_event(event: MovieEvent) {
+    MOVIE_RECORDER.with(|recorder| {
+        if let Some(recorder) = recorder.borrow_mut().as_mut() {
+            STATE.with(|state| recorder.record(event, &state.borrow().global.rng));
+        }
+    })
 }
 
 /// Helper function to read the prop config for name index `name_idx` from the game state.
@@ -164,6 +191,21 @@ pub extern "C" fn SetKatamariSpeed(
     cam_y: f32,
     cam_z: f32,
 ) {
+    record_movie_event(MovieEvent::SetKatamariSpeed {
+        forw_s,
+        side_s,
+        back_s,
+        boost_s,
+        forw_a,
+        side_a,
+        back_a,
+        boost_a,
+        rot_s,
+        limit_y,
+        cam_x,
+        cam_y,
+        cam_z,
+    });
     STATE.with(|state| {
         state.borrow_mut().set_katamari_speed(
             forw_s, side_s, back_s, boost_s, forw_a, side_a, back_a, boost_a, rot_s, limit_y,
@@ -215,6 +257,7 @@ pub extern "C" fn GetKatamariMatrix(
 
 #[no_mangle]
 pub extern "C" fn SetGravity(x: f32, y: f32, z: f32) {
+    record_movie_event(MovieEvent::SetGravity { x, y, z });
     STATE.with(|state| {
         state.borrow_mut().global.set_gravity(x, y, z);
     })
@@ -243,11 +286,18 @@ pub extern "C" fn GetMapRollMatrix(
 
 #[no_mangle]
 pub extern "C" fn SetGameMode(mode: u32) {
+    record_movie_event(MovieEvent::SetGameMode { mode });
     STATE.with(|state| state.borrow_mut().mission_state.set_gamemode(mode as u8))
 }
 
 #[no_mangle]
 pub extern "C" fn SetKatamariTranslation(player_idx: i32, x: f32, y: f32, z: f32) {
+    record_movie_event(MovieEvent::SetKatamariTranslation {
+        player_idx,
+        x,
+        y,
+        z,
+    });
     STATE.with(|state| {
         state
             .borrow_mut()
@@ -278,6 +328,19 @@ pub extern "C" fn TakesCallbackMessageRequest(cb: MessageRequestDelegate) {
     })
 }
 
+/// Set whether the simulation requests king comments via the `MessageRequest` callback when
+/// comment props are collected (off by default, since unity plays the comments itself).
+#[no_mangle]
+pub extern "C" fn SetKingCommentRequests(enabled: bool) {
+    STATE.with(|state| {
+        state
+            .borrow_mut()
+            .delegates
+            .borrow_mut()
+            .request_king_comments = enabled;
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn TakesCallbackDoHit(cb: DoHitDelegate) {
     STATE.with(|state| {
@@ -385,6 +448,12 @@ pub extern "C" fn SetGameTime(
     freeze: i32,
     cam_eff_1P: i32,
 ) {
+    record_movie_event(MovieEvent::SetGameTime {
+        game_time_ms,
+        remain_time_ticks,
+        freeze,
+        cam_eff_1P,
+    });
     STATE.with(|state| {
         state
             .borrow_mut()
@@ -477,6 +546,17 @@ pub extern "C" fn SetStickState(
     l3_held: bool,
     r3_held: bool,
 ) {
+    record_movie_event(MovieEvent::SetStickState {
+        player_idx,
+        ls_x,
+        ls_y,
+        rs_x,
+        rs_y,
+        l3_down,
+        r3_down,
+        l3_held,
+        r3_held,
+    });
     STATE.with(|state| {
         state
             .borrow_mut()
@@ -499,6 +579,18 @@ pub extern "C" fn SetTriggerState(
     r2_held: u8,
     cross_click: bool,
 ) {
+    record_movie_event(MovieEvent::SetTriggerState {
+        player_idx,
+        l1_down,
+        l2_down,
+        r1_down,
+        r2_down,
+        l1_held,
+        r1_held,
+        l2_held,
+        r2_held,
+        cross_click,
+    });
     STATE.with(|state| {
         state
             .borrow_mut()
@@ -608,11 +700,13 @@ pub extern "C" fn MonoGetVolume(ctrl_idx: i32, volume: &mut f32, collect_diam: &
 
 #[no_mangle]
 pub extern "C" fn SetPropStopFlag(ctrl_idx: i32, flag: i32) {
+    record_movie_event(MovieEvent::SetPropStopFlag { ctrl_idx, flag });
     with_prop_mut(ctrl_idx, |prop| prop.set_disabled(flag));
 }
 
 #[no_mangle]
 pub extern "C" fn SetGameStart(player_idx: i32, area: i32) {
+    record_movie_event(MovieEvent::SetGameStart { player_idx, area });
     STATE.with(|state| {
         state
             .borrow_mut()
@@ -622,6 +716,7 @@ pub extern "C" fn SetGameStart(player_idx: i32, area: i32) {
 
 #[no_mangle]
 pub extern "C" fn SetAreaChange(player_idx: i32) {
+    record_movie_event(MovieEvent::SetAreaChange { player_idx });
     STATE.with(|state| {
         state.borrow_mut().set_area_change(player_idx as usize);
     })
@@ -629,6 +724,7 @@ pub extern "C" fn SetAreaChange(player_idx: i32) {
 
 #[no_mangle]
 pub extern "C" fn SetMapChangeMode(map_change_mode: i32) {
+    record_movie_event(MovieEvent::SetMapChangeMode { map_change_mode });
     STATE.with(|state| {
         state.borrow_mut().set_map_change_mode(map_change_mode);
     })
@@ -673,9 +769,14 @@ pub unsafe extern "C" fn GetPropAttached(out: *mut u8) -> i32 {
     STATE.with(|state| state.borrow().get_props_attach_status(out))
 }
 
+/// The original `MonoInitStart` API function, which doesn't pass the length of the mono
+/// data. Since the mono data can't be read safely without its length, this only logs an
+/// error: unity has to call `MonoInitStartChecked` instead.
+/// # Safety
+/// Doesn't read `mono_data`.
 #[no_mangle]
 pub unsafe extern "C" fn MonoInitStart(
-    mono_data: *const u8,
+    _mono_data: *const u8,
     mission: i32,
     area: i32,
     stage: i32,
@@ -697,21 +798,119 @@ pub unsafe extern "C" fn MonoInitStart(
         end_flag
     );
 
+    log!("error: the mono data length is unknown (call `MonoInitStartChecked` instead)");
+}
+
+/// `MonoInitStart`, for callers that know the length `mono_data_len` of the mono data.
+/// Returns `false` (without changing the game state) if the mono data can't be parsed,
+/// or if it's longer than `MAX_MONO_DATA_LEN`.
+/// # Safety
+/// `mono_data` has to point to at least `mono_data_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn MonoInitStartChecked(
+    mono_data: *const u8,
+    mono_data_len: i32,
+    mission: i32,
+    area: i32,
+    stage: i32,
+    kadai_flag: i32,
+    clear_flag: i32,
+    end_flag: i32,
+) -> bool {
+    std::panic::set_hook(Box::new(|panic_info| {
+        log!("panic: {:?}", panic_info);
+        log!("trace: {:?}", Backtrace::new());
+    }));
+    log!(
+        "MonoInitStartChecked({}, {}, {}, {}, {}, {}, {})",
+        mono_data_len,
+        mission,
+        area,
+        stage,
+        kadai_flag,
+        clear_flag,
+        end_flag
+    );
+
+    if mono_data.is_null() || mono_data_len <= 0 {
+        log!("error: missing mono data");
+        return false;
+    }
+
+    if mono_data_len as usize > MAX_MONO_DATA_LEN {
+        log!("error: mono data is too long ({mono_data_len:#x} bytes)");
+        return false;
+    }
+
+    mono_init_start(
+        std::slice::from_raw_parts(mono_data, mono_data_len as usize),
+        mission,
+        area,
+        stage,
+        kadai_flag,
+        clear_flag,
+        end_flag,
+    )
+}
+
+/// Start initializing a mission from the mono data `mono_data`, if it can be parsed.
+/// The game state is only reset once the mono data is known to be valid.
+fn mono_init_start(
+    mono_data: &[u8],
+    mission: i32,
+    area: i32,
+    stage: i32,
+    kadai_flag: i32,
+    clear_flag: i32,
+    end_flag: i32,
+) -> bool {
+    let mono_data = match MonoData::parse(mono_data) {
+        Ok(mono_data) => mono_data,
+        Err(e) => {
+            log!("error: {e}");
+            return false;
+        }
+    };
+
+    // reload the motion data file on every `MonoInitStart`, so it can be edited between attempts
+    let motion_data_path = Path::new(props::motion::data::MOTION_DATA_PATH);
+    let motion_data =
+        props::motion::data::load_motion_data_file(motion_data_path).unwrap_or_else(|e| {
+            log!("error reading motion data: {e}");
+            None
+        });
+
+    // likewise for the random layout file, so practice layouts can be changed between attempts
+    let random_layout_path = Path::new(props::random::layout::RANDOM_LAYOUT_PATH);
+    let random_layout = props::random::layout::load_random_layout_file(random_layout_path)
+        .unwrap_or_else(|e| {
+            log!("error reading random layout: {e}");
+            Default::default()
+        });
+
+    // the loaded files are recorded too, since they change how the mission plays out
+    record_movie_event(MovieEvent::MonoInitStart {
+        mission,
+        area,
+        stage,
+        kadai_flag,
+        clear_flag,
+        end_flag,
+        motion_data,
+        random_layout,
+    });
+
     // since this is the first initialization API call made by unity (before `Init`, go figure)
     // this seems like a reasonable place to reset the game state between attempts
     STATE.with(|state| state.borrow_mut().reset());
 
     STATE.with(|state| {
-        state.borrow_mut().mono_init_start(
-            mono_data,
-            mission as u8,
-            area as u8,
-            stage as u8,
-            kadai_flag != 0,
-            clear_flag != 0,
-            end_flag != 0,
-        );
-    })
+        state
+            .borrow_mut()
+            .mono_init_start_parsed(mono_data, mission as u8, area as u8, stage as u8)
+    });
+
+    true
 }
 
 #[no_mangle]
@@ -770,11 +969,16 @@ pub extern "C" fn MonoInitAddProp(
 
     args.transform_coords_to_sim();
 
+    record_movie_event(MovieEvent::MonoInitAddProp(args.clone()));
     STATE.with(|state| state.borrow_mut().add_prop(&args))
 }
 
 #[no_mangle]
 pub extern "C" fn MonoInitAddPropSetParent(ctrl_idx: i32, parent_ctrl_idx: i32) {
+    record_movie_event(MovieEvent::MonoInitAddPropSetParent {
+        ctrl_idx,
+        parent_ctrl_idx,
+    });
     STATE.with(|state| {
         state
             .borrow_mut()
@@ -784,6 +988,7 @@ pub extern "C" fn MonoInitAddPropSetParent(ctrl_idx: i32, parent_ctrl_idx: i32)
 
 #[no_mangle]
 pub extern "C" fn MonoInitEnd() {
+    record_movie_event(MovieEvent::MonoInitEnd);
     STATE.with(|state| {
         state.borrow_mut().mono_init_end();
     });
@@ -818,6 +1023,7 @@ pub extern "C" fn MonoGetHitOffsetGround(ctrl_idx: i32) -> f32 {
 
 #[no_mangle]
 pub extern "C" fn SetCameraMode(player_idx: i32, mode: i32) {
+    record_movie_event(MovieEvent::SetCameraMode { player_idx, mode });
     STATE.with(|state| {
         state
             .borrow_mut()
@@ -828,6 +1034,7 @@ pub extern "C" fn SetCameraMode(player_idx: i32, mode: i32) {
 
 #[no_mangle]
 pub extern "C" fn SetCameraCheckScaleUp(player_idx: i32, flag: i32) {
+    record_movie_event(MovieEvent::SetCameraCheckScaleUp { player_idx, flag });
     STATE.with(|state| {
         state
             .borrow_mut()
@@ -839,6 +1046,11 @@ pub extern "C" fn SetCameraCheckScaleUp(player_idx: i32, flag: i32) {
 
 #[no_mangle]
 pub extern "C" fn SetShootingMode(player_idx: i32, fg: i32, reset: i32) {
+    record_movie_event(MovieEvent::SetShootingMode {
+        player_idx,
+        fg,
+        reset,
+    });
     STATE.with(|state| {
         state
             .borrow_mut()
@@ -849,6 +1061,7 @@ pub extern "C" fn SetShootingMode(player_idx: i32, fg: i32, reset: i32) {
 
 #[no_mangle]
 pub extern "C" fn SetPreclearMode(mode: i32) {
+    record_movie_event(MovieEvent::SetPreclearMode { mode });
     STATE.with(|state| {
         state
             .borrow_mut()
@@ -861,6 +1074,7 @@ pub extern "C" fn SetPreclearMode(mode: i32) {
 
 #[no_mangle]
 pub extern "C" fn SetTutorialA(page: i32, page_step: i32) {
+    record_movie_event(MovieEvent::SetTutorialA { page, page_step });
     STATE.with(|state| {
         state
             .borrow_mut()
@@ -872,22 +1086,33 @@ pub extern "C" fn SetTutorialA(page: i32, page_step: i32) {
 
 #[no_mangle]
 pub extern "C" fn SetStoreFlag(flag: i32) {
+    record_movie_event(MovieEvent::SetStoreFlag { flag });
     STATE.with(|state| state.borrow_mut().set_store_flag(flag != 0));
 }
 
 #[no_mangle]
 pub extern "C" fn ChangeNextArea() {
+    record_movie_event(MovieEvent::ChangeNextArea);
     STATE.with(|state| state.borrow_mut().change_next_area());
 }
 
 #[no_mangle]
 pub extern "C" fn Tick(delta: f32) {
+    record_movie_event(MovieEvent::Tick { delta });
     STATE.with(|state| state.borrow_mut().tick(delta));
 }
 
 #[no_mangle]
 pub extern "C" fn Init(player_idx: i32, override_init_size: f32, mission: i32) {
     log!("Init({}, {}, {})", player_idx, override_init_size, mission);
+    record_movie_event(MovieEvent::Init {
+        player_idx,
+        override_init_size,
+        mission,
+    });
+    if let Err(e) = debug::load_debug_config_file(Path::new(debug::DEBUG_CONFIG_PATH)) {
+        log!("error reading debug config: {e}");
+    }
     STATE.with(|state| {
         state
             .borrow_mut()
@@ -895,6 +1120,46 @@ pub extern "C" fn Init(player_idx: i32, override_init_size: f32, mission: i32) {
     });
 }
 
+/// Resets the mission to how it was after `Init` without reloading it, so that it can be retried
+/// quickly (see `GameState::reset_attempt`).
+/// Returns `false` if the mission hasn't been initialized yet.
+#[no_mangle]
+pub extern "C" fn ResetAttempt() -> bool {
+    log!("ResetAttempt()");
+    record_movie_event(MovieEvent::ResetAttempt);
+    STATE.with(|state| state.borrow_mut().reset_attempt())
+}
+
+/// Set the rate at which the simulation is ticked, in ticks per second (30 by default).
+/// Returns `false` if the rate isn't supported (see `GameState::set_tick_rate`).
+#[no_mangle]
+pub extern "C" fn SetTickRate(ticks_per_second: i32) -> bool {
+    log!("SetTickRate({})", ticks_per_second);
+    record_movie_event(MovieEvent::SetTickRate { ticks_per_second });
+    let Ok(ticks_per_second) = u16::try_from(ticks_per_second) else {
+        return false;
+    };
+    STATE.with(|state| state.borrow_mut().set_tick_rate(ticks_per_second))
+}
+
+/// Set the debug flag `name` (a field of `DebugConfig`, e.g. `"log_tick"`) to `value`.
+/// Returns `false` if `name` isn't a debug flag.
+/// # Safety
+/// `name` has to be null or point to a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn SetDebugFlag(name: *const c_char, value: bool) -> bool {
+    if name.is_null() {
+        return false;
+    }
+
+    let Ok(name) = CStr::from_ptr(name).to_str() else {
+        return false;
+    };
+
+    log!("SetDebugFlag({name}, {value})");
+    debug::set_debug_flag(name, value)
+}
+
 #[no_mangle]
 pub extern "C" fn TakesCallbackDebugDraw(cb: DebugDrawDelegate, unity_data_ptr: usize) {
     STATE.with(|state| {
@@ -904,49 +1169,137 @@ pub extern "C" fn TakesCallbackDebugDraw(cb: DebugDrawDelegate, unity_data_ptr:
     });
 }
 
+/// Returns a checksum of the gameplay-relevant parts of the game state (see `trace::FrameTrace`).
 #[no_mangle]
-pub extern "C" fn RequestSaveState(slot: i32) -> bool {
-    STATE.with(|state| {
-        temp_debug_log!("requesting save state (slot {slot})");
-        let s = serde_json::to_string_pretty(state).unwrap();
-        std::fs::write("test-state.json", &s).unwrap();
+pub extern "C" fn GetStateChecksum() -> u64 {
+    STATE.with(|state| state.borrow().checksum())
+}
 
-        true
+/// Helper function to run a savestate operation, logging its error (if there is one) and
+/// keeping its error code for `GetSaveStateError`.
+fn with_savestates<F>(cb: F) -> bool
+where
+    F: FnOnce(&mut Savestates) -> Result<(), SavestateError>,
+{
+    SAVESTATES.with(|savestates| {
+        let result = cb(&mut savestates.borrow_mut());
+        if let Err(e) = &result {
+            log!("{e}");
+        }
+
+        SAVESTATE_ERROR.with(|error| error.set(result.as_ref().map_or_else(|e| e.code(), |_| 0)));
+        result.is_ok()
     })
 }
 
+/// Saves the current game state to savestate slot `slot`.
+/// Returns `false` if the state couldn't be saved (see `GetSaveStateError`).
 #[no_mangle]
-pub extern "C" fn RequestLoadState(_slot: i32) -> bool {
-    let path = "test-state.json";
-    if let Ok(data) = std::fs::read_to_string(std::path::Path::new(path)) {
-        temp_debug_log!("read file");
-        match serde_json::from_str::<GameState>(&data) {
-            Ok(mut new_state) => {
-                temp_debug_log!("read gamestate:");
-                let kat_pos = new_state.players[0].katamari.get_center();
-                temp_debug_log!("  read pos: {:?}", kat_pos);
-                STATE.with(|old_state| {
-                    temp_debug_log!(
-                        "  old pos: {:?}",
-                        old_state.borrow().players[0].katamari.get_center()
-                    );
-                    new_state.hydrate(old_state);
-                    old_state.replace(new_state);
-                    temp_debug_log!(
-                        "  after replace pos: {:?}",
-                        old_state.borrow().players[0].katamari.get_center()
-                    );
+pub extern "C" fn RequestSaveState(slot: i32) -> bool {
+    with_savestates(|savestates| STATE.with(|state| savestates.save(slot, &state.borrow())))
+}
+
+/// Replaces the current game state with the savestate in slot `slot`.
+/// Returns `false` if the state couldn't be loaded (see `GetSaveStateError`).
+#[no_mangle]
+pub extern "C" fn RequestLoadState(slot: i32) -> bool {
+    let loaded = with_savestates(|savestates| STATE.with(|state| savestates.load(slot, state)));
+
+    // movies record the loaded state itself, since the slot might not exist when replaying
+    if loaded {
+        MOVIE_RECORDER.with(|recorder| {
+            if let Some(recorder) = recorder.borrow_mut().as_mut() {
+                STATE.with(|state| {
+                    let state = state.borrow();
+                    match encode_savestate(&state) {
+                        Ok(savestate) => {
+                            recorder.record(MovieEvent::LoadState { savestate }, &state.global.rng)
+                        }
+                        Err(e) => {
+                            log!("error recording loaded state: {e}");
+                        }
+                    }
                 });
             }
-            Err(e) => {
-                temp_debug_log!("error reading gamestate: {e:?}");
-            }
-        }
+        });
     }
 
+    loaded
+}
+
+/// Returns `true` if savestate slot `slot` has a savestate.
+#[no_mangle]
+pub extern "C" fn HasSaveState(slot: i32) -> bool {
+    SAVESTATES.with(|savestates| savestates.borrow().has_savestate(slot))
+}
+
+/// Sets the directory that savestates are written to (and loaded from) in addition to memory,
+/// where `dir` is a null-terminated UTF-8 string. If `dir` is null, savestates are only kept
+/// in memory.
+/// Returns `false` if `dir` isn't valid.
+/// # Safety
+/// `dir` has to be null or point to a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn SetSaveStateDir(dir: *const c_char) -> bool {
+    let dir = if dir.is_null() {
+        None
+    } else {
+        match CStr::from_ptr(dir).to_str() {
+            Ok(dir) => Some(Path::new(dir)),
+            Err(_) => return false,
+        }
+    };
+
+    SAVESTATES.with(|savestates| savestates.borrow_mut().set_dir(dir));
     true
 }
 
+/// Returns the error code of the last savestate API call (0 if it succeeded).
+/// See `SavestateError::code` for the meaning of each code.
+#[no_mangle]
+pub extern "C" fn GetSaveStateError() -> i32 {
+    SAVESTATE_ERROR.with(|error| error.get())
+}
+
+/// Start recording API calls to a movie file at `path` (a null-terminated UTF-8 string).
+/// The movie begins at the next `MonoInitStart` call, and is written when
+/// `StopMovieRecording` is called.
+/// Returns `false` if `path` isn't valid.
+/// # Safety
+/// `path` has to be null or point to a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn StartMovieRecording(path: *const c_char) -> bool {
+    if path.is_null() {
+        return false;
+    }
+
+    match CStr::from_ptr(path).to_str() {
+        Ok(path) => {
+            log!("recording movie to {path}");
+            MOVIE_RECORDER
+                .with(|recorder| recorder.replace(Some(MovieRecorder::new(Path::new(path)))));
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Stop recording API calls and write the recorded movie to its file.
+/// Returns `false` if there was no recording or the movie couldn't be written.
+#[no_mangle]
+pub extern "C" fn StopMovieRecording() -> bool {
+    match MOVIE_RECORDER.with(|recorder| recorder.take()) {
+        Some(recorder) => match recorder.finish() {
+            Ok(written) => written,
+            Err(e) => {
+                log!("error writing movie: {e}");
+                false
+            }
+        },
+        None => false,
+    }
+}
+
 /// This seems to be what simulates a single object in the collection UI and the names UI.
 /// Not a priority.
 #[no_mangle]
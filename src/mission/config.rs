@@ -296,7 +296,9 @@ impl MissionConfig {
     /// Compute the mission's volume penalty for a katamari of diameter `diam_cm`.
     /// offset: 0x1bd50 (lines 349-375)
     pub fn get_vol_penalty(&self, diam_cm: f32) -> f32 {
-        if let Some(ctrl_pts) = &self.vol_penalty_ctrl_pts {
+        // (vs missions have an empty list of control points)
+        let ctrl_pts = self.vol_penalty_ctrl_pts.as_ref();
+        if let Some(ctrl_pts) = ctrl_pts.filter(|ctrl_pts| !ctrl_pts.is_empty()) {
             for i in 0..ctrl_pts.len() - 1 {
                 let next = &ctrl_pts[i + 1];
                 if next.diam_cm > diam_cm {
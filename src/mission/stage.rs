@@ -52,6 +52,7 @@ impl From<u8> for Stage {
 }
 use crate::{
     constants::NUM_STAGES,
+    global::tick_rate::TickRate,
     macros::{inv_lerp_clamp, lerp, read_f32},
 };
 use gl_matrix::common::Vec3;
@@ -91,13 +92,13 @@ impl StageFlipParams {
     /// The width of an entry in the `StageFlipParams` table.
     pub const WIDTH: usize = 0x10;
 
-    /// Compute the flip duration from these params when the katamari has
-    /// diameter `diam_cm`.
-    pub fn get_duration(&self, diam_cm: f32) -> u32 {
+    /// Compute the flip duration (in ticks at `tick_rate`) from these params when
+    /// the katamari has diameter `diam_cm`.
+    pub fn get_duration(&self, diam_cm: f32, tick_rate: &TickRate) -> u32 {
         let t = inv_lerp_clamp!(diam_cm, self.min_diam_cm, self.max_diam_cm);
 
         // note that we're implicitly taking the floor here, by casting to `u32`
-        lerp!(t, self.min_flip_ticks, self.max_flip_ticks) as u32
+        tick_rate.duration(lerp!(t, self.min_flip_ticks, self.max_flip_ticks) as u32)
     }
 }
 
@@ -366,14 +367,15 @@ pub struct StageConfig {
 }
 
 impl StageConfig {
-    /// Get this stage's flip duration as a function of the katamari's current diameter (in cm).
-    pub fn get_flip_duration(&self, diam_cm: f32) -> u32 {
+    /// Get this stage's flip duration (in ticks at `tick_rate`) as a function of the
+    /// katamari's current diameter (in cm).
+    pub fn get_flip_duration(&self, diam_cm: f32, tick_rate: &TickRate) -> u32 {
         self.flip_params
             .as_ref()
             .unwrap_or_else(|| {
                 panic_log!("error reading stage flip params");
             })
-            .get_duration(diam_cm)
+            .get_duration(diam_cm, tick_rate)
     }
 
     /// Returns `true` if this stage has royal warp destinations defined.
@@ -521,3 +523,25 @@ lazy_static! {
         configs
     };
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_flip_duration_scales_with_tick_rate() {
+        let params = StageFlipParams {
+            min_flip_ticks: 10.0,
+            min_diam_cm: 10.0,
+            max_flip_ticks: 30.0,
+            max_diam_cm: 30.0,
+        };
+
+        assert_eq!(params.get_duration(15.5, &TickRate::default()), 15);
+        assert_eq!(params.get_duration(15.5, &TickRate::new(60).unwrap()), 30);
+        assert_eq!(
+            params.get_duration(100.0, &TickRate::new(120).unwrap()),
+            120
+        );
+    }
+}
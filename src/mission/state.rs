@@ -46,6 +46,12 @@ pub struct MissionState {
     /// offset: 0xff109
     pub area: u8,
 
+    /// The area that the mission started in (i.e. the area passed to `MonoInitStart`).
+    pub start_area: u8,
+
+    /// The `stage_area` that the mission started in.
+    pub start_stage_area: u8,
+
     /// If true, the current mission is in VS mode.
     /// offset: 0xff0f1
     pub is_vs_mode: bool,
@@ -76,12 +82,25 @@ impl MissionState {
         self.mission = mission.into();
         self.stage = stage.into();
         self.area = area;
+        self.start_area = area;
+        self.start_stage_area = self.stage_area;
 
         // initialize the mission and stage configs
         MissionConfig::get(&mut self.mission_config, mission);
         StageConfig::get(&mut self.stage_config, stage);
     }
 
+    /// Reset the parts of the mission state that change during a mission attempt, moving
+    /// back to the area that the mission started in.
+    pub fn reset_attempt(&mut self) {
+        self.stage_area = self.start_stage_area;
+        self.area = self.start_area;
+
+        self.ending = EndingState::default();
+        self.tutorial = TutorialState::default();
+        self.vsmode = VsModeState::default();
+    }
+
     /// Set the tutorial move `tut_move` as being held.
     pub fn set_tutorial_move_held(&mut self, tut_move: TutorialMove) {
         if self.is_tutorial() {
@@ -1,8 +1,27 @@
 use serde::{Deserialize, Serialize};
 
+use crate::gamestate::GameState;
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct VsModeState {
     /// (??) Some kind of timer
     /// offset: 0x10bf10
     pub timer_0x10bf10: i16,
 }
+
+impl GameState {
+    /// Update the interactions between the two players' katamaris in vs mode, after both
+    /// players have been updated.
+    pub fn update_vs_mode(&mut self) {
+        let (players0, players1) = self.players.split_at_mut(1);
+        let kat0 = &mut players0[0].katamari;
+        let kat1 = &mut players1[0].katamari;
+
+        // the smaller katamari takes its params from the bigger one
+        kat0.set_vs_opponent_diam(kat1.get_diam_cm());
+        kat1.set_vs_opponent_diam(kat0.get_diam_cm());
+
+        // TODO_VS: the rest of `vsmode_update()`. katamari-katamari collisions and attacks
+        // aren't ported yet (see the `TODO_VS` markers in the katamari's collision and velocity)
+    }
+}
@@ -1,14 +1,163 @@
-use std::rc::Rc;
+use std::{fmt::Display, rc::Rc};
 
-use gl_matrix::common::Vec3;
+use gl_matrix::{common::Vec3, mat4, vec3};
 
 use crate::{
     collision::{aabb::Aabb, mesh::Mesh},
     constants::NUM_NAME_PROPS,
-    macros::{max, md_follow_offset, md_read, min},
-    props::config::NamePropConfig,
+    macros::{max, min},
+    props::{config::NamePropConfig, subobject::PropSubobject},
 };
 
+/// A table in mono data, used to name the part of the mono data that failed to parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonoDataTable {
+    /// The table of offsets at the start of the mono data.
+    Header,
+    ZoneMesh,
+    /// The table of offsets to the data of the prop with the given name index.
+    PropOffsets(u16),
+    PropAabbs(u16),
+    PropCollisionMesh(u16),
+    PropVaultPoints(u16),
+}
+
+impl Display for MonoDataTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MonoDataTable::Header => write!(f, "header"),
+            MonoDataTable::ZoneMesh => write!(f, "zone mesh"),
+            MonoDataTable::PropOffsets(name_idx) => write!(f, "prop {name_idx} offsets"),
+            MonoDataTable::PropAabbs(name_idx) => write!(f, "prop {name_idx} AABBs"),
+            MonoDataTable::PropCollisionMesh(name_idx) => {
+                write!(f, "prop {name_idx} collision mesh")
+            }
+            MonoDataTable::PropVaultPoints(name_idx) => write!(f, "prop {name_idx} vault points"),
+        }
+    }
+}
+
+/// An error encountered while parsing mono data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonoDataError {
+    /// Reading `size` bytes at `offset` (while parsing `table`) would go past the end of the
+    /// mono data, which is `len` bytes long.
+    OutOfBounds {
+        table: MonoDataTable,
+        offset: usize,
+        size: usize,
+        len: usize,
+    },
+
+    /// The offset read at `offset` (while parsing `table`) points to `target`, which is
+    /// past the end of the mono data.
+    BadOffset {
+        table: MonoDataTable,
+        offset: usize,
+        target: usize,
+        len: usize,
+    },
+}
+
+impl Display for MonoDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MonoDataError::OutOfBounds {
+                table,
+                offset,
+                size,
+                len,
+            } => write!(
+                f,
+                "mono data {table}: reading {size} bytes at {offset:#x} goes past the end of the data ({len:#x} bytes)"
+            ),
+            MonoDataError::BadOffset {
+                table,
+                offset,
+                target,
+                len,
+            } => write!(
+                f,
+                "mono data {table}: offset at {offset:#x} points to {target:#x}, past the end of the data ({len:#x} bytes)"
+            ),
+        }
+    }
+}
+
+/// Reads little-endian values from mono data, checking that every read is in bounds.
+#[derive(Debug, Clone, Copy)]
+pub struct MonoDataReader<'a> {
+    data: &'a [u8],
+
+    /// The table currently being parsed, which is named in errors.
+    table: MonoDataTable,
+}
+
+impl<'a> MonoDataReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            table: MonoDataTable::Header,
+        }
+    }
+
+    /// A copy of this reader that names `table` in its errors.
+    pub fn with_table(&self, table: MonoDataTable) -> Self {
+        Self {
+            data: self.data,
+            table,
+        }
+    }
+
+    pub fn read_bytes<const N: usize>(&self, offset: usize) -> Result<[u8; N], MonoDataError> {
+        offset
+            .checked_add(N)
+            .and_then(|end| self.data.get(offset..end))
+            .map(|bytes| bytes.try_into().unwrap())
+            .ok_or(MonoDataError::OutOfBounds {
+                table: self.table,
+                offset,
+                size: N,
+                len: self.data.len(),
+            })
+    }
+
+    pub fn read_u8(&self, offset: usize) -> Result<u8, MonoDataError> {
+        self.read_bytes::<1>(offset).map(|bytes| bytes[0])
+    }
+
+    pub fn read_u32(&self, offset: usize) -> Result<u32, MonoDataError> {
+        self.read_bytes(offset).map(u32::from_le_bytes)
+    }
+
+    pub fn read_f32(&self, offset: usize) -> Result<f32, MonoDataError> {
+        self.read_bytes(offset).map(f32::from_le_bytes)
+    }
+
+    pub fn read_vec3(&self, offset: usize) -> Result<Vec3, MonoDataError> {
+        Ok([
+            self.read_f32(offset)?,
+            self.read_f32(offset + 4)?,
+            self.read_f32(offset + 8)?,
+        ])
+    }
+
+    /// Read the offset at `offset`, checking that it points inside the mono data.
+    pub fn read_offset(&self, offset: usize) -> Result<usize, MonoDataError> {
+        let target = self.read_u32(offset)? as usize;
+        if target >= self.data.len() {
+            return Err(MonoDataError::BadOffset {
+                table: self.table,
+                offset,
+                target,
+                len: self.data.len(),
+            });
+        }
+
+        Ok(target)
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct PropAabbs {
     /// The first element is the AABB of the prop.
@@ -17,20 +166,21 @@ pub struct PropAabbs {
 }
 
 impl PropAabbs {
-    /// Parses the AABBs of the prop and its subobjects (if it has any) from a raw pointer.
-    unsafe fn from_raw(mono_data: *const u8) -> PropAabbs {
-        let num_aabbs = md_read!(mono_data, u8, 0);
+    /// Parses the AABBs of the prop and its subobjects (if it has any) from the mono data
+    /// at `offset`.
+    fn parse(md: &MonoDataReader, offset: usize) -> Result<PropAabbs, MonoDataError> {
+        let num_aabbs = md.read_u8(offset)?;
         let mut aabbs = vec![];
 
-        for i in 0..num_aabbs as isize {
-            let aabb_offset = md_read!(mono_data, u32, i * 4 + 4) as isize;
-            let min = md_read!(mono_data, Vec3, aabb_offset);
-            let max = md_read!(mono_data, Vec3, aabb_offset + 0x10);
+        for i in 0..num_aabbs as usize {
+            let aabb_offset = offset + md.read_u32(offset + i * 4 + 4)? as usize;
+            let min = md.read_vec3(aabb_offset)?;
+            let max = md.read_vec3(aabb_offset + 0x10)?;
 
             aabbs.push(Aabb { min, max });
         }
 
-        PropAabbs { aabbs }
+        Ok(PropAabbs { aabbs })
     }
 
     /// Get the prop's AABB.
@@ -43,20 +193,47 @@ impl PropAabbs {
         self.aabbs.get(1 + subobj_idx as usize)
     }
 
-    /// Compute the "root" AABB which encloses both the prop and all of its subobjects.
-    pub fn get_root_aabb(&self) -> Aabb {
-        // TODO_SUBOBJECT: rotate the AABB sector of each subobject separately
-        static SUBOBJECT_ROT: Vec3 = [0.0; 3];
+    /// Iterate over the AABBs of the prop's subobjects.
+    pub fn iter_subobjects(&self) -> impl Iterator<Item = &Aabb> {
+        self.aabbs.iter().skip(1)
+    }
 
+    /// Iterate over the prop's AABB followed by the AABBs of its subobjects.
+    pub fn iter(&self) -> impl Iterator<Item = &Aabb> {
+        self.aabbs.iter()
+    }
+
+    /// Compute the "root" AABB which encloses both the prop and all of its subobjects, where
+    /// each subobject's AABB is rotated by the Euler angles of the corresponding subobject.
+    pub fn get_root_aabb(&self, subobjects: &[PropSubobject]) -> Aabb {
         let mut root_max = [0.0; 3];
         let mut root_min = [0.0; 3];
-        for aabb in self.aabbs.iter() {
-            root_min[0] = min!(root_min[0], aabb.min[0]);
-            root_min[1] = min!(root_min[1], aabb.min[1]);
-            root_min[2] = min!(root_min[2], aabb.min[2]);
-            root_max[0] = max!(root_max[0], aabb.max[0]);
-            root_max[1] = max!(root_max[1], aabb.max[1]);
-            root_max[2] = max!(root_max[2], aabb.max[2]);
+        let mut extend_root = |point: &Vec3| {
+            root_min[0] = min!(root_min[0], point[0]);
+            root_min[1] = min!(root_min[1], point[1]);
+            root_min[2] = min!(root_min[2], point[2]);
+            root_max[0] = max!(root_max[0], point[0]);
+            root_max[1] = max!(root_max[1], point[1]);
+            root_max[2] = max!(root_max[2], point[2]);
+        };
+
+        let prop_aabb = self.get_prop_aabb();
+        extend_root(&prop_aabb.min);
+        extend_root(&prop_aabb.max);
+
+        for (aabb, subobject) in self.iter_subobjects().zip(subobjects.iter()) {
+            // (??) the rotation order is assumed to match the one used for prop transforms
+            let mut temp1 = mat4::create();
+            let mut temp2 = mat4::create();
+            mat4::rotate_z(&mut temp2, &temp1, subobject.rot_vec[2]);
+            mat4::rotate_x(&mut temp1, &temp2, subobject.rot_vec[0]);
+            mat4::rotate_y(&mut temp2, &temp1, subobject.rot_vec[1]);
+
+            let mut point = vec3::create();
+            for vertex in aabb.compute_vertices() {
+                vec3::transform_mat4(&mut point, &vertex, &temp2);
+                extend_root(&point);
+            }
         }
 
         Aabb {
@@ -66,103 +243,215 @@ impl PropAabbs {
     }
 }
 
-pub type MonoDataPtr = Option<usize>;
+/// The longest mono data that `MonoInitStartChecked` will read. The largest mono data of
+/// the game's missions is about 4 MiB.
+pub const MAX_MONO_DATA_LEN: usize = 0x100_0000;
+
+/// An offset into mono data.
+pub type MonoDataOffset = Option<usize>;
 
-const NUM_MONO_DATA_PROP_PTRS: usize = 11;
+const NUM_MONO_DATA_PROP_OFFSETS: usize = 11;
 
 /// The sequence of bytes in the monodata that indicates a null value.
 /// Thus, any offset pointing to this sequence can be seen as a null pointer.
 const NIL: u64 = 0x206c696e204c494e;
 
-/// Pointers into mono data for one type of prop.
+/// Offsets into mono data for one type of prop.
 #[derive(Debug, Default)]
 pub struct PropMonoData {
-    pub ptrs: [MonoDataPtr; NUM_MONO_DATA_PROP_PTRS],
+    pub offsets: [MonoDataOffset; NUM_MONO_DATA_PROP_OFFSETS],
     pub aabbs: Option<Rc<PropAabbs>>,
     pub collision_mesh: Option<Rc<Mesh>>,
     pub vault_points: Option<Vec<Vec3>>,
 }
 
 impl PropMonoData {
-    pub unsafe fn new(mono_data: *const u8, name_idx: usize) -> PropMonoData {
-        let mut ptrs: [MonoDataPtr; NUM_MONO_DATA_PROP_PTRS] = [None; NUM_MONO_DATA_PROP_PTRS];
+    pub fn parse(md: &MonoDataReader, name_idx: usize) -> Result<PropMonoData, MonoDataError> {
+        let mut offsets: [MonoDataOffset; NUM_MONO_DATA_PROP_OFFSETS] =
+            [None; NUM_MONO_DATA_PROP_OFFSETS];
+        let name_idx_u16 = name_idx as u16;
 
-        for (ptr_idx, ptr) in ptrs.iter_mut().enumerate() {
-            let offset: i32 = 0x28 + (name_idx as i32) * 0x2c + (ptr_idx as i32) * 0x4;
-            let try_ptr = md_follow_offset!(mono_data, offset.try_into().unwrap());
+        let offsets_md = md.with_table(MonoDataTable::PropOffsets(name_idx_u16));
+        for (offset_idx, offset) in offsets.iter_mut().enumerate() {
+            let try_offset = offsets_md.read_offset(0x28 + name_idx * 0x2c + offset_idx * 0x4)?;
 
             // detect the weird null pointers in the mono data here.
             // if an offset points to null, represent it in our mono data as `None`.
-            if *(try_ptr.cast::<u64>().as_ref().unwrap()) == NIL {
-                *ptr = None;
+            if u64::from_le_bytes(offsets_md.read_bytes(try_offset)?) == NIL {
+                *offset = None;
             } else {
-                *ptr = Some(try_ptr as usize);
+                *offset = Some(try_offset);
             }
         }
 
-        PropMonoData {
-            ptrs,
-            aabbs: ptrs[0]
-                .map(|ptr| PropAabbs::from_raw(ptr as *const u8))
+        Ok(PropMonoData {
+            offsets,
+            aabbs: offsets[0]
+                .map(|offset| {
+                    PropAabbs::parse(
+                        &md.with_table(MonoDataTable::PropAabbs(name_idx_u16)),
+                        offset,
+                    )
+                })
+                .transpose()?
                 .map(Rc::new),
-            collision_mesh: ptrs[7]
-                .map(|ptr| Mesh::from_raw(ptr as *const u8))
+            collision_mesh: offsets[7]
+                .map(|offset| {
+                    Mesh::parse(
+                        &md.with_table(MonoDataTable::PropCollisionMesh(name_idx_u16)),
+                        offset,
+                    )
+                })
+                .transpose()?
                 .map(Rc::new),
-            vault_points: ptrs[8]
-                .map(|ptr| PropMonoData::parse_vault_points(ptr as *const u8, name_idx)),
-        }
+            vault_points: offsets[8]
+                .map(|offset| {
+                    PropMonoData::parse_vault_points(
+                        &md.with_table(MonoDataTable::PropVaultPoints(name_idx_u16)),
+                        offset,
+                        name_idx,
+                    )
+                })
+                .transpose()?,
+        })
     }
 
     /// Parse a list of vault points from mono data.
     /// Vault points are encoded as a contiguous array of `Vec4` values where each
     /// `w` component is 1.0 (which can be ignored, resulting in a list of `Vec3` points).
-    unsafe fn parse_vault_points(mono_data: *const u8, name_idx: usize) -> Vec<Vec3> {
-        let mut result = vec![];
+    fn parse_vault_points(
+        md: &MonoDataReader,
+        offset: usize,
+        name_idx: usize,
+    ) -> Result<Vec<Vec3>, MonoDataError> {
         let num_vault_pts = NamePropConfig::get(name_idx as u16).num_vault_pts;
 
-        for i in 0..num_vault_pts as isize {
-            result.push(md_read!(mono_data, Vec3, i * 0x10));
-        }
-
-        result
+        (0..num_vault_pts as usize)
+            .map(|i| md.read_vec3(offset + i * 0x10))
+            .collect()
     }
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct MonoData {
-    pub zone_ptr: Option<usize>,
+    pub zone_offset: Option<usize>,
     pub zone_mesh: Rc<Mesh>,
-    pub area_ptrs: [Option<usize>; 5],
+    pub area_offsets: [Option<usize>; 5],
     pub props: Vec<Rc<PropMonoData>>,
 }
 
 impl MonoData {
-    pub unsafe fn from_raw(mono_data: *const u8) -> Rc<MonoData> {
-        // read zone pointer
-        let zone_ptr = md_follow_offset!(mono_data, 0x4) as usize;
+    /// Parse a mission's mono data, checking that every offset in it is in bounds.
+    pub fn parse(data: &[u8]) -> Result<MonoData, MonoDataError> {
+        let md = MonoDataReader::new(data);
+
+        // read zone offset
+        let zone_offset = md.read_offset(0x4)?;
 
         // parse zone into mesh
-        let zone_mesh = Rc::new(Mesh::from_raw(zone_ptr as *const u8));
-        // read area pointers
-        let area_ptrs = [
-            Some(md_follow_offset!(mono_data, 0x14) as usize),
-            Some(md_follow_offset!(mono_data, 0x18) as usize),
-            Some(md_follow_offset!(mono_data, 0x1c) as usize),
-            Some(md_follow_offset!(mono_data, 0x20) as usize),
-            Some(md_follow_offset!(mono_data, 0x24) as usize),
+        let zone_mesh = Rc::new(Mesh::parse(
+            &md.with_table(MonoDataTable::ZoneMesh),
+            zone_offset,
+        )?);
+
+        // read area offsets
+        let area_offsets = [
+            Some(md.read_offset(0x14)?),
+            Some(md.read_offset(0x18)?),
+            Some(md.read_offset(0x1c)?),
+            Some(md.read_offset(0x20)?),
+            Some(md.read_offset(0x24)?),
         ];
 
         let mut props = vec![];
         for name_idx in 0..NUM_NAME_PROPS {
-            let prop_data = PropMonoData::new(mono_data, name_idx);
+            let prop_data = PropMonoData::parse(&md, name_idx)?;
             props.push(Rc::new(prop_data));
         }
 
-        Rc::new(MonoData {
-            zone_ptr: Some(zone_ptr),
+        Ok(MonoData {
+            zone_offset: Some(zone_offset),
             zone_mesh,
-            area_ptrs,
+            area_offsets,
             props,
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{collision::aabb::Aabb, constants::FRAC_PI_2, props::subobject::PropSubobject};
+
+    use super::{MonoData, MonoDataError, MonoDataTable, PropAabbs};
+
+    #[test]
+    fn test_parse_mono_data() {
+        let missions: [&[u8]; 3] = [
+            include_bytes!("./bin/monodata/mission1.bin"),
+            include_bytes!("./bin/monodata/mission12.bin"),
+            include_bytes!("./bin/monodata/mission28.bin"),
+        ];
+
+        for data in missions {
+            let mono_data = MonoData::parse(data).unwrap();
+            assert!(!mono_data.zone_mesh.sectors.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_parse_bad_mono_data() {
+        let data: &[u8] = include_bytes!("./bin/monodata/mission1.bin");
+
+        // empty
+        assert!(matches!(
+            MonoData::parse(&[]),
+            Err(MonoDataError::OutOfBounds {
+                table: MonoDataTable::Header,
+                offset: 0x4,
+                ..
+            })
+        ));
+
+        // truncated
+        assert!(MonoData::parse(&data[0..data.len() / 2]).is_err());
+
+        // zone offset pointing past the end of the data
+        let mut bad_zone = data.to_vec();
+        bad_zone[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            MonoData::parse(&bad_zone),
+            Err(MonoDataError::BadOffset {
+                table: MonoDataTable::Header,
+                offset: 0x4,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_root_aabb_rotates_subobjects() {
+        let aabbs = PropAabbs {
+            aabbs: vec![
+                Aabb {
+                    min: [-1.0, 0.0, -1.0],
+                    max: [1.0, 2.0, 1.0],
+                },
+                Aabb {
+                    min: [0.0, 0.0, 0.0],
+                    max: [4.0, 1.0, 1.0],
+                },
+            ],
+        };
+
+        let mut subobjects = vec![PropSubobject::default()];
+        let root_aabb = aabbs.get_root_aabb(&subobjects);
+        assert_eq!(root_aabb.min, [-1.0, 0.0, -1.0]);
+        assert_eq!(root_aabb.max, [4.0, 2.0, 1.0]);
+
+        // a subobject turned a quarter turn around the y axis extends the root AABB along z
+        subobjects[0].rot_vec[1] = FRAC_PI_2;
+        let root_aabb = aabbs.get_root_aabb(&subobjects);
+        assert!((root_aabb.min[2] + 4.0).abs() < 0.001);
+        assert!((root_aabb.max[0] - 1.0).abs() < 0.001);
+    }
+}
@@ -0,0 +1,736 @@
+use std::{cell::RefCell, fmt::Display, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gamestate::{GameState, MonoInitStartArgs},
+    global::rng::RngState,
+    mono_data::MonoDataError,
+    props::{
+        motion::data::{file::MotionDataFile, set_motion_data_file},
+        prop::AddPropArgs,
+        random::layout::{set_random_layouts, RandomLayoutFile},
+    },
+    savestate::{load_savestate, SavestateError},
+};
+
+/// The version of the movie file format written by this simulation.
+/// This should be bumped whenever `MovieEvent` changes in a way that breaks old movies.
+pub const MOVIE_VERSION: u32 = 1;
+
+/// A single call to a state-mutating API function, as recorded in a movie.
+/// Arguments are stored exactly as unity passed them, except that prop positions
+/// have already been converted to simulation coordinates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MovieEvent {
+    MonoInitStart {
+        mission: i32,
+        area: i32,
+        stage: i32,
+        kadai_flag: i32,
+        clear_flag: i32,
+        end_flag: i32,
+
+        /// The motion data file loaded at `MonoInitStart`, if there was one.
+        #[serde(default)]
+        motion_data: Option<MotionDataFile>,
+
+        /// The random layout file loaded at `MonoInitStart` (empty if there wasn't one).
+        #[serde(default)]
+        random_layout: RandomLayoutFile,
+    },
+    MonoInitAddProp(AddPropArgs),
+    MonoInitAddPropSetParent {
+        ctrl_idx: i32,
+        parent_ctrl_idx: i32,
+    },
+    MonoInitEnd,
+    Init {
+        player_idx: i32,
+        override_init_size: f32,
+        mission: i32,
+    },
+    ResetAttempt,
+    SetTickRate {
+        ticks_per_second: i32,
+    },
+    SetGameStart {
+        player_idx: i32,
+        area: i32,
+    },
+    SetGameMode {
+        mode: u32,
+    },
+    SetGravity {
+        x: f32,
+        y: f32,
+        z: f32,
+    },
+    SetKatamariSpeed {
+        forw_s: f32,
+        side_s: f32,
+        back_s: f32,
+        boost_s: f32,
+        forw_a: f32,
+        side_a: f32,
+        back_a: f32,
+        boost_a: f32,
+        rot_s: f32,
+        limit_y: f32,
+        cam_x: f32,
+        cam_y: f32,
+        cam_z: f32,
+    },
+    SetKatamariTranslation {
+        player_idx: i32,
+        x: f32,
+        y: f32,
+        z: f32,
+    },
+    SetGameTime {
+        game_time_ms: i32,
+        remain_time_ticks: i32,
+        freeze: i32,
+        cam_eff_1P: i32,
+    },
+    SetStickState {
+        player_idx: i32,
+        ls_x: f32,
+        ls_y: f32,
+        rs_x: f32,
+        rs_y: f32,
+        l3_down: bool,
+        r3_down: bool,
+        l3_held: bool,
+        r3_held: bool,
+    },
+    SetTriggerState {
+        player_idx: i32,
+        l1_down: u8,
+        l2_down: u8,
+        r1_down: u8,
+        r2_down: u8,
+        l1_held: u8,
+        r1_held: u8,
+        l2_held: u8,
+        r2_held: u8,
+        cross_click: bool,
+    },
+    SetCameraMode {
+        player_idx: i32,
+        mode: i32,
+    },
+    SetCameraCheckScaleUp {
+        player_idx: i32,
+        flag: i32,
+    },
+    SetShootingMode {
+        player_idx: i32,
+        fg: i32,
+        reset: i32,
+    },
+    SetPreclearMode {
+        mode: i32,
+    },
+    SetTutorialA {
+        page: i32,
+        page_step: i32,
+    },
+    SetStoreFlag {
+        flag: i32,
+    },
+    SetAreaChange {
+        player_idx: i32,
+    },
+    SetMapChangeMode {
+        map_change_mode: i32,
+    },
+    ChangeNextArea,
+    SetPropStopFlag {
+        ctrl_idx: i32,
+        flag: i32,
+    },
+    Tick {
+        delta: f32,
+    },
+    /// A successful `RequestLoadState` call, with the savestate that was loaded.
+    LoadState {
+        savestate: Vec<u8>,
+    },
+}
+
+/// An error encountered while replaying a movie.
+#[derive(Debug)]
+pub enum MovieReplayError {
+    MonoData(MonoDataError),
+    MotionData(String),
+    Savestate(SavestateError),
+}
+
+impl Display for MovieReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MovieReplayError::MonoData(e) => write!(f, "{e}"),
+            MovieReplayError::MotionData(e) => write!(f, "error applying motion data: {e}"),
+            MovieReplayError::Savestate(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl MovieEvent {
+    /// Apply this event to `state` in the same way as its API function.
+    /// `mono_data` is the mission's mono data, which is only read by `MonoInitStart`.
+    pub fn apply(&self, state: &mut GameState, mono_data: &[u8]) -> Result<(), MovieReplayError> {
+        match *self {
+            MovieEvent::MonoInitStart {
+                mission,
+                area,
+                stage,
+                kadai_flag,
+                clear_flag,
+                end_flag,
+                ref motion_data,
+                ref random_layout,
+            } => {
+                set_motion_data_file(motion_data.as_ref()).map_err(MovieReplayError::MotionData)?;
+                set_random_layouts(random_layout.clone());
+
+                state.reset();
+                state
+                    .mono_init_start(
+                        mono_data,
+                        &MonoInitStartArgs {
+                            mission: mission as u8,
+                            area: area as u8,
+                            stage: stage as u8,
+                            kadai_flag: kadai_flag != 0,
+                            clear_flag: clear_flag != 0,
+                            end_flag: end_flag != 0,
+                        },
+                    )
+                    .map_err(MovieReplayError::MonoData)?;
+            }
+            MovieEvent::MonoInitAddProp(ref args) => {
+                state.add_prop(args);
+            }
+            MovieEvent::MonoInitAddPropSetParent {
+                ctrl_idx,
+                parent_ctrl_idx,
+            } => state.add_prop_set_parent(ctrl_idx, parent_ctrl_idx),
+            MovieEvent::MonoInitEnd => state.mono_init_end(),
+            MovieEvent::Init {
+                player_idx,
+                override_init_size,
+                mission,
+            } => state.init(player_idx as usize, override_init_size, mission as u8),
+            MovieEvent::ResetAttempt => {
+                state.reset_attempt();
+            }
+            MovieEvent::SetTickRate { ticks_per_second } => {
+                if let Ok(ticks_per_second) = u16::try_from(ticks_per_second) {
+                    state.set_tick_rate(ticks_per_second);
+                }
+            }
+            MovieEvent::SetGameStart { player_idx, area } => {
+                state.set_game_start(player_idx as usize, area as u8)
+            }
+            MovieEvent::SetGameMode { mode } => state.mission_state.set_gamemode(mode as u8),
+            MovieEvent::SetGravity { x, y, z } => state.global.set_gravity(x, y, z),
+            MovieEvent::SetKatamariSpeed {
+                forw_s,
+                side_s,
+                back_s,
+                boost_s,
+                forw_a,
+                side_a,
+                back_a,
+                boost_a,
+                rot_s,
+                limit_y,
+                cam_x,
+                cam_y,
+                cam_z,
+            } => state.set_katamari_speed(
+                forw_s, side_s, back_s, boost_s, forw_a, side_a, back_a, boost_a, rot_s, limit_y,
+                cam_x, cam_y, cam_z,
+            ),
+            MovieEvent::SetKatamariTranslation {
+                player_idx,
+                x,
+                y,
+                z,
+            } => state
+                .get_player_mut(player_idx as usize)
+                .katamari
+                .set_translation(x, y, z),
+            MovieEvent::SetGameTime {
+                game_time_ms,
+                remain_time_ticks,
+                freeze,
+                cam_eff_1P,
+            } => state.set_game_time(game_time_ms, remain_time_ticks, freeze, cam_eff_1P),
+            MovieEvent::SetStickState {
+                player_idx,
+                ls_x,
+                ls_y,
+                rs_x,
+                rs_y,
+                l3_down,
+                r3_down,
+                l3_held,
+                r3_held,
+            } => state
+                .get_player_mut(player_idx as usize)
+                .input
+                .set_stick_state(ls_x, ls_y, rs_x, rs_y, l3_down, r3_down, l3_held, r3_held),
+            MovieEvent::SetTriggerState {
+                player_idx,
+                l1_down,
+                l2_down,
+                r1_down,
+                r2_down,
+                l1_held,
+                r1_held,
+                l2_held,
+                r2_held,
+                cross_click,
+            } => state
+                .get_player_mut(player_idx as usize)
+                .input
+                .set_trigger_state(
+                    l1_down != 0,
+                    l1_held != 0,
+                    l2_down != 0,
+                    l2_held != 0,
+                    r1_down != 0,
+                    r1_held != 0,
+                    r2_down != 0,
+                    r2_held != 0,
+                    cross_click,
+                ),
+            MovieEvent::SetCameraMode { player_idx, mode } => state
+                .get_player_mut(player_idx as usize)
+                .set_camera_mode(mode.into()),
+            MovieEvent::SetCameraCheckScaleUp { player_idx, flag } => state
+                .get_player_mut(player_idx as usize)
+                .camera
+                .check_scale_up(flag != 0),
+            MovieEvent::SetShootingMode {
+                player_idx,
+                fg,
+                reset,
+            } => state
+                .get_player_mut(player_idx as usize)
+                .set_shooting_mode(fg != 0, reset != 0),
+            MovieEvent::SetPreclearMode { mode } => {
+                state.get_player_mut(0).camera.preclear.set_mode(mode != 0)
+            }
+            MovieEvent::SetTutorialA { page, page_step } => {
+                state.mission_state.tutorial.set_page(page, page_step)
+            }
+            MovieEvent::SetStoreFlag { flag } => state.set_store_flag(flag != 0),
+            MovieEvent::SetAreaChange { player_idx } => state.set_area_change(player_idx as usize),
+            MovieEvent::SetMapChangeMode { map_change_mode } => {
+                state.set_map_change_mode(map_change_mode)
+            }
+            MovieEvent::ChangeNextArea => state.change_next_area(),
+            MovieEvent::SetPropStopFlag { ctrl_idx, flag } => {
+                if let Some(prop_ref) = state.props.get_prop(ctrl_idx as usize) {
+                    prop_ref.borrow_mut().set_disabled(flag);
+                }
+            }
+            MovieEvent::Tick { delta } => state.tick(delta),
+            MovieEvent::LoadState { ref savestate } => {
+                let state_ref = RefCell::new(std::mem::take(state));
+                let result = load_savestate(savestate, &state_ref);
+                *state = state_ref.into_inner();
+                result.map_err(MovieReplayError::Savestate)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An error encountered while reading a movie file.
+#[derive(Debug)]
+pub enum MovieError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    UnsupportedVersion(u32),
+    /// The movie doesn't begin with a `MonoInitStart` event.
+    MissingMonoInitStart,
+}
+
+impl Display for MovieError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MovieError::Io(e) => write!(f, "error reading movie: {e}"),
+            MovieError::Parse(e) => write!(f, "error parsing movie: {e}"),
+            MovieError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported movie version {version} (expected {MOVIE_VERSION})"
+            ),
+            MovieError::MissingMonoInitStart => {
+                write!(f, "movie doesn't begin with `MonoInitStart`")
+            }
+        }
+    }
+}
+
+/// A recording of every state-mutating API call made by unity during a single mission
+/// attempt, starting from its `MonoInitStart` call. Since the simulation is deterministic
+/// given the rng state at `MonoInitStart` (and the motion data and random layout files that
+/// are recorded with it), replaying a movie reproduces the attempt exactly (as long as the
+/// replay gets the same answers to world raycasts). Loaded savestates are recorded in full.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Movie {
+    /// The movie file format version (see `MOVIE_VERSION`).
+    pub version: u32,
+
+    /// The rng state right before `MonoInitStart` was called.
+    pub rng: RngState,
+
+    /// The recorded API calls, in order. Each frame ends with a `Tick` event.
+    pub events: Vec<MovieEvent>,
+}
+
+impl Movie {
+    pub fn new(rng: RngState) -> Self {
+        Self {
+            version: MOVIE_VERSION,
+            rng,
+            events: vec![],
+        }
+    }
+
+    /// The number of ticks in the movie.
+    pub fn num_frames(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|event| matches!(event, MovieEvent::Tick { .. }))
+            .count()
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> std::io::Result<()> {
+        let data = serde_json::to_string(self)?;
+        std::fs::write(path, data)
+    }
+
+    pub fn read_from_file(path: &Path) -> Result<Self, MovieError> {
+        let data = std::fs::read_to_string(path).map_err(MovieError::Io)?;
+        Self::from_json(&data)
+    }
+
+    pub fn from_json(data: &str) -> Result<Self, MovieError> {
+        let movie: Movie = serde_json::from_str(data).map_err(MovieError::Parse)?;
+
+        if movie.version != MOVIE_VERSION {
+            return Err(MovieError::UnsupportedVersion(movie.version));
+        }
+        if !matches!(movie.events.first(), Some(MovieEvent::MonoInitStart { .. })) {
+            return Err(MovieError::MissingMonoInitStart);
+        }
+
+        Ok(movie)
+    }
+}
+
+/// Records API calls to a movie file.
+#[derive(Debug)]
+pub struct MovieRecorder {
+    /// The path the movie is written to when recording stops.
+    path: Box<Path>,
+
+    /// The movie being recorded, which starts at the first `MonoInitStart` call
+    /// after recording was requested.
+    movie: Option<Movie>,
+}
+
+impl MovieRecorder {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.into(),
+            movie: None,
+        }
+    }
+
+    /// Record `event`, given the rng state before the event was applied.
+    pub fn record(&mut self, event: MovieEvent, rng: &RngState) {
+        if let MovieEvent::MonoInitStart { .. } = event {
+            // a new mission attempt always starts a new movie
+            self.movie = Some(Movie::new(*rng));
+        }
+
+        if let Some(movie) = &mut self.movie {
+            movie.events.push(event);
+        }
+    }
+
+    /// Write the recorded movie to the recorder's path.
+    /// Returns `false` if nothing was recorded or the movie couldn't be written.
+    pub fn finish(self) -> std::io::Result<bool> {
+        match self.movie {
+            Some(movie) => movie.write_to_file(&self.path).map(|_| true),
+            None => Ok(false),
+        }
+    }
+}
+
+/// Replays a movie against a game state, one frame at a time.
+#[derive(Debug)]
+pub struct MoviePlayer<'a> {
+    movie: &'a Movie,
+
+    /// The index of the next event to be applied.
+    next_event_idx: usize,
+}
+
+impl<'a> MoviePlayer<'a> {
+    pub fn new(movie: &'a Movie) -> Self {
+        Self {
+            movie,
+            next_event_idx: 0,
+        }
+    }
+
+    /// Returns `true` if every event in the movie has been applied.
+    pub fn is_finished(&self) -> bool {
+        self.next_event_idx >= self.movie.events.len()
+    }
+
+    /// Apply movie events to `state` until the next `Tick` event has been applied
+    /// (or until the movie runs out of events).
+    /// Returns `true` if a tick was applied.
+    pub fn play_frame(
+        &mut self,
+        state: &mut GameState,
+        mono_data: &[u8],
+    ) -> Result<bool, MovieReplayError> {
+        while let Some(event) = self.movie.events.get(self.next_event_idx) {
+            self.next_event_idx += 1;
+
+            if let MovieEvent::MonoInitStart { .. } = event {
+                // `MonoInitStart` resets everything but the rng, so restore the rng first
+                state.global.rng = self.movie.rng;
+            }
+
+            event.apply(state, mono_data)?;
+
+            if let MovieEvent::Tick { .. } = event {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{num::Wrapping, rc::Rc};
+
+    use crate::{
+        collision::world_collider::StageMeshCollider,
+        gamestate::GameState,
+        props::{
+            prop::AddPropArgs,
+            random::layout::{RandomLayoutFile, RandomLayoutOverride, RngSeed},
+        },
+        savestate::encode_savestate,
+    };
+
+    use super::{Movie, MovieError, MovieEvent, MoviePlayer, MovieRecorder};
+
+    static MAS1_MONO_DATA: &[u8] = include_bytes!("./bin/monodata/mission1.bin");
+
+    fn record_events(state: &mut GameState, recorder: &mut MovieRecorder, events: &[MovieEvent]) {
+        for event in events {
+            recorder.record(event.clone(), &state.global.rng);
+            event.apply(state, MAS1_MONO_DATA).unwrap();
+        }
+    }
+
+    fn new_state() -> GameState {
+        let mut state = GameState::new();
+        state.reset();
+
+        // a floor 10 units below the origin
+        let collider = StageMeshCollider::from_obj_str(
+            "v -100 -10 -100\nv 100 -10 -100\nv 100 -10 100\nv -100 -10 100\nf 1 4 3 2\n",
+        )
+        .unwrap();
+        state.set_world_collider(Some(Rc::new(collider)));
+
+        state
+    }
+
+    #[test]
+    fn test_movie_replay() {
+        let mut prop_args = AddPropArgs {
+            pos_x: 0.0,
+            pos_y: 0.0,
+            pos_z: 1.0,
+            rot_x: 0.0,
+            rot_y: 0.0,
+            rot_z: 0.0,
+            rot_w: 1.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            scale_z: 1.0,
+            name_idx: 782,
+            loc_pos_type: 0,
+            random_group_id: 0xffff,
+            mono_move_type: 0xffff,
+            mono_hit_on_area: 0xffff,
+            link_action: 0xffff,
+            extra_action_type: 0xffff,
+            unique_name_id: 0xffff,
+            disp_off_area_no: 0xffff,
+            vs_drop_flag: 0,
+            comment_id: 0xffff,
+            comment_group_id: 0xffff,
+            twin_id: 0xffff,
+            shake_off_flag: 0,
+        };
+        prop_args.transform_coords_to_sim();
+
+        let mut events = vec![
+            MovieEvent::MonoInitStart {
+                mission: 1,
+                area: 0,
+                stage: 1,
+                kadai_flag: 0,
+                clear_flag: 0,
+                end_flag: 0,
+                motion_data: None,
+                random_layout: RandomLayoutFile::default(),
+            },
+            MovieEvent::MonoInitAddProp(prop_args),
+            MovieEvent::MonoInitEnd,
+            MovieEvent::Init {
+                player_idx: 0,
+                override_init_size: -1.0,
+                mission: 1,
+            },
+            MovieEvent::SetGravity {
+                x: 0.0,
+                y: -1.0,
+                z: 0.0,
+            },
+            MovieEvent::SetKatamariSpeed {
+                forw_s: 1.0,
+                side_s: 1.0,
+                back_s: 1.0,
+                boost_s: 1.0,
+                forw_a: 1.0,
+                side_a: 1.0,
+                back_a: 1.0,
+                boost_a: 1.0,
+                rot_s: 1.0,
+                limit_y: -100.0,
+                cam_x: 1.0,
+                cam_y: 1.0,
+                cam_z: 1.0,
+            },
+            MovieEvent::SetGameStart {
+                player_idx: 0,
+                area: 0,
+            },
+        ];
+        for frame in 0..60 {
+            events.push(MovieEvent::SetStickState {
+                player_idx: 0,
+                ls_x: 0.0,
+                ls_y: 1.0,
+                rs_x: if frame < 30 { 0.0 } else { 1.0 },
+                rs_y: 1.0,
+                l3_down: false,
+                r3_down: false,
+                l3_held: false,
+                r3_held: false,
+            });
+            events.push(MovieEvent::Tick { delta: 1.0 / 30.0 });
+        }
+
+        // record a run, and round-trip the movie through its file format
+        let mut recorded_state = new_state();
+        recorded_state.global.rng.rng1 = Wrapping(1234);
+        let mut recorder = MovieRecorder::new(std::path::Path::new("unused"));
+        record_events(&mut recorded_state, &mut recorder, &events);
+        let json = serde_json::to_string(&recorder.movie.unwrap()).unwrap();
+        let movie = Movie::from_json(&json).unwrap();
+        assert_eq!(movie.num_frames(), 60);
+
+        // replaying the movie on a fresh state should reproduce the run exactly
+        let mut replayed_state = new_state();
+        let mut player = MoviePlayer::new(&movie);
+        while player
+            .play_frame(&mut replayed_state, MAS1_MONO_DATA)
+            .unwrap()
+        {}
+        assert!(player.is_finished());
+
+        let recorded_kat = &recorded_state.get_player(0).katamari;
+        let replayed_kat = &replayed_state.get_player(0).katamari;
+        assert_eq!(recorded_kat.get_center(), replayed_kat.get_center());
+        assert_eq!(recorded_kat.get_speed(), replayed_kat.get_speed());
+        assert_eq!(
+            recorded_state.get_player(0).prince.get_pos(),
+            replayed_state.get_player(0).prince.get_pos()
+        );
+    }
+
+    #[test]
+    fn test_movie_files_and_load_state() {
+        // the random layout file recorded at `MonoInitStart` is used when replaying
+        let seed = RngSeed { rng1: 99, rng2: 7 };
+        let mut random_layout = RandomLayoutFile::default();
+        random_layout.missions.insert(
+            1,
+            RandomLayoutOverride {
+                seed: Some(seed),
+                ..Default::default()
+            },
+        );
+        let init = MovieEvent::MonoInitStart {
+            mission: 1,
+            area: 0,
+            stage: 1,
+            kadai_flag: 0,
+            clear_flag: 0,
+            end_flag: 0,
+            motion_data: None,
+            random_layout,
+        };
+
+        let mut state = new_state();
+        init.apply(&mut state, MAS1_MONO_DATA).unwrap();
+        assert_eq!(RngSeed::from(&state.global.rng), seed);
+
+        // loading a state restores the recorded state
+        let savestate = encode_savestate(&state).unwrap();
+        let center = *state.get_player(0).katamari.get_center();
+        state
+            .get_player_mut(0)
+            .katamari
+            .set_translation(1.0, 2.0, 3.0);
+        state.global.rng.rng1 += 1;
+
+        let load = MovieEvent::LoadState { savestate };
+        let json = serde_json::to_string(&load).unwrap();
+        let load: MovieEvent = serde_json::from_str(&json).unwrap();
+        load.apply(&mut state, MAS1_MONO_DATA).unwrap();
+        assert_eq!(*state.get_player(0).katamari.get_center(), center);
+        assert_eq!(RngSeed::from(&state.global.rng), seed);
+    }
+
+    #[test]
+    fn test_movie_bad_version() {
+        let json = r#"{"version":0,"rng":{"rng1":4321,"rng1_calls":0,"rng2":0},"events":[]}"#;
+        assert!(matches!(
+            Movie::from_json(json),
+            Err(MovieError::UnsupportedVersion(0))
+        ));
+    }
+}
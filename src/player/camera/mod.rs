@@ -8,6 +8,7 @@ use crate::{
     collision::raycast_state::{RaycastCallType, RaycastRef, Raycasts},
     constants::{FRAC_PI_2, UNITY_TO_SIM_SCALE, VEC3_Y_POS, VEC3_ZERO, VEC3_Z_POS},
     delegates::{has_delegates::HasDelegates, sound_id::SoundId, DelegatesRef},
+    global::tick_rate::TickRate,
     macros::{max, min, set_y, vec3_from, vec3_unit_xz},
     math::{
         acos_f32, change_bounded_angle, mat4_compute_yaw_rot, mat4_look_at, vec3_inplace_add_vec,
@@ -81,6 +82,9 @@ pub struct CameraState {
     #[serde(skip)]
     delegates_ref: Option<DelegatesRef>,
 
+    /// The rate at which the simulation is ticked (a copy of `GlobalState::tick_rate`).
+    tick_rate: TickRate,
+
     // END extra fields not in the original simulation
     /// The camera position's offset from the katamari center position.
     /// This vector is usually constant, but changes during "swirl" size-up effects
@@ -319,6 +323,7 @@ impl CameraState {
                 self.load_ray(&ray_start, &ray_end);
                 let delegates = self.delegates_ref.as_ref().unwrap();
                 let hits = delegates.borrow().call_do_hit(
+                    self.raycasts.as_ref(),
                     &ray_start,
                     &ray_end,
                     RaycastCallType::Stage,
@@ -337,7 +342,7 @@ impl CameraState {
                 self.r1_jump_counter = 0;
                 self.r1_jump_state = CamR1JumpState::Rising;
                 // TODO_PARAM
-                self.r1_jump_duration = 0x14;
+                self.r1_jump_duration = self.tick_rate.duration(0x14);
                 self.r1_jump_init_pos = pos;
                 vec3::zero(&mut self.r1_jump_translation);
                 vec3::zero(&mut self.r1_jump_last_translation);
@@ -522,6 +527,7 @@ impl CameraState {
                 let mut target_moved = vec3_from!(-, target, self.last_target);
 
                 if self.apply_easing {
+                    let easing_speed = self.tick_rate.per_tick_ratio(easing_speed);
                     vec3_inplace_scale(&mut pos_moved, easing_speed);
                     vec3_inplace_scale(&mut target_moved, easing_speed);
                 }
@@ -808,6 +814,7 @@ impl CameraState {
                 self.load_ray(&ray_start, &ray_end);
                 self.r1_jump_peak_height = if let Some(delegates) = &self.delegates_ref {
                     let found_hits = delegates.borrow().call_do_hit(
+                        self.raycasts.as_ref(),
                         &ray_start,
                         &ray_end,
                         RaycastCallType::Stage,
@@ -1005,6 +1012,22 @@ impl Camera {
         self.params.delay_y = y;
         self.params.delay_z = z;
     }
+
+    pub fn set_tick_rate(&mut self, tick_rate: TickRate) {
+        self.state.tick_rate = tick_rate;
+    }
+
+    /// Reset the camera to its state before `init`, keeping its params (which unity can
+    /// change with `SetKatamariSpeed`) and the tick rate.
+    pub fn reset_keeping_params(&mut self) {
+        let params = std::mem::take(&mut self.params);
+        let tick_rate = self.state.tick_rate;
+        *self = Camera {
+            params,
+            ..Default::default()
+        };
+        self.state.tick_rate = tick_rate;
+    }
 }
 
 impl Camera {
@@ -1086,8 +1109,8 @@ impl Camera {
     /// field on the `Prince` object is needed.
     /// offset: 0x54c90 (the second half)
     pub fn update_l1_look(&mut self, ls_x: f32, ls_y: f32, prince_angle: &mut f32) {
-        let speed_x = self.params.l1_look_speed_x;
-        let speed_y = self.params.l1_look_speed_y;
+        let speed_x = self.state.tick_rate.per_tick(self.params.l1_look_speed_x);
+        let speed_y = self.state.tick_rate.per_tick(self.params.l1_look_speed_y);
         let min_y = self.params.l1_look_min_y;
         let max_y = self.params.l1_look_max_y;
 
@@ -3,7 +3,7 @@ use gl_matrix::{common::Vec3, mat4, vec3};
 use crate::{
     collision::{hit_attribute::HitAttribute, raycast_state::RaycastCallType},
     constants::{FRAC_5PI_12, FRAC_PI_2, FRAC_PI_90, PI, VEC3_Y_NEG, VEC3_ZERO},
-    debug::DEBUG_CONFIG,
+    debug::debug_config,
     delegates::{has_delegates::HasDelegates, sound_id::SoundId, vfx_id::VfxId},
     global::GlobalState,
     macros::{
@@ -20,8 +20,8 @@ use crate::{
     props::{
         config::{NamePropConfig, NAME_PROP_CONFIGS},
         prop::{
-            Prop, PropFlags1, PropFlags2, PropGlobalState, PropRef, PropTrajectoryType,
-            PropUnattachedState,
+            ParentAttachedReaction, Prop, PropFlags1, PropFlags2, PropGlobalState, PropRef,
+            PropTrajectoryType, PropUnattachedState,
         },
         PropsState,
     },
@@ -130,7 +130,7 @@ impl Katamari {
         // TODO_VS: `kat_update_collision:96-101` (decrement timer)
 
         mark_address!("0x12f7f");
-        self.find_nearby_props(props, prince, mission_state);
+        self.find_nearby_props(props, prince, global, mission_state);
         mark_address!("0x12f87");
 
         if mission_state.gamemode == GameMode::Ending {
@@ -206,7 +206,7 @@ impl Katamari {
             self.remove_prop_from_attached_list(ctrl_idx);
         }
 
-        self.process_nearby_collectible_props(mission_state);
+        self.process_nearby_collectible_props(mission_state, global);
         self.process_collected_props(props, mission_state, global);
         // TODO: `kat_update_world_size_threshold??()`
 
@@ -238,6 +238,7 @@ impl Katamari {
         &mut self,
         props: &mut PropsState,
         prince: &mut Prince,
+        global: &mut GlobalState,
         mission_state: &MissionState,
     ) {
         // TODO_VS: `kat_find_nearby_props:43` (return immediately if vs mode or if other vs condition holds)
@@ -287,7 +288,10 @@ impl Katamari {
             // The prop-katamari distance decreased at most by the distance the katamari just moved.
             // If that minimum distance is still bigger than the sum of the kat's and prop's bounding
             // spheres, they can't collide.
-            let min_dist_to_kat = max!(prop.get_dist_to_katamari(0) - kat_move_len, 0.0);
+            let min_dist_to_kat = max!(
+                prop.get_dist_to_katamari(self.player as i32) - kat_move_len,
+                0.0
+            );
 
             if min_dist_to_kat > self.radius_cm + prop.get_radius() {
                 continue;
@@ -334,7 +338,12 @@ impl Katamari {
                 }
             }
 
-            prop.reset_scream_cooldown_timer();
+            // (??) a bonked vending machine ejects a drink, at most as often as props scream
+            if fast_enough_for_scream && scream_off_cooldown && prop.is_vending_machine() {
+                self.eject_vending_machine_drink(&prop, global);
+            }
+
+            prop.reset_scream_cooldown_timer(&self.tick_rate);
 
             // TODO_DOC: this shouldn't be necessary and it's not clear why the simulation does it
             // when the contact prop is already set in `check_prop_mesh_collision`
@@ -359,8 +368,9 @@ impl Katamari {
                     self.speed,
                 );
 
-                // TODO_VS: this call doesn't happen in vs mode
-                prince.end_spin_and_boost(self);
+                if !mission_state.is_vs_mode {
+                    prince.end_spin_and_boost(self);
+                }
             }
         }
     }
@@ -638,7 +648,7 @@ impl Katamari {
                 // TODO_DOC: if the katamari isn't going to contact the prop, then it
                 // should bounce off of the prop instead
                 self.physics_flags.contacts_prop_0xa = true;
-                prop.intangible_timer = PROP_INTANGIBILITY_AFTER_HIT;
+                prop.intangible_timer = self.tick_rate.duration(PROP_INTANGIBILITY_AFTER_HIT);
 
                 let base_speed = self.max_boost_speed;
 
@@ -661,7 +671,7 @@ impl Katamari {
             self.set_contact_prop(&prop_ref, prop.get_ctrl_idx());
 
             // if any aabb was hit, attempt to draw the prop's mesh
-            if DEBUG_CONFIG.draw_collided_prop_mesh {
+            if debug_config().draw_collided_prop_mesh {
                 self.debug_draw_collided_prop_mesh(&prop_mesh, &prop_transform);
             }
         }
@@ -673,46 +683,44 @@ impl Katamari {
     /// offset: 0x2af40
     fn resolve_uncollectible_prop_collision(
         &mut self,
-        _props: &mut PropsState,
+        props: &mut PropsState,
         _prop_ref: PropRef,
-        _prop: &mut Prop,
+        prop: &mut Prop,
     ) {
-        // let root_prop = if prop.has_parent() {
-        //     let root_ref = prop.get_root_ref(props);
-        //     root_ref.clone().borrow_mut()
-        // } else {
-        //     prop
-        // };
-
-        // // TODO_LINK:
-        // // if `root_prop.link_action + ~CHILDREN_INTANGIBLE & 0xfd == 0` { root_prop = prop }
-
-        // // Handle collisions with a stationary prop.
-        // if root_prop.get_move_type().is_none() {
-        //     return self.resolve_stationary_prop_collision(prop);
-        // }
-
-        // // TODO_DOC: what is this doing, something to do with turntables
-        // let behavior_cond = root_prop.get_behavior_type() == Some(PropBehavior::Value0x15);
-        // let name_idx = prop.get_name_idx();
-        // let prop_is_turntable = name_idx == 0x31d // Manhole Cover
-        //     || name_idx == 0x35b // Round Table
-        //     || name_idx == 0x55b; // Parking Turntable
-        // let prop_barely_moved = vec3::len(&vec3_from!(-, prop.last_pos, prop.pos)) <= 1.0;
-        // let weird_cond = behavior_cond || prop_is_turntable || prop_barely_moved;
-        // if root_prop.get_stationary() && weird_cond {
-        //     return;
-        // }
-
-        // if self.physics_flags.vs_attack {
-        //     return;
-        // }
-
-        // if root_prop.get_flags2().contains(PropFlags2::Wobble) {
-        //     return;
-        // }
-
-        // TODO_PROP_MOTION: `kat_resolve_uncollectible_prop_collision:72-`
+        // the collision depends on the root prop of the prop's tree (`prop` is already
+        // borrowed, so it's only looked up when it has a parent)
+        let root_ref = prop.has_parent().then(|| prop.get_root_ref(props));
+        let (root_moves, root_stationary, root_behavior, root_wobbles) = {
+            let root_borrow = root_ref.as_ref().map(|root_ref| root_ref.borrow());
+            let root_prop = root_borrow.as_deref().unwrap_or(prop);
+            (
+                root_prop.get_move_type().is_some(),
+                root_prop.get_stationary(),
+                root_prop.get_behavior(),
+                root_prop.get_flags2().contains(PropFlags2::Wobble),
+            )
+        };
+
+        // TODO_LINK:
+        // if `root_prop.link_action + ~CHILDREN_INTANGIBLE & 0xfd == 0` { root_prop = prop }
+
+        // handle collisions with a stationary prop
+        if !root_moves {
+            return self.resolve_stationary_prop_collision(prop);
+        }
+
+        // TODO_DOC: what is this doing, something to do with turntables
+        let behavior_cond = root_behavior == Some(0x15);
+        let prop_barely_moved = vec3::distance(&prop.last_pos, &prop.pos) <= 1.0;
+        if root_stationary && (behavior_cond || prop.is_turntable() || prop_barely_moved) {
+            return;
+        }
+
+        // the rest of the collision is skipped while the katamari is knocked back in vs mode,
+        // or while the prop is wobbling
+        if !self.physics_flags.vs_attack && !root_wobbles {
+            // TODO_PROP_MOTION: `kat_resolve_uncollectible_prop_collision:72-`
+        }
     }
 
     /// Resolve a collision between this katamari and an uncollectible, stationary prop.
@@ -727,7 +735,11 @@ impl Katamari {
     }
 
     /// offset: 0x28640
-    fn process_nearby_collectible_props(&mut self, mission_state: &MissionState) {
+    fn process_nearby_collectible_props(
+        &mut self,
+        mission_state: &MissionState,
+        global: &mut GlobalState,
+    ) {
         // TODO_PARAM
         let SQUASH_PROP_VOL_MULTIPLIER = 3.0;
         let MAX_COLLECTED_PROPS_PER_FRAME = 0x40;
@@ -737,7 +749,7 @@ impl Katamari {
         } else {
             // TODO_PERF: not amazing having to clone the `nearby_collectible_props` list here
             for prop_ref in self.nearby_collectible_props.clone() {
-                let prop = prop_ref.borrow_mut();
+                let mut prop = prop_ref.borrow_mut();
                 let prop_config = NAME_PROP_CONFIGS.get(prop.get_name_idx() as usize).unwrap();
 
                 let link_cond = prop.parent.is_none()
@@ -753,7 +765,7 @@ impl Katamari {
                         > SQUASH_PROP_VOL_MULTIPLIER * prop.get_compare_vol_m3();
 
                     if can_prop_be_airborne && !is_prop_squashed {
-                        // TODO_AIRBORNE: `kat_init_prop_launch()`
+                        self.init_prop_launch(&mut prop, global);
                     } else {
                         self.new_collected_props.push(prop_ref.clone());
                         if self.new_collected_props.len() >= MAX_COLLECTED_PROPS_PER_FRAME {
@@ -815,7 +827,7 @@ impl Katamari {
     /// offset: 0x280c0
     fn process_collected_props(
         &mut self,
-        props: &PropsState,
+        props: &mut PropsState,
         mission_state: &MissionState,
         global: &mut GlobalState,
     ) {
@@ -853,7 +865,7 @@ impl Katamari {
             prop.set_katamari_contact(self.player);
 
             self.attach_prop(props, prop_ref, &mut prop, mission_state, global);
-            // TODO_LINK: `attach_prop_with_children(prop)`
+            self.attach_prop_children(props, &prop, mission_state, global);
             prop.get_flags2_mut().remove(PropFlags2::Flee);
 
             // if the object has a scream type, play its collection scream sfx
@@ -882,11 +894,65 @@ impl Katamari {
         }
     }
 
+    /// Update the Gemini score (the number of twin pairs attached to the katamari) after
+    /// `prop` is attached (if `attached`) or detached. A pair only counts while both of its
+    /// twins are attached, so the score only changes if `prop`'s twin is attached.
+    fn update_gemini_pair_count(&self, prop: &Prop, attached: bool, global: &mut GlobalState) {
+        let Some(twin_ctrl_idx) = prop.get_twin() else {
+            return;
+        };
+
+        if self.attached_prop_ctrl_indices.contains(&twin_ctrl_idx) {
+            global.catch_count_b += if attached { 1 } else { -1 };
+        }
+    }
+
+    /// Make the children of the newly attached prop `parent` react to it being attached,
+    /// according to their link actions: they're attached too, destroyed, start their alt
+    /// motion action, or fall off.
+    /// (`attach_prop_with_children` in the original simulation)
+    fn attach_prop_children(
+        &mut self,
+        props: &mut PropsState,
+        parent: &Prop,
+        mission_state: &MissionState,
+        global: &mut GlobalState,
+    ) {
+        for child_ref in parent.get_child_refs() {
+            let mut child = child_ref.borrow_mut();
+            if child.is_disabled()
+                || child.is_attached()
+                || child.parent != Some(parent.get_ctrl_idx())
+            {
+                continue;
+            }
+
+            let reaction = child.get_parent_attached_reaction();
+            child.detach_from_parent();
+
+            match reaction {
+                ParentAttachedReaction::AttachWithParent => {
+                    child.set_katamari_contact(self.player);
+                    self.attach_prop(props, &child_ref, &mut child, mission_state, global);
+                    self.attach_prop_children(props, &child, mission_state, global);
+                }
+                ParentAttachedReaction::Destroy => child.destroy(),
+                ParentAttachedReaction::StartAltAction => props.start_alt_motion_action(&mut child),
+                ParentAttachedReaction::FallOff => child.apply_trajectory(
+                    &[0.0; 3],
+                    PropTrajectoryType::Normal,
+                    self.airborne_prop_gravity,
+                    global,
+                ),
+            }
+        }
+    }
+
     /// Attaches `prop` to the katamari.
     /// offset: 0x28ef0
     fn attach_prop(
         &mut self,
-        props: &PropsState,
+        props: &mut PropsState,
         prop_ref: &PropRef,
         prop: &mut Prop,
         mission_state: &MissionState,
@@ -910,15 +976,8 @@ impl Katamari {
 
         // update the score in theme object constellations
         if mission_state.mission_config.game_type == GameType::NumThemeProps {
-            // increment gemini score if the attached prop's twin is already attached
             if mission_state.mission == Mission::Gemini {
-                if let Some(twin_ctrl_idx) = prop.get_twin() {
-                    if let Some(twin_ref) = props.get_prop(twin_ctrl_idx as usize) {
-                        if twin_ref.borrow().is_attached() {
-                            global.catch_count_b += 1;
-                        }
-                    }
-                }
+                self.update_gemini_pair_count(prop, true, global);
             } else {
                 // in other constellations, increment the score if the prop's name index belongs to
                 // the mission's list of applicable name indices
@@ -931,10 +990,19 @@ impl Katamari {
         }
 
         prop.attach_to_kat(&self);
+        if mission_state.is_vs_mode {
+            self.vs_catch_count += 1;
+        }
+        props.comments.on_prop_attached(
+            prop.get_ctrl_idx(),
+            prop.get_comment_id(),
+            prop.get_comment_group_id(),
+        );
         self.vol_m3 += self.attach_vol_penalty * prop.get_attach_vol_m3();
 
         // update collection order list
         self.attached_props.push(prop_ref.clone());
+        self.attached_prop_ctrl_indices.push(prop.get_ctrl_idx());
 
         // compute the unit vector from this katamari to `prop`
         let prop_pos = prop.get_position().clone();
@@ -1621,7 +1689,11 @@ impl Katamari {
 
             // also detach props every so often, because why not? maybe that'll help.
             // what the hell do i know? fuck it!
-            if self.stuck_ticks > self.params.detach_cooldown_when_stuck_btwn_walls {
+            if self.stuck_ticks
+                > self
+                    .tick_rate
+                    .duration(self.params.detach_cooldown_when_stuck_btwn_walls)
+            {
                 // TODO: (line 352) needs camera+global state here
                 if !self.physics_flags.detaching_props {
                     self.static_detaching_props = true;
@@ -1666,9 +1738,8 @@ impl Katamari {
 
         // TODO_PERF: don't clone this
         let mut attached_props = self.attached_props.clone();
-        let mut detached_indices = vec![];
 
-        for (attach_idx, prop_ref) in attached_props.iter_mut().rev().enumerate() {
+        for prop_ref in attached_props.iter_mut().rev() {
             let should_detach;
             let prop_vol;
             {
@@ -1688,7 +1759,6 @@ impl Katamari {
 
             if should_detach {
                 self.detach_prop(mission_state, global, prop_ref, detach_speed);
-                detached_indices.push(attach_idx);
                 remaining_life -= prop_vol;
                 if remaining_life <= 0.0 {
                     return;
@@ -1700,8 +1770,6 @@ impl Katamari {
                 return;
             }
         }
-
-        // TODO_BUG: remove `detached_indices` from `attached_props`
     }
 
     /// Detach a prop from the katamari with the speed `detach_speed`.
@@ -1717,6 +1785,12 @@ impl Katamari {
             .borrow_mut()
             .detach_from_katamari(mission_state, global);
 
+        if mission_state.mission == Mission::Gemini {
+            self.update_gemini_pair_count(&prop_ref.borrow(), false, global);
+        }
+
+        self.remove_prop_from_attached_list(prop_ref.borrow().get_ctrl_idx());
+
         let mut prop_init_vel = vec3::create();
         self.compute_detached_prop_init_vel(&mut prop_init_vel, prop_ref, detach_speed);
 
@@ -1724,6 +1798,7 @@ impl Katamari {
             &prop_init_vel,
             PropTrajectoryType::Normal,
             self.airborne_prop_gravity,
+            global,
         );
 
         self.detached_props_from_bonk += 1;
@@ -1760,7 +1835,73 @@ impl Katamari {
         vec3_inplace_scale(out_prop_init_vel, prop_speed);
 
         // TODO_PARAM
-        prop.intangible_timer = 10;
+        prop.intangible_timer = self.tick_rate.duration(10);
+    }
+
+    /// Knock the prop `prop` into the air when the katamari hits it, rather than collecting it.
+    /// (??) `kat_init_prop_launch` hasn't been ported yet, so the launch angle and speed are
+    /// guesses.
+    fn init_prop_launch(&self, prop: &mut Prop, global: &mut GlobalState) {
+        // TODO_PARAM
+        let LAUNCH_ANGLE = FRAC_PI_2 * 0.5;
+        let MIN_LAUNCH_SPEED_RATIO = 0.5;
+        let LAUNCH_SPEED_MULT = 1.2;
+        let PROP_INTANGIBILITY_AFTER_LAUNCH = 10;
+
+        let kat_to_prop_lateral_unit = vec3_unit_xz!(&vec3_from!(-, prop.pos, self.center));
+        let prop_speed =
+            LAUNCH_SPEED_MULT * max!(self.speed, self.max_forwards_speed * MIN_LAUNCH_SPEED_RATIO);
+
+        let mut prop_init_vel = [
+            LAUNCH_ANGLE.cos() * kat_to_prop_lateral_unit[0],
+            LAUNCH_ANGLE.sin(),
+            LAUNCH_ANGLE.cos() * kat_to_prop_lateral_unit[2],
+        ];
+        vec3_inplace_scale(&mut prop_init_vel, prop_speed);
+
+        prop.apply_trajectory(
+            &prop_init_vel,
+            PropTrajectoryType::HitAirborne,
+            self.airborne_prop_gravity,
+            global,
+        );
+        prop.intangible_timer = self.tick_rate.duration(PROP_INTANGIBILITY_AFTER_LAUNCH);
+    }
+
+    /// (??) Eject the next drink out of the front of the vending machine `machine` after the
+    /// katamari bonked it. The drinks are assumed to be the props linked to the machine as its
+    /// children, which are ejected in the order they were linked.
+    fn eject_vending_machine_drink(&self, machine: &Prop, global: &mut GlobalState) {
+        // TODO_PARAM
+        let EJECT_LATERAL_SPEED = 5.0;
+        let EJECT_UP_SPEED = 3.0;
+
+        let machine_ctrl_idx = machine.get_ctrl_idx();
+        let drink_ref = machine.get_child_refs().into_iter().find(|child_ref| {
+            let child = child_ref.borrow();
+            child.parent == Some(machine_ctrl_idx) && !child.is_attached() && !child.is_disabled()
+        });
+        let Some(drink_ref) = drink_ref else {
+            return;
+        };
+
+        let forward = [
+            machine.rotation_mat[8],
+            machine.rotation_mat[9],
+            machine.rotation_mat[10],
+        ];
+        let mut init_vel = vec3_unit_xz!(forward);
+        vec3_inplace_scale(&mut init_vel, EJECT_LATERAL_SPEED);
+        init_vel[1] = EJECT_UP_SPEED;
+
+        let mut drink = drink_ref.borrow_mut();
+        drink.detach_from_parent();
+        drink.apply_trajectory(
+            &init_vel,
+            PropTrajectoryType::VendingMachine,
+            self.airborne_prop_gravity,
+            global,
+        );
     }
 
     /// Update the katamari's vault and climbing state.
@@ -1818,7 +1959,7 @@ impl Katamari {
                     if _was_climbing {
                         // if a wallclimb was ongoing, initiate a cooldown for the next one
                         // and reset the wallclimb duration
-                        self.wallclimb_cooldown_timer = 10;
+                        self.wallclimb_cooldown_timer = self.tick_rate.duration(10);
                         self.climb_ticks = 0;
                     }
                     self.physics_flags.climbing = false;
@@ -1958,7 +2099,7 @@ impl Katamari {
                 self.is_climbing_0x898 -= 1;
             } else {
                 if _was_climbing {
-                    self.wallclimb_cooldown_timer = 10;
+                    self.wallclimb_cooldown_timer = self.tick_rate.duration(10);
                     self.climb_ticks = 0;
                 }
 
@@ -2016,7 +2157,8 @@ impl Katamari {
         let contacts_wall = self.num_wall_contacts > 0;
         let should_halve_speed = fast_collision && contacts_wall;
         let can_bonk_and_lose_props = !fast_collision || should_halve_speed;
-        let landed_from_fast_fall = !(fast_collision && !contacts_wall && self.falling_ticks < 10);
+        let landed_from_fast_fall =
+            !(fast_collision && !contacts_wall && self.falling_ticks < self.tick_rate.duration(10));
         let flag_d_false_in_1p = false;
 
         let mut surface_normal_unit = [0.0; 3];
@@ -2083,7 +2225,8 @@ impl Katamari {
             }
 
             let check_a = self.physics_flags.contacts_floor && !fast_collision;
-            let check_b = self.physics_flags.wheel_spin || self.airborne_ticks < 5;
+            let check_b =
+                self.physics_flags.wheel_spin || self.airborne_ticks < self.tick_rate.duration(5);
             if check_a && check_b {
                 set_y!(self.velocity.vel_accel, 0.0);
                 vec3::zero(&mut self.init_bonk_velocity);
@@ -2106,7 +2249,8 @@ impl Katamari {
             // TODO_PARAM
             let magic_num_0x7b264 = 70.0;
             let magic_num_0x71580 = 0.1;
-            let falling_tick_ratio = self.falling_ticks as f32 / magic_num_0x7b264;
+            let falling_tick_ratio =
+                self.falling_ticks as f32 / self.tick_rate.duration(magic_num_0x7b264);
             if landed_from_fast_fall {
                 // TODO_VIBRATION: `kat_update_wall_contacts:218-220` (vibration)
                 if !self.physics_flags.contacts_wall
@@ -2465,7 +2609,9 @@ impl Katamari {
 
         if self.physics_flags.at_max_climb_height {
             self.climb_max_height_duration += 1;
-            if self.climb_max_height_duration > MAX_FRAMES_AT_MAX_WALLCLIMB_HEIGHT {
+            if self.climb_max_height_duration
+                > self.tick_rate.duration(MAX_FRAMES_AT_MAX_WALLCLIMB_HEIGHT)
+            {
                 self.end_wall_climb();
             }
             self.physics_flags.at_max_climb_height = true;
@@ -2476,7 +2622,9 @@ impl Katamari {
 
         if !self.hit_flags.small_ledge_climb && self.max_wallclimb_height_gain <= height_gain {
             self.climb_max_height_duration += 1;
-            if self.climb_max_height_duration > MAX_FRAMES_AT_MAX_WALLCLIMB_HEIGHT {
+            if self.climb_max_height_duration
+                > self.tick_rate.duration(MAX_FRAMES_AT_MAX_WALLCLIMB_HEIGHT)
+            {
                 self.end_wall_climb();
             }
             self.physics_flags.at_max_climb_height = true;
@@ -2485,24 +2633,25 @@ impl Katamari {
 
         self.climb_ticks += 1;
 
-        if self.climb_ticks == WALLCLIMB_VFX_DELAY_FRAMES {
+        if self.climb_ticks == self.tick_rate.duration(WALLCLIMB_VFX_DELAY_FRAMES) {
             static VFX_DIR: Vec3 = [0.0, 0.0, 0.0];
 
             self.play_vfx(VfxId::Climb, &self.center, &VFX_DIR, self.diam_cm, -1, 0);
         }
 
         let max_wallclimb_speed = self.diam_cm * MAX_WALLCLIMB_SPEED_DIAMS;
-        self.climb_speed += WALLCLIMB_ACCEL;
+        self.climb_speed += self.tick_rate.per_tick(WALLCLIMB_ACCEL);
         self.climb_speed = min!(self.climb_speed, max_wallclimb_speed);
+        let climb_step = self.tick_rate.per_tick(self.climb_speed);
 
         let delta_y = if !self.hit_flags.small_ledge_climb
-            && self.max_wallclimb_height_gain < height_gain + self.climb_speed
+            && self.max_wallclimb_height_gain < height_gain + climb_step
         {
             self.climb_max_height_duration = 0;
             self.physics_flags.at_max_climb_height = true;
             self.max_wallclimb_height_gain - height_gain
         } else {
-            self.climb_speed
+            climb_step
         };
 
         self.center[1] += delta_y;
@@ -2516,7 +2665,7 @@ impl Katamari {
                 return self.is_climbing_0x898 -= 1;
             }
             self.climb_ticks = 0;
-            self.wallclimb_cooldown_timer = 10;
+            self.wallclimb_cooldown_timer = self.tick_rate.duration(10);
         }
 
         self.physics_flags.climbing = false;
@@ -2533,8 +2682,10 @@ impl Katamari {
                 // if contacting a floor, interpolate the number of ticks the katamari has been falling
                 return inv_lerp_clamp!(
                     self.falling_ticks as f32,
-                    self.params.min_impact_falling_frames as f32,
-                    self.params.max_impact_falling_frames as f32
+                    self.tick_rate
+                        .duration(self.params.min_impact_falling_frames as f32),
+                    self.tick_rate
+                        .duration(self.params.max_impact_falling_frames as f32)
                 );
             }
 
@@ -5,7 +5,7 @@ use gl_matrix::{
 
 use crate::{
     collision::mesh::Mesh,
-    debug::DEBUG_CONFIG,
+    debug::debug_config,
     macros::{max, min, vec3_from},
     util::color,
 };
@@ -43,7 +43,7 @@ impl Katamari {
     /// Use the `debug_draw_line` delegate to draw the katamari's collision rays on the screen.
     #[rustfmt::skip]
     pub fn debug_draw_collision_rays(&self) {
-        if !DEBUG_CONFIG.kat_draw_collision_rays { return; }
+        if !debug_config().kat_draw_collision_rays { return; }
 
         if let Some(delegates) = &self.delegates {
             let mut my_delegates = delegates.borrow_mut();
@@ -101,7 +101,7 @@ impl Katamari {
         shell_initial_pts: &[Vec3; 5],
         shell_final_pts: &[Vec3; 5],
     ) {
-        if !DEBUG_CONFIG.kat_draw_shell_rays { return; }
+        if !debug_config().kat_draw_shell_rays { return; }
 
         let SHELL_RAY_COLOR = [0.0, 1.0, 1.0, 1.0];
 
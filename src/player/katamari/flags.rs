@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     collision::hit_attribute::HitAttribute,
-    debug::DEBUG_CONFIG,
+    debug::debug_config,
     macros::{debug_log, panic_log},
 };
 
@@ -296,7 +296,7 @@ impl KatHitFlags {
     /// Turn on flags applicable to the given hit attribute `attr`.
     /// offset: 0x16d10
     pub fn apply_hit_attr(&mut self, attr: HitAttribute) {
-        if DEBUG_CONFIG.log_nonzero_hit_attribute_hits && attr != HitAttribute::None {
+        if debug_config().log_nonzero_hit_attribute_hits && attr != HitAttribute::None {
             debug_log!("  contacted hit attribute: {attr:?}");
         }
 
@@ -5,6 +5,7 @@ mod params;
 pub mod scaled_params;
 pub mod spline;
 mod velocity;
+pub mod vs;
 
 use std::{cell::RefCell, rc::Rc};
 
@@ -21,7 +22,7 @@ use crate::{
         VEC3_X_NEG, VEC3_Y_POS, VEC3_ZERO,
     },
     delegates::{has_delegates::HasDelegates, Delegates, DelegatesRef},
-    global::GlobalState,
+    global::{tick_rate::TickRate, GlobalState},
     macros::{inv_lerp, mark_address, min, panic_log, set_translation, vec3_from},
     math::{
         normalize_bounded_angle, vec3_inplace_add_scaled, vec3_inplace_add_vec,
@@ -81,6 +82,9 @@ pub struct Katamari {
     /// mostly static constants.
     params: KatamariParams,
 
+    /// The rate at which the simulation is ticked (a copy of `GlobalState::tick_rate`).
+    tick_rate: TickRate,
+
     /// A static flag to tell to turn on the "map semi translucent" hit flag.
     /// offset: 0x10eacd
     has_map_semi_translucent_hit: bool,
@@ -741,14 +745,19 @@ pub struct Katamari {
     /// offset: 0x3a84
     spin_rotation_mat: Mat4,
 
-    /// (??) vs mode value. returned from `KataVsGet_CatchCount` API function.
+    /// The number of props this katamari has collected in vs mode.
+    /// Returned from `KataVsGet_CatchCount` API function.
     /// offset: 0x3ad4
     pub vs_catch_count: i16,
 
-    /// (??) vs mode value. returned from `KataVsGet_AttackCount` API function.
+    /// The number of times this katamari has attacked the other katamari in vs mode.
+    /// Returned from `KataVsGet_AttackCount` API function.
     /// offset: 0x3ad6
     pub vs_attack_count: i16,
 
+    /// The diameter of the other katamari in vs mode (or 0 outside of vs mode).
+    vs_opponent_diam_cm: f32,
+
     /// True if the player is in the "look L1" state.
     /// offset: 0x3b38
     is_look_l1: bool,
@@ -811,7 +820,11 @@ impl Katamari {
     }
 
     pub fn get_diam_m(&self) -> f32 {
-        self.diam_cm * 100.0
+        self.diam_m
+    }
+
+    pub fn get_attached_prop_alpha(&self) -> f32 {
+        self.attached_prop_alpha
     }
 
     pub fn get_vol(&self) -> f32 {
@@ -964,6 +977,14 @@ impl Katamari {
         &self.rotation_mat
     }
 
+    pub fn get_velocity(&self) -> &Vec3 {
+        &self.velocity.vel
+    }
+
+    pub fn get_attached_prop_ctrl_indices(&self) -> &[u16] {
+        &self.attached_prop_ctrl_indices
+    }
+
     pub fn get_transform(&self) -> &Mat4 {
         &self.transform
     }
@@ -1045,7 +1066,9 @@ impl Katamari {
         self.is_climbing_0x898 = 0;
         if self.physics_flags.climbing {
             self.climb_ticks = 0;
-            self.wallclimb_cooldown_timer = self.params.init_wallclimb_cooldown_timer;
+            self.wallclimb_cooldown_timer = self
+                .tick_rate
+                .duration(self.params.init_wallclimb_cooldown_timer);
         }
 
         self.physics_flags.climbing = false;
@@ -1066,6 +1089,21 @@ impl Katamari {
         self.params.backwards_speed_mult = backw;
         self.params.boost_speed_mult = boost;
     }
+
+    pub fn set_tick_rate(&mut self, tick_rate: TickRate) {
+        self.tick_rate = tick_rate;
+    }
+
+    /// Reset the katamari to its state before `init`, keeping its params (which unity can
+    /// change with `SetKatamariSpeed`) and the tick rate.
+    pub fn reset_keeping_params(&mut self) {
+        let params = std::mem::take(&mut self.params);
+        *self = Katamari {
+            params,
+            tick_rate: self.tick_rate,
+            ..Default::default()
+        };
+    }
 }
 
 impl Katamari {
@@ -1117,12 +1155,13 @@ impl Katamari {
             // if the katamari is spinning:
             self.spin_ticks += 1;
             self.boost_speed = min!(
-                self.boost_speed + self.scaled_params.boost_accel,
+                self.boost_speed + self.tick_rate.per_tick(self.scaled_params.boost_accel),
                 self.scaled_params.base_max_speed * self.scaled_params.max_boost_speed
             );
         };
 
-        let spin_rotation = normalize_bounded_angle(self.boost_speed / self.radius_cm);
+        let spin_rotation =
+            normalize_bounded_angle(self.tick_rate.per_tick(self.boost_speed / self.radius_cm));
         mat4::from_rotation(
             &mut self.spin_rotation_mat,
             spin_rotation,
@@ -1199,9 +1238,8 @@ impl Katamari {
     /// Update the katamari's scaled params by interpolating the mission's param control points.
     /// offset: 0x1f980
     pub fn update_scaled_params(&mut self, mission_config: &MissionConfig) {
-        // TODO_VS: in vs mode, the smaller of the two katamaris takes its params from
-        // the bigger katamari, so that would need to be handled differently.
-        mission_config.get_kat_scaled_params(&mut self.scaled_params, self.diam_cm);
+        let params_diam_cm = self.scaled_params_diam_cm();
+        mission_config.get_kat_scaled_params(&mut self.scaled_params, params_diam_cm);
         self.gravity = self.scaled_params.accel_grav;
         // TODO_VS: conditionally double gravity in vs mode
     }
@@ -1239,7 +1277,7 @@ impl Katamari {
         if vec3::length(&self.rotation_axis_unit) > 0.0 {
             mat4::from_rotation(
                 &mut spin_rotation_mat,
-                self.rotation_speed,
+                self.tick_rate.per_tick(self.rotation_speed),
                 &self.rotation_axis_unit,
             );
         }
@@ -1265,7 +1303,7 @@ impl Katamari {
         self.update_rotation_speed(&vel_accel);
 
         let rot_dist = if !self.physics_flags.wheel_spin {
-            self.speed
+            self.tick_rate.per_tick(self.speed)
         } else {
             // TODO_LOW: `kat_apply_pitch_when_spinning()` (this seems to be unused)
             0.0
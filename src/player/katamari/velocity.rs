@@ -228,7 +228,7 @@ impl Katamari {
                                 // if the player isn't moving, start downhill acceleration
                                 // along the incline.
                                 self.physics_flags.immobile = false;
-                                self.move_downhill_ticks = 10;
+                                self.move_downhill_ticks = self.tick_rate.duration(10);
                                 KatInclineMoveType::Downhill
                             };
 
@@ -259,8 +259,11 @@ impl Katamari {
                                     self.scaled_params.push_uphill_accel
                                 };
 
+                                let easein_duration = self
+                                    .tick_rate
+                                    .duration(self.params.uphill_accel_easein_duration);
                                 let easein_accel = (self.move_uphill_ticks as f32
-                                    / self.params.uphill_accel_easein_duration)
+                                    / easein_duration)
                                     .clamp(0.0, 1.0);
                                 let incline_mult =
                                     easein_accel * (self.diam_cm / 50.0) * incline_base_accel;
@@ -275,8 +278,11 @@ impl Katamari {
                                 self.move_downhill_ticks += 1;
                                 self.move_uphill_ticks = 0;
 
+                                let easein_duration = self
+                                    .tick_rate
+                                    .duration(self.params.downhill_accel_easein_duration);
                                 let easein_accel = (self.move_downhill_ticks as f32
-                                    / self.params.downhill_accel_easein_duration)
+                                    / easein_duration)
                                     .clamp(0.0, 1.0);
                                 let incline_mult = (self.scaled_params.not_push_uphill_accel
                                     * self.diam_cm)
@@ -305,7 +311,7 @@ impl Katamari {
             } else {
                 // if not wallclimbing and airborne:
                 // apply gravity acceleration
-                self.velocity.vel_grav[1] += self.scaled_params.accel_grav;
+                self.velocity.vel_grav[1] += self.tick_rate.per_tick(self.scaled_params.accel_grav);
                 self.end_incline_movement(prince);
             }
         } else {
@@ -332,9 +338,10 @@ impl Katamari {
     ) {
         let init_vel_accel_len = vec3::length(&self.velocity.vel_accel);
 
+        let params_diam_cm = self.scaled_params_diam_cm();
         mission_state
             .mission_config
-            .get_kat_scaled_params(&mut self.scaled_params, self.diam_cm);
+            .get_kat_scaled_params(&mut self.scaled_params, params_diam_cm);
 
         if self.physics_flags.vs_mode_state == 2 {
             return;
@@ -353,12 +360,16 @@ impl Katamari {
                     if !self.physics_flags.in_water || self.boost_effect_timer > 0 {
                         prince.oujistate.dash_effect = true;
                         self.boost_effect_timer += 1;
-                        if self.boost_effect_timer > self.params.boost_build_duration {
+                        if self.boost_effect_timer
+                            > self.tick_rate.duration(self.params.boost_build_duration)
+                        {
                             self.boost_effect_state = Some(KatBoostEffectState::StopBuilding);
                         }
                         if self.physics_flags.in_water {
                             self.boost_effect_state = Some(KatBoostEffectState::Release);
-                            self.boost_effect_timer = self.params.boost_release_duration_in_water;
+                            self.boost_effect_timer = self
+                                .tick_rate
+                                .duration(self.params.boost_release_duration_in_water);
                         }
                     }
                 }
@@ -366,11 +377,14 @@ impl Katamari {
                     prince.oujistate.dash_effect = true;
                     if self.physics_flags.braking || !prince.oujistate.wheel_spin {
                         self.boost_effect_state = Some(KatBoostEffectState::Release);
-                        self.boost_effect_timer = self.params.boost_release_duration;
+                        self.boost_effect_timer =
+                            self.tick_rate.duration(self.params.boost_release_duration);
                     }
                     if self.physics_flags.in_water {
                         self.boost_effect_state = Some(KatBoostEffectState::Release);
-                        self.boost_effect_timer = self.params.boost_release_duration_in_water;
+                        self.boost_effect_timer = self
+                            .tick_rate
+                            .duration(self.params.boost_release_duration_in_water);
                     }
                 }
                 Some(KatBoostEffectState::Release) => {
@@ -438,7 +452,10 @@ impl Katamari {
             // if quick shifting or pinching:
             // rotate the `vel_accel` velocity by the angle the prince is turning
             let mut yaw_rot = [0.0; 16];
-            mat4::from_y_rotation(&mut yaw_rot, prince.get_angle_speed());
+            mat4::from_y_rotation(
+                &mut yaw_rot,
+                self.tick_rate.per_tick(prince.get_angle_speed()),
+            );
             mark_address!("0x2256d");
 
             let vel_accel = self.velocity.vel_accel;
@@ -590,6 +607,7 @@ impl Katamari {
 
         self.velocity.last_vel_accel = self.velocity.vel_accel;
 
+        let accel = self.tick_rate.per_tick_vec3(&accel);
         let mut next_velocity = vec3_from!(+, accel, self.velocity.vel_accel);
         let next_speed = vec3::len(&next_velocity);
 
@@ -643,7 +661,7 @@ impl Katamari {
             &self.velocity.vel_accel_unit,
             self.speed,
         );
-        self.sw_speed_disp_timer = SW_SPEED_DISP_DURATION;
+        self.sw_speed_disp_timer = self.tick_rate.duration(SW_SPEED_DISP_DURATION);
 
         self.play_sound_fx(SoundId::Boost, 1.0, 0);
         self.play_boost_vfx();
@@ -770,7 +788,9 @@ impl Katamari {
                     } else {
                         self.brake_vfx_timer -= 1;
                         if self.brake_vfx_timer < 1 {
-                            self.brake_vfx_timer = self.params.brake_vfx_cooldown as i16;
+                            self.brake_vfx_timer = self
+                                .tick_rate
+                                .duration(self.params.brake_vfx_cooldown as i16);
                             self.play_vfx_at_bottom(brake_vfx_id);
                         }
                     }
@@ -902,12 +922,23 @@ impl Katamari {
         vec3::zero(&mut self.bonus_vel);
 
         // start with `velocity + accel_incline`
-        let mut vel_accel = vec3_from!(+, self.velocity.vel, self.velocity.accel_incline);
+        let accel_incline = self.tick_rate.per_tick_vec3(&self.velocity.accel_incline);
+        let mut vel_accel = vec3_from!(+, self.velocity.vel, accel_incline);
 
         let speed0 = vec3::length(&vel_accel);
         if speed0 > 0.0 && !self.physics_flags.climbing {
             // if moving and not climbing a wall, apply ground friction
-            vec3_inplace_add_vec(&mut vel_accel, &self.velocity.accel_ground_friction);
+            let mut accel_ground_friction = self.velocity.accel_ground_friction;
+            if !self.tick_rate.is_base() {
+                // friction removes a ratio of the katamari's speed, so at other tick rates
+                // it compounds per tick rather than scaling linearly
+                let friction_ratio = (vec3::length(&accel_ground_friction) / speed0).min(1.0);
+                if friction_ratio > 0.0 {
+                    let tick_ratio = self.tick_rate.per_tick_ratio(friction_ratio);
+                    vec3_inplace_scale(&mut accel_ground_friction, tick_ratio / friction_ratio);
+                }
+            }
+            vec3_inplace_add_vec(&mut vel_accel, &accel_ground_friction);
         }
 
         if self.hit_flags.speed_check_off
@@ -948,15 +979,17 @@ impl Katamari {
             // TODO_VS: `kat_apply_acceleration:79-90`
             // TODO_ENDING: `kat_apply_acceleration:91-96`
             // TODO_VS: weird conditional here depending on vs mode, but it's always true in single player
+            let vel_accel = self.tick_rate.per_tick_vec3(&self.velocity.vel_accel);
             if !self.physics_flags.climbing {
                 // if not wall climbing:
-                vec3_inplace_add_vec(&mut self.center, &self.velocity.vel_accel);
-                vec3_inplace_add_vec(&mut self.center, &self.velocity.vel_grav);
+                let vel_grav = self.tick_rate.per_tick_vec3(&self.velocity.vel_grav);
+                vec3_inplace_add_vec(&mut self.center, &vel_accel);
+                vec3_inplace_add_vec(&mut self.center, &vel_grav);
             } else {
                 // if wall climbing:
                 if !self.physics_flags.at_max_climb_height {
                     // if still gaining height from the wall climb:
-                    vec3_inplace_add_vec(&mut self.center, &self.velocity.vel_accel);
+                    vec3_inplace_add_vec(&mut self.center, &vel_accel);
                 }
                 self.update_climb_position();
             }
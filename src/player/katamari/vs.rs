@@ -0,0 +1,16 @@
+use crate::macros::max;
+
+use super::Katamari;
+
+impl Katamari {
+    /// The diameter used to look up the katamari's scaled params. In vs mode, the smaller
+    /// katamari takes its params from the bigger one, so that it can keep up.
+    pub(super) fn scaled_params_diam_cm(&self) -> f32 {
+        max!(self.diam_cm, self.vs_opponent_diam_cm)
+    }
+
+    /// Record the diameter of the other katamari in vs mode.
+    pub fn set_vs_opponent_diam(&mut self, diam_cm: f32) {
+        self.vs_opponent_diam_cm = diam_cm;
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     collision::raycast_state::RaycastRef, delegates::DelegatesRef, gamestate::GameState,
-    mission::state::MissionState,
+    global::tick_rate::TickRate, mission::state::MissionState,
 };
 
 use self::{
@@ -71,6 +71,31 @@ impl Player {
         self.animation.set_delegates(delegates);
     }
 
+    /// Reinitialize the player as in `Init`, with the same initial katamari size.
+    /// The player's input and the params set by unity (e.g. with `SetKatamariSpeed`) are kept.
+    pub fn reset_attempt(
+        &mut self,
+        player: u8,
+        delegates: &DelegatesRef,
+        mission_state: &MissionState,
+        raycasts: RaycastRef,
+    ) {
+        let init_diam = self.katamari.get_init_radius() * 2.0;
+
+        self.animation = Animation::default();
+        self.camera.reset_keeping_params();
+        self.katamari.reset_keeping_params();
+        self.prince.reset_keeping_params();
+
+        self.init(player, delegates, mission_state, init_diam, raycasts);
+    }
+
+    pub fn set_tick_rate(&mut self, tick_rate: TickRate) {
+        self.katamari.set_tick_rate(tick_rate);
+        self.prince.set_tick_rate(tick_rate);
+        self.camera.set_tick_rate(tick_rate);
+    }
+
     pub fn update_camera(&mut self, mission_state: &MissionState) {
         self.camera
             .update(&self.prince, &mut self.katamari, mission_state, &self.input);
@@ -96,16 +121,17 @@ impl Player {
         }
 
         // only run a royal warp if the stage has royal warp destinations
-        let dest = mission_state
+        // TODO_VS: in vs mode, the two players have different royal warp destinations
+        let Some(dest) = mission_state
             .stage_config
-            .get_royal_warp_dest(area as usize);
-        if dest.is_none() {
+            .get_royal_warp_dest(area as usize)
+        else {
             return;
-        }
+        };
 
         // update the warped player's katamari, prince, and camera.
-        katamari.update_royal_warp(&dest.unwrap().kat_pos, mission_state);
-        prince.update_royal_warp(katamari, camera, dest.unwrap().prince_angle);
+        katamari.update_royal_warp(&dest.kat_pos, mission_state);
+        prince.update_royal_warp(katamari, camera, dest.prince_angle);
         camera.reset_state(katamari, prince);
 
         // TODO_VS: call `vs_volume_diff_callback` delegate
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     constants::{UNITY_TO_SIM_SCALE, VEC3_ZERO},
     delegates::{has_delegates::HasDelegates, sound_id::SoundId, DelegatesRef},
+    global::tick_rate::TickRate,
     macros::{inv_lerp, inv_lerp_clamp, lerp, mark_address, max, min, panic_log, set_y},
     math::{
         acos_f32, change_bounded_angle, normalize_bounded_angle, vec3_inplace_add_vec,
@@ -189,6 +190,9 @@ impl Default for PrinceTurnType {
 pub struct Prince {
     params: PrinceParams,
 
+    /// The rate at which the simulation is ticked (a copy of `GlobalState::tick_rate`).
+    tick_rate: TickRate,
+
     #[serde(skip)]
     delegates: Option<DelegatesRef>,
 
@@ -548,6 +552,21 @@ impl Prince {
         self.params.global_turn_speed_mult = value;
     }
 
+    pub fn set_tick_rate(&mut self, tick_rate: TickRate) {
+        self.tick_rate = tick_rate;
+    }
+
+    /// Reset the prince to its state before `init`, keeping its params (which unity can
+    /// change with `SetKatamariSpeed`) and the tick rate.
+    pub fn reset_keeping_params(&mut self) {
+        let params = std::mem::take(&mut self.params);
+        *self = Prince {
+            params,
+            tick_rate: self.tick_rate,
+            ..Default::default()
+        };
+    }
+
     pub fn get_pos(&self) -> &Vec3 {
         &self.pos
     }
@@ -652,7 +671,7 @@ impl Prince {
     }
 
     pub fn decrease_push_uphill_strength(&mut self, t: f32) {
-        self.push_uphill_strength -= t * self.uphill_strength_loss;
+        self.push_uphill_strength -= self.tick_rate.per_tick(t * self.uphill_strength_loss);
         if self.push_uphill_strength < 0.0 {
             self.push_uphill_strength = 0.0;
         }
@@ -717,7 +736,7 @@ impl Prince {
 
         self.update_transform(kat, camera);
 
-        self.boost_energy = self.boost_max_energy;
+        self.boost_energy = self.tick_rate.duration(self.boost_max_energy);
         self.push_uphill_strength = self.max_push_uphill_strength;
         self.view_mode = PrinceViewMode::Normal;
         self.ignore_input_timer = 0;
@@ -888,7 +907,7 @@ impl Prince {
             0.0
         } else {
             self.huff_timer_0x486 -= 1;
-            self.huff_timer_0x486 as f32 / self.huff_duration_0x2f4 as f32
+            self.huff_timer_0x486 as f32 / self.tick_rate.duration(self.huff_duration_0x2f4) as f32
         };
 
         let is_huffing = self.huff_timer_0x486 != 0;
@@ -941,12 +960,12 @@ impl Prince {
         if !self.oujistate.dash {
             // if we aren't dashing, increment `no_dash_ticks`:
             self.no_dash_ticks += 1;
-            if self.no_dash_ticks >= self.boost_recharge_frequency {
+            if self.no_dash_ticks >= self.tick_rate.duration(self.boost_recharge_frequency) {
                 // if we haven't spun for long enough to recharge, do the recharge:
                 self.no_dash_ticks = 0;
                 self.boost_energy = max!(
-                    self.boost_energy + self.boost_recharge,
-                    self.boost_max_energy
+                    self.boost_energy + self.tick_rate.duration(self.boost_recharge),
+                    self.tick_rate.duration(self.boost_max_energy)
                 );
             }
         } else {
@@ -1067,8 +1086,8 @@ impl Prince {
                 self.boost_energy -= 1;
                 if self.boost_energy == 0 {
                     self.reset_boost_state(katamari);
-                    self.huff_timer_0x486 = self.huff_duration_0x2f4;
-                    self.huff_timer_0x480 = self.huff_duration_0x2e8;
+                    self.huff_timer_0x486 = self.tick_rate.duration(self.huff_duration_0x2f4);
+                    self.huff_timer_0x480 = self.tick_rate.duration(self.huff_duration_0x2e8);
                     self.is_huffing_0x482 = true;
                     self.is_huffing_0x9d = true;
                     return;
@@ -1106,7 +1125,7 @@ impl Prince {
 
         if did_next_gacha {
             self.last_gacha_direction = new_gacha_direction;
-            self.gacha_window_timer = self.gacha_window_duration;
+            self.gacha_window_timer = self.tick_rate.duration(self.gacha_window_duration);
             self.gacha_count += 1;
         }
 
@@ -1176,7 +1195,7 @@ impl Prince {
     /// offset: 0x56650
     fn reset_boost_state(&mut self, katamari: &mut Katamari) {
         self.end_spin_and_boost(katamari);
-        self.boost_energy = self.boost_max_energy;
+        self.boost_energy = self.tick_rate.duration(self.boost_max_energy);
         self.is_huffing_0x9d = false;
         self.huff_timer_0x480 = 0;
         self.is_huffing_0x482 = false;
@@ -1199,6 +1218,12 @@ impl Prince {
         }
     }
 
+    /// Turn the prince around the katamari, where `angle_speed` is the angle turned over a
+    /// 30 Hz frame.
+    fn turn(&mut self, angle_speed: f32) {
+        change_bounded_angle(&mut self.angle, self.tick_rate.per_tick(angle_speed));
+    }
+
     /// Update the prince's angle around the katamari.
     /// If the katamari is performing a tutorial move, that move is returned.
     /// offset: 0x55b70
@@ -1234,14 +1259,14 @@ impl Prince {
                 self.turn_type = PrinceTurnType::RightStickUp;
                 self.angle_speed =
                     -self.one_stick_up_turn_speed * inv_lerp!(self.input_rs_abs.y(), min_push, 1.0);
-                change_bounded_angle(&mut self.angle, self.angle_speed);
+                self.turn(self.angle_speed);
             } else {
                 // case 3 (left stick neutral, right stick down)
                 self.turn_type = PrinceTurnType::RightStickDown;
                 self.angle_speed = self.one_stick_down_turn_speed
                     * 0.7
                     * inv_lerp!(self.input_rs_abs.y(), min_push, 1.0);
-                change_bounded_angle(&mut self.angle, self.angle_speed);
+                self.turn(self.angle_speed);
             }
         } else if self.input_rs_len == 0.0 {
             if self.input_ls.y() > 0.0 {
@@ -1249,14 +1274,14 @@ impl Prince {
                 self.turn_type = PrinceTurnType::LeftStickUp;
                 self.angle_speed =
                     self.one_stick_up_turn_speed * inv_lerp!(self.input_ls_abs.y(), min_push, 1.0);
-                change_bounded_angle(&mut self.angle, self.angle_speed);
+                self.turn(self.angle_speed);
             } else {
                 // case 5 (right stick neutral, left stick down)
                 self.turn_type = PrinceTurnType::LeftStickDown;
                 self.angle_speed = -self.one_stick_down_turn_speed
                     * 0.7
                     * inv_lerp!(self.input_ls_abs.y(), min_push, 1.0);
-                change_bounded_angle(&mut self.angle, self.angle_speed);
+                self.turn(self.angle_speed);
             }
         } else {
             // case 6 (neither stick neutral)
@@ -1347,7 +1372,7 @@ impl Prince {
 
         self.is_quick_shifting = true;
         self.angle_speed = base_turn_speed * self.quick_shift_turn_speed;
-        change_bounded_angle(&mut self.angle, global_speed_mult * self.angle_speed);
+        self.turn(global_speed_mult * self.angle_speed);
     }
 
     /// Update the prince's angle around the katamari when pushing (i.e.
@@ -1400,7 +1425,7 @@ impl Prince {
 
         // compute angle speed from the above two speed components, then update the angle
         self.angle_speed = 0.5 * base_angle_speed * (push_angle_speed + stick_mag_speed);
-        change_bounded_angle(&mut self.angle, global_speed_mult * self.angle_speed);
+        self.turn(global_speed_mult * self.angle_speed);
 
         // compute the push direction and push strength
         if push_angle <= forw_max {
@@ -1602,7 +1627,7 @@ impl Prince {
         kat_bottom: &Vec3,
     ) {
         self.angle = normalize_bounded_angle(self.angle);
-        self.angle += self.extra_flat_angle_speed;
+        self.angle += self.tick_rate.per_tick(self.extra_flat_angle_speed);
         self.angle = normalize_bounded_angle(self.angle);
 
         let id = mat4::create();
@@ -1644,7 +1669,8 @@ impl Player {
 
         prince.last_oujistate = prince.oujistate;
 
-        prince.flip_duration = stage_config.get_flip_duration(katamari.get_diam_cm()) as u16;
+        prince.flip_duration =
+            stage_config.get_flip_duration(katamari.get_diam_cm(), &prince.tick_rate) as u16;
 
         prince.read_input(input, mission_state, katamari, camera);
         prince.update_huff();
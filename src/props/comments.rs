@@ -1,15 +1,40 @@
-use crate::macros::panic_log;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
-#[derive(Debug, Default)]
+use serde::{Deserialize, Serialize};
+
+use crate::{global::tick_rate::TickRate, macros::panic_log};
+
+/// The number of frames after a comment is requested before the next one can be requested.
+/// This isn't from the original simulation (which leaves comments to unity): it only spaces
+/// out the requests made when `Delegates::request_king_comments` is set.
+/// TODO_PARAM
+const COMMENT_COOLDOWN_FRAMES: u16 = 90;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct KingCommentState {
     /// The number of props in each comment group, indexed by their `comment_group_id`.
     /// offset: 0x155250
     pub group_sizes: Vec<u16>,
 
-    /// The number of props in each comment group that are attached to the katamari,
+    /// The number of props in each comment group that are attached to a katamari,
     /// indexed by their `comment_group_id`.
     /// offset: 0x1530bc
     pub group_attached_counts: Vec<u16>,
+
+    /// The comment group of each prop that's attached to a katamari, by control index.
+    attached_props: BTreeMap<u16, Option<u16>>,
+
+    /// The control indices of the props whose comments have been requested, and the comment
+    /// groups whose comments have been requested, so that no comment is requested twice.
+    commented_props: BTreeSet<u16>,
+    commented_groups: BTreeSet<u16>,
+
+    /// The control indices of the props whose comments are waiting for the cooldown to end,
+    /// in the order they were collected.
+    pending: VecDeque<u16>,
+
+    /// The number of ticks before the next comment can be requested.
+    cooldown_ticks: u16,
 }
 
 impl KingCommentState {
@@ -25,6 +50,12 @@ impl KingCommentState {
             self.group_sizes.push(0);
             self.group_attached_counts.push(0);
         }
+
+        self.attached_props.clear();
+        self.commented_props.clear();
+        self.commented_groups.clear();
+        self.pending.clear();
+        self.cooldown_ticks = 0;
     }
 
     /// Add an item to the group `group_idx`.
@@ -37,4 +68,112 @@ impl KingCommentState {
 
         self.group_sizes[group_idx] += 1;
     }
+
+    /// Record that the prop `ctrl_idx` was attached to a katamari. A comment is requested for
+    /// it if it has its own comment (`comment_id`), or if it completes its comment group
+    /// (`comment_group_id`), unless that comment was already requested.
+    /// (Unity looks up which comment to play from the prop's control index.)
+    pub fn on_prop_attached(
+        &mut self,
+        ctrl_idx: u16,
+        comment_id: Option<u16>,
+        comment_group_id: Option<u16>,
+    ) {
+        if self
+            .attached_props
+            .insert(ctrl_idx, comment_group_id)
+            .is_some()
+        {
+            return;
+        }
+
+        let mut completed_group = false;
+        if let Some(group_id) = comment_group_id {
+            let group_idx = group_id as usize;
+            if group_idx < self.group_attached_counts.len() {
+                self.group_attached_counts[group_idx] += 1;
+                completed_group = self.group_attached_counts[group_idx]
+                    == self.group_sizes[group_idx]
+                    && self.commented_groups.insert(group_id);
+            }
+        }
+
+        let prop_comment = comment_id.is_some() && self.commented_props.insert(ctrl_idx);
+        if prop_comment || completed_group {
+            self.pending.push_back(ctrl_idx);
+        }
+    }
+
+    /// Stop counting the props which are no longer attached to a katamari (according to
+    /// `is_attached`) towards their comment groups.
+    pub fn remove_detached_props(&mut self, is_attached: impl Fn(u16) -> bool) {
+        let group_attached_counts = &mut self.group_attached_counts;
+        self.attached_props.retain(|&ctrl_idx, group_id| {
+            if is_attached(ctrl_idx) {
+                return true;
+            }
+
+            if let Some(count) = group_id.and_then(|id| group_attached_counts.get_mut(id as usize))
+            {
+                *count = count.saturating_sub(1);
+            }
+            false
+        });
+    }
+
+    /// Count down the comment cooldown. Once it's over, returns the control index of the
+    /// next prop whose comment should be requested (and starts the cooldown again).
+    pub fn next_request(&mut self, tick_rate: &TickRate) -> Option<u16> {
+        if self.cooldown_ticks > 0 {
+            self.cooldown_ticks -= 1;
+            return None;
+        }
+
+        let ctrl_idx = self.pending.pop_front()?;
+        self.cooldown_ticks = tick_rate.duration(COMMENT_COOLDOWN_FRAMES);
+        Some(ctrl_idx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::global::tick_rate::TickRate;
+
+    use super::{KingCommentState, COMMENT_COOLDOWN_FRAMES};
+
+    #[test]
+    fn test_comment_requests() {
+        let mut comments = KingCommentState::default();
+        comments.reset();
+
+        // a comment group of two props, one of which also has its own comment
+        comments.add_to_group(3);
+        comments.add_to_group(3);
+
+        comments.on_prop_attached(7, None, Some(3));
+        assert!(comments.pending.is_empty());
+        comments.on_prop_attached(8, Some(12), Some(3));
+        comments.on_prop_attached(9, Some(13), None);
+
+        // collecting a prop again doesn't request its comment again
+        comments.on_prop_attached(9, Some(13), None);
+        assert_eq!(comments.group_attached_counts[3], 2);
+
+        // a prop knocked off of the katamari no longer counts towards its group, and completing
+        // the group again doesn't request its comment again
+        comments.remove_detached_props(|ctrl_idx| ctrl_idx != 7);
+        assert_eq!(comments.group_attached_counts[3], 1);
+        comments.on_prop_attached(7, None, Some(3));
+        assert_eq!(comments.group_attached_counts[3], 2);
+
+        let tick_rate = TickRate::default();
+        assert_eq!(comments.next_request(&tick_rate), Some(8));
+
+        // the next request waits for the cooldown to end
+        for _ in 0..COMMENT_COOLDOWN_FRAMES {
+            assert_eq!(comments.next_request(&tick_rate), None);
+        }
+        assert_eq!(comments.next_request(&tick_rate), Some(9));
+        assert_eq!(comments.pending.len(), 0);
+    }
 }
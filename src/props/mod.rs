@@ -1,4 +1,4 @@
-use std::slice;
+use std::{collections::BTreeMap, rc::Rc, slice};
 
 use gl_matrix::common::Mat4;
 use serde::{Deserialize, Serialize};
@@ -8,9 +8,10 @@ use crate::{
     constants::ZERO,
     delegates::{has_delegates::HasDelegates, DelegatesRef},
     global::GlobalState,
+    macros::{log, panic_log},
     mission::state::MissionState,
     mono_data::MonoData,
-    player::Player,
+    player::{katamari::Katamari, Player},
 };
 
 use self::{
@@ -32,6 +33,7 @@ pub mod motion;
 pub mod params;
 pub mod prop;
 pub mod random;
+pub mod subobject;
 
 /// State of all props in the current mission.
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -44,11 +46,8 @@ pub struct PropsState {
 
     pub gps: GlobalPathState,
 
-    /// NOTE: the simulation sort of tracks comment groups, but they aren't actually
-    /// used since unity also tracks them and doesn't query the simulation's data.
-    /// they also appear to be inaccurately tracked.
-    /// so this field isn't used in this simulation at present.
-    #[serde(skip)]
+    /// NOTE: unity also tracks comment groups and plays the king's comments itself, so the
+    /// comments are only requested from unity when `Delegates::request_king_comments` is set.
     pub comments: KingCommentState,
 
     pub random: RandomPropsState,
@@ -63,6 +62,11 @@ pub struct PropsState {
 
     #[serde(skip)]
     pub raycasts: Option<RaycastRef>,
+
+    /// The encoded props state at the end of mission initialization, which is restored
+    /// by `reset_attempt`.
+    #[serde(skip)]
+    pub initial_state: Option<Rc<Vec<u8>>>,
 }
 
 impl PropsState {
@@ -81,6 +85,47 @@ impl PropsState {
         self.gps.init();
         self.random.reset();
         self.config = Some(&NAME_PROP_CONFIGS);
+        self.initial_state = None;
+    }
+
+    /// Save the state of every prop at the end of mission initialization, so that it can be
+    /// restored by `reset_attempt`.
+    pub fn save_initial_state(&mut self) {
+        let encoded = bincode::serialize(self).unwrap_or_else(|e| {
+            panic_log!("error encoding the initial props state: {e}");
+        });
+        self.initial_state = Some(Rc::new(encoded));
+    }
+
+    /// Restore every prop to its state at the end of mission initialization.
+    /// Returns `false` (without changing anything) if there's no saved initial state.
+    pub fn reset_attempt(&mut self, mono_data: &MonoData) -> bool {
+        let Some(initial_state) = &self.initial_state else {
+            return false;
+        };
+        let initial: PropsState = bincode::deserialize(initial_state).unwrap_or_else(|e| {
+            panic_log!("error decoding the initial props state: {e}");
+        });
+
+        self.props = initial.props;
+        self.prop_motions = initial.prop_motions;
+        self.gps = initial.gps;
+        self.random = initial.random;
+        self.comments = initial.comments;
+        self.params = initial.params;
+
+        // rebuild the fields that aren't encoded (as when a savestate is loaded)
+        for prop_ref in self.props.iter() {
+            let mut prop = prop_ref.borrow_mut();
+            let name_idx = prop.get_name_idx();
+            prop.init_mono_data_fields(
+                &mono_data.props[name_idx as usize],
+                NamePropConfig::get(name_idx),
+            );
+        }
+        self.hydrate_prop_links();
+
+        true
     }
 
     /// Each prop contains three fields that may be the control index of another linked prop.
@@ -191,6 +236,50 @@ impl PropsState {
         self.prop_motions.push(motion_action);
     }
 
+    /// Link each twin prop to the other prop with the same twin id, and recount the
+    /// number of twin props. Twin props without exactly one other prop with the same twin
+    /// id are left unlinked. Only called on the Gemini mission.
+    pub fn init_cache_gemini_twins(&mut self, global: &mut GlobalState) {
+        let mut twins: BTreeMap<u16, Vec<u16>> = BTreeMap::new();
+        for prop_ref in self.props.iter() {
+            let prop = prop_ref.borrow();
+            if let Some(twin_id) = prop.get_twin_id() {
+                twins.entry(twin_id).or_default().push(prop.get_ctrl_idx());
+            }
+        }
+
+        global.num_twin_props = 0;
+        for (twin_id, ctrl_indices) in twins {
+            match ctrl_indices[..] {
+                [ctrl_idx_a, ctrl_idx_b] => {
+                    global.num_twin_props += 2;
+                    self.props[ctrl_idx_a as usize]
+                        .borrow_mut()
+                        .set_twin(Some(ctrl_idx_b));
+                    self.props[ctrl_idx_b as usize]
+                        .borrow_mut()
+                        .set_twin(Some(ctrl_idx_a));
+                }
+                _ => {
+                    log!(
+                        "twin id {twin_id} has {} props, expected 2",
+                        ctrl_indices.len()
+                    );
+                    for ctrl_idx in ctrl_indices {
+                        self.props[ctrl_idx as usize].borrow_mut().set_twin(None);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Switch `prop` to its alt motion action, if it has one that's been ported.
+    pub fn start_alt_motion_action(&mut self, prop: &mut Prop) {
+        if let Some(alt_action) = MotionActionState::start_alt_action(prop) {
+            self.prop_motions[prop.get_ctrl_idx() as usize] = Some(alt_action);
+        }
+    }
+
     pub fn change_next_area(&mut self, area: u8) {
         // TODO_BUG: we can't `retain` here without ruining the property that `ctrl_idx` is an index
         // into `self.props`
@@ -248,7 +337,7 @@ impl PropsState {
     /// offset: 0x259c0
     pub fn update(
         &mut self,
-        player: &Player,
+        players: &[Player],
         mission_state: &MissionState,
         global_state: &mut GlobalState,
     ) {
@@ -263,7 +352,7 @@ impl PropsState {
         if mission_state.is_ending() {
             self.update_ending();
         } else {
-            self.update_nonending(player, mission_state, global_state);
+            self.update_nonending(players, mission_state, global_state);
         }
     }
 
@@ -271,7 +360,7 @@ impl PropsState {
     /// offset: 0x50050
     pub fn update_nonending(
         &mut self,
-        player: &Player,
+        players: &[Player],
         mission_state: &MissionState,
         global_state: &mut GlobalState,
     ) {
@@ -287,7 +376,7 @@ impl PropsState {
             let motion_action = self.prop_motions[ctrl_idx as usize].as_mut();
 
             prop.update_last_pos_and_rotation();
-            prop.update_global_state();
+            prop.update_global_state(&self.params, global_state, raycasts);
             prop.update_child_link();
             prop.update_name_index_motion(
                 motion_action,
@@ -295,13 +384,15 @@ impl PropsState {
                 mission_state,
                 global_state,
                 raycasts.clone(),
+                // TODO_VS: props only react to the first player's katamari
+                &players[0].katamari,
             );
 
             // if let Some(_script) = prop.innate_script.as_ref() {
             //     // TODO_PROP_MOTION: call `innate_script`
             // }
 
-            prop.cache_distance_to_players(player);
+            prop.cache_distance_to_players(players);
             prop.update_delta_pos();
             prop.update_transform_unattached();
         }
@@ -309,18 +400,73 @@ impl PropsState {
         // TODO: `props_update_nonending:142-` (updating global path state flags)
     }
 
+    /// Request the next pending king comment from unity (via `message_request`), once the
+    /// comment cooldown is over.
+    pub fn update_king_comments(&mut self, global: &GlobalState) {
+        let props = &self.props;
+        self.comments.remove_detached_props(|ctrl_idx| {
+            props
+                .get(ctrl_idx as usize)
+                .is_some_and(|prop| prop.borrow().is_attached())
+        });
+
+        if let Some(ctrl_idx) = self.comments.next_request(&global.tick_rate) {
+            self.message_request(ctrl_idx);
+        }
+    }
+
     /// Root function to update all props when in the `Ending` game mode.
     /// offset: 0x259f0 (note: this offset is in the middle of a function in the original simulation)
     pub fn update_ending(&mut self) {}
 
+    /// Sets the alpha of attached props from `katamari`, and shows all enabled props.
     /// offset: 0x24be0
-    pub fn update_prop_alphas(&mut self) {
-        // TODO_PROP_ALPHA
+    pub fn update_prop_alphas(&mut self, katamari: &Katamari) {
+        // TODO_PROP_ALPHA: `update_game:176-255` (fade out unattached props by distance, and
+        // destroy faded props which are too small relative to the katamari)
         for prop_ref in self.props.iter_mut() {
             let mut prop = prop_ref.borrow_mut();
-            if !prop.is_disabled() {
-                prop.set_visible(true);
+            if prop.is_disabled() {
+                continue;
             }
+
+            if prop.is_attached() {
+                prop.set_alpha(katamari.get_attached_prop_alpha());
+            }
+            prop.set_visible(true);
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::global::GlobalState;
+
+    use super::{prop::Prop, PropsState};
+
+    #[test]
+    fn test_init_gemini_twins() {
+        let mut props = PropsState::default();
+        let mut global = GlobalState::default();
+        for (ctrl_idx, twin_id) in [(0, Some(5)), (1, None), (2, Some(5)), (3, Some(6))] {
+            let mut prop = Prop::default();
+            prop.debug_set_ctrl_idx(ctrl_idx);
+            prop.debug_set_twin_id(twin_id);
+            props.props.push(Rc::new(RefCell::new(prop)));
+        }
+
+        props.init_cache_gemini_twins(&mut global);
+
+        let twin = |ctrl_idx: usize| props.props[ctrl_idx].borrow().get_twin();
+        assert_eq!(twin(0), Some(2));
+        assert_eq!(twin(1), None);
+        assert_eq!(twin(2), Some(0));
+
+        // a twin prop without another prop with the same twin id isn't a twin
+        assert_eq!(twin(3), None);
+        assert!(!props.props[3].borrow().get_has_twin());
+        assert_eq!(global.num_twin_props, 2);
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     collision::raycast_state::RaycastRef, global::GlobalState, mission::state::MissionState,
-    props::prop::Prop,
+    player::katamari::Katamari, props::prop::Prop,
 };
 
 use self::{path::FollowPath, roam::Roam, sway::SwayAction, zone_trigger::ZoneTrigger};
@@ -33,22 +33,55 @@ pub enum MotionActionState {
     // TODO_LINK: in `pmot_misc_init` the prop decides if it's `stationary` based on its root prop's
     // link action this needs to happen for all misc motions
     MiscSway(SwayAction),
+    /// An action which hasn't been ported from the original simulation yet (the prop stays still).
+    /// This is still most of them: actions 0x1, 0x5-0x15, and 0x17-0x1d (e.g. fleeing, hopping,
+    /// and flying). See `todo.txt`.
     Unimplemented(u16),
 }
 
 impl MotionActionState {
+    /// The behavior whose misc action (0x16) is sway. The only other behavior known to share
+    /// the sway prop flag is 0x15 (yaw spin) (see `PropFlags2::Motion0x40`).
+    const SWAY_BEHAVIOR: i16 = 0x20;
+
     pub fn parse_id(action_id: u16) -> Self {
         match action_id {
             0x2 => Self::FollowPath(FollowPath::default()),
             0x3 => Self::Roam(Roam::default()),
             0x4 => Self::ZoneTrigger(ZoneTrigger::default()),
-            // TODO_BUG: there are several misc actions with `action_id` 0x16. this function also needs
-            // access to the behavior id to distinguish between these misc actions.
+            // TODO_BUG: there are several misc actions with `action_id` 0x16, which the original
+            // simulation tells apart by behavior. only sway is ported, so the misc actions of the
+            // other behaviors sway too (see `is_stand_in`).
             0x16 => Self::MiscSway(SwayAction::default()),
             _ => Self::Unimplemented(action_id),
         }
     }
 
+    /// Returns `true` if the motion action `action_id` of a prop with the given `behavior`
+    /// parses to an action standing in for one that hasn't been ported yet, i.e. the misc
+    /// actions (0x16) of behaviors other than sway (0x15 (yaw spin), 0x16, 0x19, 0x1e, 0x22).
+    pub fn is_stand_in(action_id: u16, behavior: Option<i16>) -> bool {
+        action_id == 0x16 && behavior != Some(Self::SWAY_BEHAVIOR)
+    }
+
+    /// Returns `false` if this action hasn't been ported yet.
+    pub fn is_implemented(&self) -> bool {
+        !matches!(self, Self::Unimplemented(_))
+    }
+
+    /// Replace `prop`'s motion action with its alt motion action, returning the state of the
+    /// new action. Returns `None` if the prop has no alt action, or if its alt action hasn't been
+    /// ported yet (in which case the prop keeps its current action rather than freezing).
+    pub fn start_alt_action(prop: &mut Prop) -> Option<Self> {
+        let alt_action = Self::parse_id(prop.alt_motion_action?);
+        if !alt_action.is_implemented() {
+            return None;
+        }
+
+        prop.start_alt_motion_action();
+        Some(alt_action)
+    }
+
     pub fn update(
         &mut self,
         prop: &mut Prop,
@@ -56,15 +89,82 @@ impl MotionActionState {
         mission_state: &MissionState,
         global_state: &mut GlobalState,
         raycast_ref: RaycastRef,
+        katamari: &Katamari,
     ) {
         match self {
-            MotionActionState::FollowPath(follow_path) => {
-                follow_path.update(prop, gps, mission_state.mission)
+            MotionActionState::FollowPath(follow_path) => follow_path.update(
+                prop,
+                gps,
+                mission_state.mission,
+                &global_state.tick_rate,
+                katamari,
+            ),
+            MotionActionState::Roam(roam) => roam.update(prop, global_state, raycast_ref, katamari),
+            MotionActionState::ZoneTrigger(zone_trigger) => {
+                zone_trigger.update(prop, raycast_ref, katamari)
             }
-            MotionActionState::Roam(roam) => roam.update(prop, global_state, raycast_ref),
-            MotionActionState::ZoneTrigger(zone_trigger) => zone_trigger.update(prop, raycast_ref),
-            MotionActionState::MiscSway(sway) => sway.update(prop),
+            MotionActionState::MiscSway(sway) => sway.update(prop, &global_state.tick_rate),
             MotionActionState::Unimplemented(_) => {}
         }
+
+        // if the action's alt action trigger fired, switch to the alt action
+        if self.should_do_alt_action() {
+            if let Some(alt_action) = Self::start_alt_action(prop) {
+                *self = alt_action;
+            }
+        }
+    }
+
+    fn should_do_alt_action(&self) -> bool {
+        match self {
+            MotionActionState::FollowPath(follow_path) => follow_path.should_do_alt_action(),
+            MotionActionState::Roam(roam) => roam.should_do_alt_action(),
+            MotionActionState::ZoneTrigger(zone_trigger) => zone_trigger.should_do_alt_action(),
+            MotionActionState::MiscSway(sway) => sway.should_do_alt_action(),
+            MotionActionState::Unimplemented(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_start_alt_action_skips_unimplemented() {
+        // a prop whose alt action hasn't been ported keeps its current action
+        let mut prop = Prop::default();
+        prop.motion_action = Some(0x4);
+        prop.alt_motion_action = Some(0x5);
+        assert!(MotionActionState::start_alt_action(&mut prop).is_none());
+        assert_eq!(prop.motion_action, Some(0x4));
+        assert_eq!(prop.alt_motion_action, Some(0x5));
+
+        let mut prop = Prop::default();
+        prop.motion_action = Some(0x4);
+        prop.alt_motion_action = Some(0x3);
+        assert!(matches!(
+            MotionActionState::start_alt_action(&mut prop),
+            Some(MotionActionState::Roam(_))
+        ));
+        assert_eq!(prop.motion_action, Some(0x3));
+        assert_eq!(prop.alt_motion_action, None);
+    }
+
+    #[test]
+    fn test_parse_misc_action() {
+        // every misc action sways, but only behavior 0x20's sway is the real action
+        assert!(matches!(
+            MotionActionState::parse_id(0x16),
+            MotionActionState::MiscSway(_)
+        ));
+        assert!(!MotionActionState::is_stand_in(0x16, Some(0x20)));
+        assert!(MotionActionState::is_stand_in(0x16, Some(0x15)));
+        assert!(MotionActionState::is_stand_in(0x16, None));
+        assert!(!MotionActionState::is_stand_in(0x3, Some(0x15)));
+        assert!(matches!(
+            MotionActionState::parse_id(0x3),
+            MotionActionState::Roam(_)
+        ));
     }
 }
@@ -3,16 +3,16 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     constants::{PI, VEC3_Z_POS},
+    global::tick_rate::TickRate,
     macros::{vec3_from, vec3_unit_xz},
     math::{acos_f32, normalize_bounded_angle, vec3_inplace_add_vec, vec3_inplace_normalize},
     mission::Mission,
+    player::katamari::Katamari,
     props::{
         config::NamePropConfig,
         motion::{
-            data::{
-                move_types::MISSION_MOVE_TYPES,
-                prop_paths::{PathStage, PROP_PATH_DATA},
-            },
+            alt_action::AltActionTrigger,
+            data::motion_data,
             global_path::{GlobalPathFlags, GlobalPathState},
         },
         prop::{Prop, PropAnimationType, PropFlags2},
@@ -185,7 +185,14 @@ pub fn yaw_angle_to_target(forward: &Vec3, pos: &Vec3, target: &Vec3) -> f32 {
 impl FollowPath {
     /// The main update behavior for the `FollowPath` action.
     /// ofset: 0x399c0
-    pub fn update(&mut self, prop: &mut Prop, gps: &GlobalPathState, mission: Mission) {
+    pub fn update(
+        &mut self,
+        prop: &mut Prop,
+        gps: &GlobalPathState,
+        mission: Mission,
+        tick_rate: &TickRate,
+        katamari: &Katamari,
+    ) {
         if prop.get_move_type().is_none() {
             return;
         }
@@ -213,14 +220,14 @@ impl FollowPath {
             match self.state {
                 FollowPathState::Init => self.update_state_init(prop, mission),
                 FollowPathState::MoveTowardsTarget => {
-                    self.update_state_move_towards_target(prop, gps, mission)
+                    self.update_state_move_towards_target(prop, gps, mission, tick_rate)
                 }
                 FollowPathState::TurnInPlace => todo!(),
                 FollowPathState::WaitWhileFlag0x2 => todo!(),
             }
 
             prop.update_somethings_coming();
-            // TODO: check if alt action should be applied
+            self.do_alt_motion = AltActionTrigger::check(prop, katamari);
         }
     }
 
@@ -229,16 +236,18 @@ impl FollowPath {
     fn generic_init_path(&mut self, prop: &mut Prop, mission: Mission) {
         self.target_point_idx = 0;
 
-        if !PathStage::has_paths(mission) {
+        let data = motion_data();
+        if !data.paths.has_paths(mission) {
             return prop.end_motion();
         }
 
         self.path_idx =
-            MISSION_MOVE_TYPES[mission as usize][prop.move_type.unwrap() as usize].path_idx;
-        PROP_PATH_DATA.load_initial_target_point_idx(self, prop, mission);
+            data.move_types[mission as usize][prop.move_type.unwrap() as usize].path_idx;
+        data.paths
+            .load_initial_target_point_idx(self, prop, mission);
         vec3::copy(&mut self.init_pos, &prop.pos);
 
-        let failed_getting_point = PROP_PATH_DATA.get_mission_path_point(
+        let failed_getting_point = data.paths.get_mission_path_point(
             &mut self.target_point,
             mission,
             self.target_point_idx as usize,
@@ -272,7 +281,9 @@ impl FollowPath {
         prop.animation_type = PropAnimationType::MovingForward;
         vec3::copy(&mut prop.pos, &self.target_point);
 
-        let path = PROP_PATH_DATA
+        let data = motion_data();
+        let path = data
+            .paths
             .get_mission_path(mission, self.path_idx as usize)
             .unwrap();
 
@@ -282,7 +293,7 @@ impl FollowPath {
             path.speed
         };
 
-        PROP_PATH_DATA.load_next_target_point(self, prop, mission);
+        data.paths.load_next_target_point(self, prop, mission);
 
         // TODO_LOW: `pmot_current_facing_angle(prop)` is called here, but seemingly unused?
 
@@ -305,6 +316,7 @@ impl FollowPath {
         prop: &mut Prop,
         gps: &GlobalPathState,
         mission: Mission,
+        tick_rate: &TickRate,
     ) {
         if self.flags.contains(FollowPathFlags::Unk_0x2) {
             self.state = FollowPathState::WaitWhileFlag0x2;
@@ -313,7 +325,7 @@ impl FollowPath {
             self.state = FollowPathState::TurnInPlace;
             self.reversing = false;
         } else {
-            self.move_towards_target(prop, gps, mission);
+            self.move_towards_target(prop, gps, mission, tick_rate);
         }
     }
 
@@ -377,9 +389,12 @@ impl FollowPath {
         prop: &mut Prop,
         gps: &GlobalPathState,
         mission: Mission,
+        tick_rate: &TickRate,
     ) -> bool {
-        // TODO_PARAM: really multiplying by `30.0 * FRAME_TIME`
-        let mut speed = self.speed * 1.0;
+        // the original simulation multiplies by `30.0 * FRAME_TIME` here, i.e. the number of
+        // 30 Hz frames in a tick. since the yaw speed is computed from this per-tick speed,
+        // it's already per-tick and isn't scaled again in `apply_yaw_speed`.
+        let mut speed = tick_rate.per_tick(self.speed);
         if gps.get_path(self.path_idx as usize).double_speed {
             speed = speed + speed;
         }
@@ -394,7 +409,9 @@ impl FollowPath {
             vec3::copy(&mut prop.pos, &self.target_point);
 
             // load the target point after that
-            let found_next_target = PROP_PATH_DATA.load_next_target_point(self, prop, mission);
+            let found_next_target = motion_data()
+                .paths
+                .load_next_target_point(self, prop, mission);
             if !found_next_target {
                 return false;
             }
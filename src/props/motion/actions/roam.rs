@@ -13,9 +13,10 @@ use crate::{
         acos_f32, mat4_from_rotation_sim, normalize_bounded_angle, vec3_inplace_add_vec,
         vec3_inplace_normalize, vec3_inplace_scale, vec3_inplace_zero_small,
     },
+    player::katamari::Katamari,
     props::{
         config::NamePropConfig,
-        motion::actions::common::is_not_facing_target,
+        motion::{actions::common::is_not_facing_target, alt_action::AltActionTrigger},
         prop::{Prop, PropAnimationType, PropFlags2},
     },
 };
@@ -240,6 +241,7 @@ impl Roam {
         prop: &mut Prop,
         global_state: &mut GlobalState,
         raycast_ref: RaycastRef,
+        katamari: &Katamari,
     ) {
         if prop.move_type.is_some() && !prop.get_flags2().contains(PropFlags2::Wobble) {
             // core motion update depending on the motion's state
@@ -247,7 +249,7 @@ impl Roam {
                 RoamState::Init => self.update_state_init(prop, global_state, raycast_ref),
                 RoamState::Roam => self.update_state_roam(prop, global_state, raycast_ref),
                 RoamState::InitTurnInPlace => {
-                    self.update_state_init_turn_in_place(prop, raycast_ref)
+                    self.update_state_init_turn_in_place(prop, global_state, raycast_ref)
                 }
                 RoamState::TurnInPlace => self.update_state_turn_in_place(prop),
             }
@@ -255,15 +257,15 @@ impl Roam {
             prop.update_somethings_coming();
 
             if prop.get_behavior() != Some(0x1f) {
-                self.check_alt_action_trigger(prop);
+                self.check_alt_action_trigger(prop, katamari);
             }
         }
     }
 
     /// offset: 0x3c0b0
-    fn check_alt_action_trigger(&mut self, prop: &mut Prop) {
+    fn check_alt_action_trigger(&mut self, prop: &mut Prop, katamari: &Katamari) {
         if prop.alt_motion_action.is_some() {
-            // TODO_ALT
+            self.do_alt_action = AltActionTrigger::check(prop, katamari);
         }
     }
 
@@ -311,7 +313,10 @@ impl Roam {
         prop.animation_type = PropAnimationType::MovingForward;
         self.turn_state = RoamTurnState::NotTurning;
         self.last_turn_direction = None;
-        self.turn_timer = compute_rand_turn_timer(global_state.rng.get_rng1(), prop.get_ctrl_idx());
+        self.turn_timer = global_state.tick_rate.duration(compute_rand_turn_timer(
+            global_state.rng.get_rng1(),
+            prop.get_ctrl_idx(),
+        ));
 
         self.zone = raycast_ref.borrow_mut().find_zone_below_point(
             &prop.pos,
@@ -330,7 +335,7 @@ impl Roam {
 
         if prop.get_motion_action() == Some(0xf) {
             self.wait_flags = WaitFlags::CanWait;
-            self.wait_timer = 0x1e;
+            self.wait_timer = global_state.tick_rate.duration(0x1e);
         } else {
             self.wait_flags = WaitFlags::empty();
             self.wait_timer = 0;
@@ -357,7 +362,9 @@ impl Roam {
                 if self.wait_timer == 0 {
                     // If the wait timer is 0, stop waiting.
                     self.wait_flags.remove(WaitFlags::IsWaiting);
-                    self.wait_timer = compute_rand_move_duration(global_state.rng.get_rng1());
+                    self.wait_timer = global_state
+                        .tick_rate
+                        .duration(compute_rand_move_duration(global_state.rng.get_rng1()));
                     prop.animation_type = PropAnimationType::MovingForward;
                 } else {
                     // If the wait timer is nonzero, decrement it and keep waiting.
@@ -369,7 +376,9 @@ impl Roam {
                 if self.wait_timer == 0 {
                     // If the wait timer is 0, start waiting.
                     self.wait_flags.insert(WaitFlags::IsWaiting);
-                    self.wait_timer = compute_rand_wait_duration(global_state.rng.get_rng1());
+                    self.wait_timer = global_state
+                        .tick_rate
+                        .duration(compute_rand_wait_duration(global_state.rng.get_rng1()));
                     prop.animation_type = PropAnimationType::Waiting;
                     stationary = true;
                 } else {
@@ -384,7 +393,7 @@ impl Roam {
         if !stationary {
             prop.animation_type = PropAnimationType::MovingForward;
             self.update_forward_yaw(prop, global_state);
-            if self.update_forward_pos(prop, false, raycast_ref) {
+            if self.update_forward_pos(prop, false, global_state, raycast_ref) {
                 self.state = RoamState::InitTurnInPlace;
             }
         }
@@ -414,11 +423,11 @@ impl Roam {
                 match turn_dir {
                     RoamTurnDirection::Right => {
                         self.yaw_target = YAW_TARGET;
-                        self.yaw_speed = YAW_SPEED;
+                        self.yaw_speed = global_state.tick_rate.per_tick(YAW_SPEED);
                     }
                     RoamTurnDirection::Left => {
                         self.yaw_target = -YAW_TARGET;
-                        self.yaw_speed = -YAW_SPEED;
+                        self.yaw_speed = -global_state.tick_rate.per_tick(YAW_SPEED);
                     }
                 }
             }
@@ -430,8 +439,10 @@ impl Roam {
             }
             RoamTurnState::DoneTurning => {
                 self.turn_state = RoamTurnState::NotTurning;
-                self.turn_timer =
-                    compute_rand_turn_timer(global_state.rng.get_rng1(), prop.get_ctrl_idx());
+                self.turn_timer = global_state.tick_rate.duration(compute_rand_turn_timer(
+                    global_state.rng.get_rng1(),
+                    prop.get_ctrl_idx(),
+                ));
             }
         }
     }
@@ -470,6 +481,7 @@ impl Roam {
         &mut self,
         prop: &mut Prop,
         unk_flag: bool,
+        global_state: &GlobalState,
         raycast_ref: RaycastRef,
     ) -> bool {
         let bbox_max = prop.get_aabb_max();
@@ -480,7 +492,7 @@ impl Roam {
         vec3::scale(
             &mut prop.trajectory_velocity,
             &self.forward_unit,
-            self.forward_speed,
+            global_state.tick_rate.per_tick(self.forward_speed),
         );
 
         // cast a ray downwards from the prop's next position.
@@ -538,7 +550,7 @@ impl Roam {
         // or `self.zone == current_zone` from the else branch above.
         self.moving_duration += 1;
         // TODO_PARAM
-        if self.moving_duration > 0x3c {
+        if self.moving_duration > global_state.tick_rate.duration(0x3c) {
             self.just_started_moving = false;
         }
 
@@ -633,7 +645,12 @@ impl Roam {
     }
 
     /// offset: 0x3b340
-    fn update_state_init_turn_in_place(&mut self, prop: &mut Prop, raycast_ref: RaycastRef) {
+    fn update_state_init_turn_in_place(
+        &mut self,
+        prop: &mut Prop,
+        global_state: &GlobalState,
+        raycast_ref: RaycastRef,
+    ) {
         const TURN_ANGLES_DEGREES: [f32; 7] = [45.0, -45.0, 90.0, -90.0, 135.0, -135.0, 180.0];
         let mut raycast = raycast_ref.borrow_mut();
 
@@ -680,7 +697,8 @@ impl Roam {
         // TODO_PARAM
         // the number of frames it will take for the prop's yaw angle to reach its target
         const TURN_FRAMES: u32 = 12;
-        let yaw_speed = self.yaw_target / (TURN_FRAMES as f32);
+        let turn_ticks = global_state.tick_rate.duration(TURN_FRAMES);
+        let yaw_speed = self.yaw_target / (turn_ticks as f32);
 
         self.yaw_turned = 0.0;
         self.yaw_speed = yaw_speed;
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     constants::{FRAC_PI_180, FRAC_PI_45, VEC3_Y_NEG},
+    global::tick_rate::TickRate,
     macros::set_translation,
     math::{normalize_bounded_angle, vec3_inplace_scale},
     props::prop::{Prop, PropFlags2},
@@ -46,7 +47,7 @@ impl MotionAction for SwayAction {
 }
 
 impl SwayAction {
-    pub fn update(&mut self, prop: &mut Prop) {
+    pub fn update(&mut self, prop: &mut Prop, tick_rate: &TickRate) {
         if !self.initialized {
             // not initialized: do initialization (`pmot_misc_init`)
             let name_idx = prop.get_name_idx();
@@ -78,9 +79,9 @@ impl SwayAction {
             let mut progress = self.sway_progress;
 
             if !follow_parent {
-                // TODO_LOW: should be the `delta` passed to `Tick`, for some reason
                 // TODO_PARAM: 30.0 seems to be sway amplitude
-                progress += self.sway_speed * 30.0 * (1.0 / 30.0);
+                // (the original simulation multiplies by the `delta` passed to `Tick`)
+                progress += tick_rate.per_tick(self.sway_speed * 30.0 * (1.0 / 30.0));
                 progress = normalize_bounded_angle(progress);
                 self.sway_progress = progress;
             }
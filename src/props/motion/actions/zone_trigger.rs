@@ -2,7 +2,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     collision::raycast_state::RaycastRef,
-    props::prop::{Prop, PropAnimationType, PropMotionFlags},
+    player::katamari::Katamari,
+    props::{
+        motion::alt_action::AltActionTrigger,
+        prop::{Prop, PropAnimationType, PropMotionFlags},
+    },
 };
 
 use super::MotionAction;
@@ -71,10 +75,10 @@ impl MotionAction for ZoneTrigger {
 impl ZoneTrigger {
     /// The main update behavior for the `ZoneTrigger` action.
     /// offset: 0x3c230
-    pub fn update(&mut self, prop: &mut Prop, raycast_ref: RaycastRef) {
+    pub fn update(&mut self, prop: &mut Prop, raycast_ref: RaycastRef, katamari: &Katamari) {
         match self.state {
             ZoneTriggerState::Init => self.update_state_init(prop, raycast_ref),
-            ZoneTriggerState::WaitForTrigger => self.update_state_wait_for_trigger(prop),
+            ZoneTriggerState::WaitForTrigger => self.update_state_wait_for_trigger(prop, katamari),
         }
     }
 
@@ -110,14 +114,12 @@ impl ZoneTrigger {
     }
 
     /// offset: 0x3c2e0
-    fn update_state_wait_for_trigger(&mut self, prop: &mut Prop) {
+    fn update_state_wait_for_trigger(&mut self, prop: &mut Prop, katamari: &Katamari) {
         if prop.alt_motion_action.is_none() {
             return;
         }
 
-        // TODO_HIGH: call alt motion trigger based on behavior type
-        // up next: just add all alt motion triggers, indexed by behavior
-        let should_do_alt_motion = false;
+        let should_do_alt_motion = AltActionTrigger::check(prop, katamari);
 
         if should_do_alt_motion {
             self.do_alt_action = true;
@@ -6,6 +6,7 @@ use crate::{
     player::katamari::Katamari,
     props::{
         config::NamePropConfig,
+        motion::get_behavior_motion_actions,
         prop::{Prop, PropGlobalState, PropRef},
     },
 };
@@ -197,13 +198,17 @@ pub fn guard_parent_predicate(prop: &mut Prop) -> bool {
     }
 }
 
-// TODO
+// TODO: not reverse-engineered yet, so behavior 3 props never switch to alt motion.
 /// offset: 0x362b0
-pub fn behavior_3_predicate() {}
+pub fn behavior_3_predicate(_prop: &Prop, _katamari: &Katamari) -> bool {
+    false
+}
 
-// TODO
+// TODO: not reverse-engineered yet, so behavior 5 props never switch to alt motion.
 /// offset: 0x36080
-pub fn behavior_5_predicate() {}
+pub fn behavior_5_predicate(_prop: &Prop, _katamari: &Katamari) -> bool {
+    false
+}
 
 /// Never switches to alt motion.
 /// offset: 0x35f80
@@ -216,3 +221,101 @@ pub fn never_predicate() -> bool {
 pub fn area_loaded_predicate(trigger_area: u8, loaded_area: u8) -> bool {
     trigger_area == loaded_area
 }
+
+/// The predicate which decides when a prop switches from its motion action to its alt
+/// motion action. Each prop behavior has one predicate, which is checked every tick by the
+/// prop's motion action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AltActionTrigger {
+    /// The prop has no alt action (`never_predicate`).
+    Never,
+
+    /// `behavior_3_predicate`
+    Behavior3,
+
+    /// `behavior_5_predicate`
+    Behavior5,
+
+    /// The prop has an alt action, but its predicate isn't known yet, so (as with `Never`)
+    /// the prop never switches to it.
+    Unknown,
+}
+
+impl AltActionTrigger {
+    /// The predicate used by props with the given `behavior`.
+    pub fn from_behavior(behavior: i16) -> Self {
+        let [_, alt_action] = get_behavior_motion_actions(behavior);
+
+        match behavior {
+            3 => Self::Behavior3,
+            5 => Self::Behavior5,
+            _ if alt_action == 0 => Self::Never,
+            // TODO_ALT: the dispatch from the remaining behaviors to their predicates (e.g.
+            // `vol_and_dist_predicate` or `guard_parent_predicate`) isn't ported yet
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Returns `true` if this predicate hasn't been ported yet, so props using it never switch
+    /// to their alt action.
+    pub fn is_stubbed(&self) -> bool {
+        matches!(self, Self::Behavior3 | Self::Behavior5 | Self::Unknown)
+    }
+
+    /// Returns `true` if `prop` should switch to its alt motion action.
+    pub fn should_do_alt_action(&self, prop: &Prop, katamari: &Katamari) -> bool {
+        match self {
+            Self::Never => never_predicate(),
+            Self::Behavior3 => behavior_3_predicate(prop, katamari),
+            Self::Behavior5 => behavior_5_predicate(prop, katamari),
+            Self::Unknown => false,
+        }
+    }
+
+    /// Returns `true` if `prop` should switch to its alt motion action, given its behavior.
+    pub fn check(prop: &Prop, katamari: &Katamari) -> bool {
+        match (prop.get_behavior(), prop.alt_motion_action) {
+            (Some(behavior), Some(_)) => {
+                Self::from_behavior(behavior).should_do_alt_action(prop, katamari)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AltActionTrigger;
+
+    #[test]
+    fn test_alt_action_trigger_from_behavior() {
+        // zone trigger, then flee (with a predicate that isn't ported yet)
+        assert_eq!(
+            AltActionTrigger::from_behavior(0),
+            AltActionTrigger::Unknown
+        );
+        assert_eq!(
+            AltActionTrigger::from_behavior(3),
+            AltActionTrigger::Behavior3
+        );
+        assert_eq!(
+            AltActionTrigger::from_behavior(5),
+            AltActionTrigger::Behavior5
+        );
+        // sway, with no alt action
+        assert_eq!(
+            AltActionTrigger::from_behavior(0x20),
+            AltActionTrigger::Never
+        );
+        // misc actions, with an unknown predicate
+        assert_eq!(
+            AltActionTrigger::from_behavior(0x13),
+            AltActionTrigger::Unknown
+        );
+
+        assert!(AltActionTrigger::from_behavior(3).is_stubbed());
+        assert!(AltActionTrigger::from_behavior(0x13).is_stubbed());
+        assert!(AltActionTrigger::from_behavior(0).is_stubbed());
+        assert!(!AltActionTrigger::from_behavior(0x20).is_stubbed());
+    }
+}
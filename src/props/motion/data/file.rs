@@ -0,0 +1,328 @@
+use std::collections::BTreeMap;
+
+use gl_matrix::common::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::mission::Mission;
+
+use super::{
+    behaviors::BEHAVIOR_ACTIONS,
+    move_types::MissionMoveType,
+    prop_paths::{PathStage, PropPath, PropPathData},
+    MotionData,
+};
+
+/// A text (JSON) representation of `MotionData`. The runner's `motion-data` command writes the
+/// built-in motion data in this format, and a file in this format can override parts of it
+/// without recompiling (see `MOTION_DATA_PATH`, or the runner's `--motion-data` option).
+///
+/// ```json
+/// {
+///   "mission_stages": { "1": "House" },
+///   "paths": {
+///     "House": {
+///       "17": { "speed": -1.0, "points": [[0.0, 0.0, 0.0], [100.0, 0.0, 0.0]] }
+///     }
+///   },
+///   "move_types": {
+///     "1": { "5": { "default_action": 0, "path_idx": 17, "behavior": 2 } }
+///   }
+/// }
+/// ```
+///
+///   - `mission_stages` maps a mission index to the `PathStage` whose paths its props follow,
+///     or to `null` if the mission has no paths.
+///   - `paths` maps a `PathStage` to its paths, keyed by path index (the `path_idx` of a move
+///     type). A path's `speed` is the fixed speed of props following it, or -1 if they move
+///     at their own speed. Its `points` are visited in order, and then the path loops.
+///     A `null` path removes that path.
+///   - `move_types` maps a mission index to its move types, keyed by move type (the
+///     `mono_move_type` argument of `MonoInitAddProp`). A move type's `behavior` is an index
+///     into `BEHAVIOR_ACTIONS`, or -1 if the prop only has the action `default_action` (0-2).
+///
+/// Every field is optional. When overriding motion data, each entry in the file replaces the
+/// entry with the same key (or adds it, if there isn't one), and everything else is kept.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MotionDataFile {
+    pub mission_stages: BTreeMap<u8, Option<PathStage>>,
+    pub paths: BTreeMap<PathStage, BTreeMap<u16, Option<PropPathEntry>>>,
+    pub move_types: BTreeMap<u8, BTreeMap<u16, MissionMoveType>>,
+}
+
+/// A path in a `MotionDataFile`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropPathEntry {
+    pub speed: f32,
+    pub points: Vec<Vec3>,
+}
+
+/// Returns an error if `mission` isn't a valid mission index.
+fn check_mission(mission: u8) -> Result<(), String> {
+    if mission > Mission::Test4 as u8 {
+        return Err(format!("unknown mission {mission}"));
+    }
+
+    Ok(())
+}
+
+/// The paths of each stage of `data`, indexed by path index (`None` for null paths).
+pub fn stage_paths(data: &PropPathData) -> BTreeMap<PathStage, Vec<Option<PropPathEntry>>> {
+    PathStage::ALL
+        .iter()
+        .map(|&stage| {
+            let paths = (0..data.stage_path_ranges[&stage].len())
+                .map(|path_idx| {
+                    let path = data.get_stage_path(stage, path_idx)?;
+                    let last_point_idx = data.get_last_stage_path_point_idx(stage, path_idx)?;
+                    let start = path.point_idx as usize;
+                    let points = data.points[start..=start + last_point_idx as usize]
+                        .iter()
+                        .map(|point| [point[0], point[1], point[2]])
+                        .collect();
+
+                    Some(PropPathEntry {
+                        speed: path.speed,
+                        points,
+                    })
+                })
+                .collect();
+
+            (stage, paths)
+        })
+        .collect()
+}
+
+impl MotionDataFile {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("error parsing motion data: {e}"))
+    }
+
+    /// The full contents of `data` (the format of the built-in data export).
+    pub fn from_motion_data(data: &MotionData) -> Self {
+        let mission_stages = data
+            .paths
+            .mission_stages
+            .iter()
+            .map(|(mission, stage)| (*mission as u8, Some(*stage)))
+            .collect();
+
+        let paths = stage_paths(&data.paths)
+            .into_iter()
+            .map(|(stage, paths)| {
+                let paths = paths
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(path_idx, path)| Some((path_idx as u16, Some(path?))))
+                    .collect();
+                (stage, paths)
+            })
+            .collect();
+
+        let move_types = data
+            .move_types
+            .iter()
+            .enumerate()
+            .filter(|(_, move_types)| !move_types.is_empty())
+            .map(|(mission, move_types)| {
+                let move_types = move_types
+                    .iter()
+                    .enumerate()
+                    .map(|(move_type, data)| (move_type as u16, *data))
+                    .collect();
+                (mission as u8, move_types)
+            })
+            .collect();
+
+        Self {
+            mission_stages,
+            paths,
+            move_types,
+        }
+    }
+
+    /// Override `data` with the entries of this file.
+    pub fn apply(self, data: &mut MotionData) -> Result<(), String> {
+        for (mission, stage) in self.mission_stages {
+            check_mission(mission)?;
+            let mission = Mission::from(mission);
+
+            match stage {
+                Some(stage) => data.paths.mission_stages.insert(mission, stage),
+                None => data.paths.mission_stages.remove(&mission),
+            };
+        }
+
+        if !self.paths.is_empty() {
+            let mut stage_paths = stage_paths(&data.paths);
+
+            for (stage, paths) in self.paths {
+                let stage_paths = stage_paths.get_mut(&stage).unwrap();
+
+                for (path_idx, path) in paths {
+                    if path.as_ref().is_some_and(|path| path.points.is_empty()) {
+                        return Err(format!("path {path_idx} of {stage:?} has no points"));
+                    }
+
+                    let path_idx = path_idx as usize;
+                    if path_idx >= stage_paths.len() {
+                        stage_paths.resize(path_idx + 1, None);
+                    }
+                    stage_paths[path_idx] = path;
+                }
+            }
+
+            Self::write_paths(&mut data.paths, stage_paths);
+        }
+
+        for (mission, move_types) in self.move_types {
+            check_mission(mission)?;
+
+            let mission_idx = mission as usize;
+            if mission_idx >= data.move_types.len() {
+                data.move_types.resize(mission_idx + 1, vec![]);
+            }
+            let mission_move_types = &mut data.move_types[mission_idx];
+
+            // move types are visited in increasing order, so they can be appended one by one
+            for (move_type, move_type_data) in move_types {
+                let MissionMoveType {
+                    default_action,
+                    behavior,
+                    ..
+                } = move_type_data;
+
+                if behavior < 0 && default_action > 2 {
+                    return Err(format!(
+                        "move type {move_type} of mission {mission} has unknown default action {default_action}"
+                    ));
+                }
+
+                if behavior >= BEHAVIOR_ACTIONS.len() as i16 {
+                    return Err(format!(
+                        "move type {move_type} of mission {mission} has unknown behavior {behavior}"
+                    ));
+                }
+
+                let move_type = move_type as usize;
+                match move_type.cmp(&mission_move_types.len()) {
+                    std::cmp::Ordering::Less => mission_move_types[move_type] = move_type_data,
+                    std::cmp::Ordering::Equal => mission_move_types.push(move_type_data),
+                    std::cmp::Ordering::Greater => {
+                        return Err(format!(
+                            "move type {move_type} of mission {mission} leaves a gap after move type {}",
+                            mission_move_types.len()
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replace the paths of `data` with `stage_paths`, laid out as in the original simulation:
+    /// the paths of each stage are contiguous, and each path's points are followed by an
+    /// end-of-list sentinel point.
+    fn write_paths(
+        data: &mut PropPathData,
+        stage_paths: BTreeMap<PathStage, Vec<Option<PropPathEntry>>>,
+    ) {
+        data.paths.clear();
+        data.points.clear();
+
+        for stage in PathStage::ALL {
+            let start = data.paths.len();
+
+            for path in stage_paths[&stage].iter() {
+                let Some(path) = path else {
+                    data.paths.push(PropPath {
+                        point_idx: PropPath::NULL_POINT_IDX,
+                        speed: -1.0,
+                    });
+                    continue;
+                };
+
+                data.paths.push(PropPath {
+                    point_idx: data.points.len() as u32,
+                    speed: path.speed,
+                });
+                data.points.extend(
+                    path.points
+                        .iter()
+                        .map(|point| [point[0], point[1], point[2], 1.0]),
+                );
+                data.points
+                    .push([0.0, 0.0, 0.0, PropPathData::END_POINT_LIST_SENTINEL]);
+            }
+
+            data.stage_path_ranges
+                .insert(stage, start..data.paths.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{mission::Mission, props::motion::data::MotionData};
+
+    use super::MotionDataFile;
+
+    #[test]
+    fn test_motion_data_file_round_trip() {
+        let built_in = MotionData::built_in();
+        let file = MotionDataFile::from_motion_data(&built_in);
+        let json = serde_json::to_string(&file).unwrap();
+
+        // re-applying the whole built-in data (after re-laying out the paths) changes nothing
+        let mut data = MotionData::built_in();
+        MotionDataFile::from_json(&json)
+            .unwrap()
+            .apply(&mut data)
+            .unwrap();
+        assert_eq!(MotionDataFile::from_motion_data(&data), file);
+        assert_eq!(
+            data.paths.get_last_path_point_idx(Mission::Eternal3, 16),
+            Some(168)
+        );
+    }
+
+    #[test]
+    fn test_motion_data_file_override() {
+        let json = r#"{
+            "paths": { "House": { "3": { "speed": 5.0, "points": [[1, 2, 3], [4, 5, 6]] } } },
+            "move_types": { "1": { "0": { "default_action": 1, "path_idx": 3, "behavior": -1 } } }
+        }"#;
+
+        let mut data = MotionData::built_in();
+        MotionDataFile::from_json(json)
+            .unwrap()
+            .apply(&mut data)
+            .unwrap();
+
+        let path = data.paths.get_mission_path(Mission::MAS1, 3).unwrap();
+        assert_eq!(path.speed, 5.0);
+        assert_eq!(
+            data.paths.get_last_path_point_idx(Mission::MAS1, 3),
+            Some(1)
+        );
+        assert_eq!(data.move_types[1][0].default_action, 1);
+
+        // other paths and move types are unchanged
+        let built_in = MotionData::built_in();
+        assert_eq!(
+            data.paths.get_last_path_point_idx(Mission::Eternal3, 49),
+            built_in
+                .paths
+                .get_last_path_point_idx(Mission::Eternal3, 49)
+        );
+        assert_eq!(data.move_types[1][1], built_in.move_types[1][1]);
+
+        let gap = r#"{ "move_types": { "1": { "5000": { "default_action": 0, "path_idx": 0, "behavior": -1 } } } }"#;
+        let mut data = MotionData::built_in();
+        assert!(MotionDataFile::from_json(gap)
+            .unwrap()
+            .apply(&mut data)
+            .is_err());
+    }
+}
@@ -1,3 +1,86 @@
+use std::{cell::RefCell, path::Path, rc::Rc};
+
+use self::{
+    file::MotionDataFile,
+    move_types::{MissionMoveType, MISSION_MOVE_TYPES},
+    prop_paths::PropPathData,
+};
+
 pub mod behaviors;
+pub mod file;
 pub mod move_types;
 pub mod prop_paths;
+
+/// The path of the motion data file read at `MonoInitStart`, which overrides the built-in
+/// motion data (see `MotionDataFile`).
+pub const MOTION_DATA_PATH: &str =
+    "C:\\Program Files (x86)\\Steam\\steamapps\\common\\Katamari Damacy REROLL\\motion_data.json";
+
+/// The data which parameterizes prop motion in every mission.
+#[derive(Debug, Clone)]
+pub struct MotionData {
+    pub paths: PropPathData,
+
+    /// The move types of each mission, indexed by mission.
+    pub move_types: Vec<Vec<MissionMoveType>>,
+}
+
+impl MotionData {
+    /// The motion data extracted from the original simulation.
+    pub fn built_in() -> Self {
+        Self {
+            paths: PropPathData::from_bin(),
+            move_types: MISSION_MOVE_TYPES.clone(),
+        }
+    }
+}
+
+thread_local! {
+    static MOTION_DATA: RefCell<Rc<MotionData>> = RefCell::new(Rc::new(MotionData::built_in()));
+}
+
+/// Returns the current motion data.
+pub fn motion_data() -> Rc<MotionData> {
+    MOTION_DATA.with(|data| data.borrow().clone())
+}
+
+/// Replace the current motion data.
+pub fn set_motion_data(data: MotionData) {
+    MOTION_DATA.with(|current| current.replace(Rc::new(data)));
+}
+
+/// Replace the current motion data with the built-in motion data, overridden by `file` if
+/// there is one.
+pub fn set_motion_data_file(file: Option<&MotionDataFile>) -> Result<(), String> {
+    let mut data = MotionData::built_in();
+    if let Some(file) = file {
+        file.clone().apply(&mut data)?;
+    }
+
+    set_motion_data(data);
+    Ok(())
+}
+
+/// Replace the current motion data with the built-in motion data, overridden by the motion
+/// data file at `path` if there is one. Returns the file that was loaded (if any), so that
+/// it can be recorded. If the file can't be loaded, the built-in motion data is used.
+pub fn load_motion_data_file(path: &Path) -> Result<Option<MotionDataFile>, String> {
+    let result = read_motion_data_file(path).and_then(|file| {
+        set_motion_data_file(file.as_ref())?;
+        Ok(file)
+    });
+
+    if result.is_err() {
+        set_motion_data(MotionData::built_in());
+    }
+
+    result
+}
+
+fn read_motion_data_file(path: &Path) -> Result<Option<MotionDataFile>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(json) => MotionDataFile::from_json(&json).map(Some),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
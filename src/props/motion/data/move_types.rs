@@ -1,6 +1,7 @@
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct MissionMoveType {
     pub default_action: u16,
     pub path_idx: u16,
@@ -1,5 +1,7 @@
+use std::{collections::HashMap, ops::Range};
+
 use gl_matrix::common::{Vec3, Vec4};
-use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     macros::{include_bytes_align_as, transmute_included_bytes},
@@ -27,7 +29,7 @@ macro_rules! path_pt_dist {
     }};
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum PathStage {
     House,
     Town,
@@ -38,7 +40,8 @@ pub enum PathStage {
     Gameshow,
 }
 
-/// Maps a `Mission` to its associated `PathStage`, if that mission has one.
+/// Maps a `Mission` to its associated `PathStage` in the original simulation, if that mission
+/// has one. (Use `PropPathData::get_path_stage` to account for overridden motion data.)
 impl TryFrom<Mission> for PathStage {
     type Error = ();
     fn try_from(value: Mission) -> Result<Self, Self::Error> {
@@ -83,11 +86,16 @@ impl TryFrom<Mission> for PathStage {
 }
 
 impl PathStage {
-    /// Returns `true` if `mission` is associated to some `PathStage`, which means that
-    /// there are paths defined for that mission.
-    pub fn has_paths(mission: Mission) -> bool {
-        TryInto::<PathStage>::try_into(mission).is_ok()
-    }
+    /// Every path stage, in the order their paths are stored in `PropPathData::paths`.
+    pub const ALL: [PathStage; 7] = [
+        PathStage::ShopDemo,
+        PathStage::Town,
+        PathStage::VsMode,
+        PathStage::House,
+        PathStage::Gameshow,
+        PathStage::UrsaMajor,
+        PathStage::World,
+    ];
 }
 
 /// A path that can be travelled by a prop.
@@ -106,18 +114,25 @@ pub struct PropPath {
 impl PropPath {
     /// The "null pointer" values of `point_idx` in the original simulation are encoded
     /// here as `u32::MAX`.
-    const NULL_POINT_IDX: u32 = u32::MAX;
+    pub const NULL_POINT_IDX: u32 = u32::MAX;
 
-    fn has_null_point_idx(&self) -> bool {
+    pub fn has_null_point_idx(&self) -> bool {
         self.point_idx == Self::NULL_POINT_IDX
     }
 }
 
 /// The collection of all data that parameterizes prop paths, as it is stored (roughly)
 /// in the original simulation.
+#[derive(Debug, Clone)]
 pub struct PropPathData {
-    pub paths: &'static [PropPath],
-    pub points: &'static [Vec4],
+    pub paths: Vec<PropPath>,
+    pub points: Vec<Vec4>,
+
+    /// The range of indices in `paths` of the paths in each stage.
+    pub stage_path_ranges: HashMap<PathStage, Range<usize>>,
+
+    /// The path stage of each mission which has paths.
+    pub mission_stages: HashMap<Mission, PathStage>,
 }
 
 impl PropPathData {
@@ -125,13 +140,47 @@ impl PropPathData {
     const NUM_POINTS: usize = 18921;
 
     /// Read the `PropPathData` from the binary files extracted from the original simulation.
-    fn from_bin() -> PropPathData {
+    pub fn from_bin() -> PropPathData {
         let points = unsafe { transmute_included_bytes!(PROP_PATH_POINTS, Vec4, Self::NUM_POINTS) };
 
         let paths =
             unsafe { transmute_included_bytes!(PROP_PATHS, PropPath, Self::NUM_PROP_PATHS) };
 
-        Self { points, paths }
+        let stage_path_ranges = PathStage::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, stage)| {
+                let end = PathStage::ALL
+                    .get(i + 1)
+                    .map_or(Self::NUM_PROP_PATHS, |next| {
+                        Self::path_stage_init_idx(*next)
+                    });
+                (*stage, Self::path_stage_init_idx(*stage)..end)
+            })
+            .collect();
+
+        let mission_stages = (0..=Mission::Test4 as u8)
+            .map(Mission::from)
+            .filter_map(|mission| Some((mission, PathStage::try_from(mission).ok()?)))
+            .collect();
+
+        Self {
+            points: points.to_vec(),
+            paths: paths.to_vec(),
+            stage_path_ranges,
+            mission_stages,
+        }
+    }
+
+    /// The path stage of `mission`, if it has one.
+    pub fn get_path_stage(&self, mission: Mission) -> Option<PathStage> {
+        self.mission_stages.get(&mission).copied()
+    }
+
+    /// Returns `true` if `mission` is associated to some `PathStage`, which means that
+    /// there are paths defined for that mission.
+    pub fn has_paths(&self, mission: Mission) -> bool {
+        self.mission_stages.contains_key(&mission)
     }
 
     /// Get a slice of all points on the `path_idx`-th path in `mission`.
@@ -143,8 +192,8 @@ impl PropPathData {
         })
     }
 
-    /// The first index in the `paths` array corresponding to the paths in `stage`.
-    /// For example, the path with index 17 in the `House` stage is at index
+    /// The first index in the `paths` array of the original simulation corresponding to the
+    /// paths in `stage`. For example, the path with index 17 in the `House` stage is at index
     /// 85 + 17 = 102 in `paths`.
     fn path_stage_init_idx(stage: PathStage) -> usize {
         match stage {
@@ -159,20 +208,16 @@ impl PropPathData {
     }
 
     /// Get the `stage_path_idx`-th path in `stage`, if it exists.
-    fn get_stage_path(&self, stage: PathStage, path_idx: usize) -> Option<&PropPath> {
-        let path_idx = Self::path_stage_init_idx(stage) + path_idx;
-        let path = &self.paths[path_idx];
-        if path.has_null_point_idx() {
-            None
-        } else {
-            Some(path)
-        }
+    pub fn get_stage_path(&self, stage: PathStage, path_idx: usize) -> Option<&PropPath> {
+        let path_idx = self.stage_path_ranges[&stage].start + path_idx;
+        self.paths
+            .get(path_idx)
+            .filter(|path| !path.has_null_point_idx())
     }
 
     /// Get the `mission_path_idx`-th path in `mission`, if it exists.
     pub fn get_mission_path(&self, mission: Mission, path_idx: usize) -> Option<&PropPath> {
-        TryInto::<PathStage>::try_into(mission)
-            .ok()
+        self.get_path_stage(mission)
             .and_then(|stage| self.get_stage_path(stage, path_idx))
     }
 
@@ -205,7 +250,7 @@ impl PropPathData {
         point_idx: usize,
         path_idx: usize,
     ) -> bool {
-        if let Ok(stage) = TryInto::<PathStage>::try_into(mission) {
+        if let Some(stage) = self.get_path_stage(mission) {
             self.get_stage_path_point(out, stage, point_idx, path_idx)
         } else {
             true
@@ -216,28 +261,30 @@ impl PropPathData {
     /// Specifically, the `Vec4` value [0.0, 0.0, 0.0, 255.0] indicates the end of a list.
     /// Note that all other index-3 values are 1.0, so it's enough to check that it's not 1.0
     /// rather than specifically checking for the sentinel.
-    const END_POINT_LIST_SENTINEL: f32 = 255.0;
+    pub const END_POINT_LIST_SENTINEL: f32 = 255.0;
 
     /// Compute the last point index on the `path_idx`-th path in `mission`. This is the
     /// **largest valid** point index for the path.
     /// If no such path exists, returns `None`.
     /// offset: 0x37790
     pub fn get_last_path_point_idx(&self, mission: Mission, path_idx: usize) -> Option<u16> {
-        if let Ok(stage) = TryInto::<PathStage>::try_into(mission) {
-            if let Some(path) = self.get_stage_path(stage, path_idx) {
-                let mut point_idx = path.point_idx as usize;
-                let mut result = 0;
-                loop {
-                    if self.points[point_idx][3] == Self::END_POINT_LIST_SENTINEL {
-                        return Some(result - 1);
-                    }
-                    point_idx += 1;
-                    result += 1;
-                }
+        self.get_path_stage(mission)
+            .and_then(|stage| self.get_last_stage_path_point_idx(stage, path_idx))
+    }
+
+    /// Compute the last point index on the `path_idx`-th path in `stage` (see
+    /// `get_last_path_point_idx`).
+    pub fn get_last_stage_path_point_idx(&self, stage: PathStage, path_idx: usize) -> Option<u16> {
+        let path = self.get_stage_path(stage, path_idx)?;
+        let mut point_idx = path.point_idx as usize;
+        let mut result = 0;
+        loop {
+            if self.points[point_idx][3] == Self::END_POINT_LIST_SENTINEL {
+                return Some(result - 1);
             }
+            point_idx += 1;
+            result += 1;
         }
-
-        None
     }
 
     /// offset: 0x37800
@@ -364,24 +411,20 @@ impl PropPathData {
     }
 }
 
-lazy_static! {
-    /// Global static interface for prop path data.
-    pub static ref PROP_PATH_DATA: PropPathData = PropPathData::from_bin();
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
 
     #[test]
     fn test_get_last_point_idx() {
+        let data = PropPathData::from_bin();
         assert_eq!(
             Some(168),
-            PROP_PATH_DATA.get_last_path_point_idx(Mission::Eternal3, 16)
+            data.get_last_path_point_idx(Mission::Eternal3, 16)
         );
         assert_eq!(
             Some(12),
-            PROP_PATH_DATA.get_last_path_point_idx(Mission::Eternal3, 49)
+            data.get_last_path_point_idx(Mission::Eternal3, 49)
         );
     }
 }
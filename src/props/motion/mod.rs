@@ -1,12 +1,11 @@
+use serde::{Deserialize, Serialize};
+
 use crate::mission::Mission;
 
-use self::data::{
-    behaviors::BEHAVIOR_ACTIONS,
-    move_types::{MissionMoveType, MISSION_MOVE_TYPES},
-};
+use self::data::{behaviors::BEHAVIOR_ACTIONS, motion_data, move_types::MissionMoveType};
 
 pub mod actions;
-mod alt_action;
+pub mod alt_action;
 pub mod data;
 pub mod global_path;
 pub mod name_idx;
@@ -15,7 +14,7 @@ pub mod name_idx;
 // scaling it up to include all motion actions
 
 pub fn get_mission_move_type_data(mission: Mission, move_type: u16) -> Option<MissionMoveType> {
-    if let Some(mission_move_types) = MISSION_MOVE_TYPES.get(mission as u8 as usize) {
+    if let Some(mission_move_types) = motion_data().move_types.get(mission as u8 as usize) {
         mission_move_types
             .get(move_type as usize)
             .map(|result| result.clone())
@@ -28,8 +27,21 @@ pub fn get_behavior_motion_actions(behavior: i16) -> [u16; 2] {
     BEHAVIOR_ACTIONS[behavior as usize]
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RotationAxis {
+    #[default]
     X,
     Y,
     Z,
 }
+
+impl RotationAxis {
+    /// The index of this axis's coordinate in a vector.
+    pub fn index(self) -> usize {
+        match self {
+            RotationAxis::X => 0,
+            RotationAxis::Y => 1,
+            RotationAxis::Z => 2,
+        }
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     collision::raycast_state::RaycastRef, global::GlobalState, mission::state::MissionState,
-    props::prop::Prop,
+    player::katamari::Katamari, props::prop::Prop,
 };
 
 use super::{
@@ -30,15 +30,17 @@ impl Prop {
         mission_state: &MissionState,
         global_state: &mut GlobalState,
         raycasts: RaycastRef,
+        katamari: &Katamari,
     ) {
         match self.get_name_index_motion() {
             NameIndexMotion::Normal => {
                 // offset: 0x39850
-                if let Some(_move_type) = self.get_move_type() {
+                // props don't follow their motion while they're flying through the air
+                if self.get_move_type().is_some() && !self.is_under_trajectory() {
                     // TODO: (*(code *)(&callback3_generic_moving_states)[prop->pstActionState])()
                     if let Some(motion) = motion {
                         // motion.should_do_alt_motion();
-                        motion.update(self, gps, mission_state, global_state, raycasts);
+                        motion.update(self, gps, mission_state, global_state, raycasts, katamari);
                     }
                 }
             }
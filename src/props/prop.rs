@@ -12,14 +12,19 @@ use gl_matrix::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    collision::{mesh::Mesh, util::max_transformed_y},
+    collision::{
+        mesh::Mesh,
+        raycast_state::{RaycastCallType, RaycastRef},
+        util::{max_transformed_y, min_transformed_y},
+    },
     constants::{FRAC_1_3, FRAC_PI_750, UNITY_TO_SIM_SCALE, _4PI},
-    debug::DEBUG_CONFIG,
-    global::GlobalState,
+    debug::debug_config,
+    global::{tick_rate::TickRate, GlobalState},
     macros::{
         debug_log, max_to_none, modify_translation, new_mat4_copy, panic_log, scale_translation,
         set_translation, vec3_from,
     },
+    math::{normalize_bounded_angle, vec3_inplace_add_vec, vec3_inplace_scale},
     mission::state::MissionState,
     mono_data::{MonoData, PropAabbs, PropMonoData},
     player::{katamari::Katamari, Player},
@@ -36,10 +41,20 @@ use crate::{
 use super::{
     comments::KingCommentState,
     motion::{name_idx::NameIndexMotion, RotationAxis},
+    params::PropParams,
     random::RandomPropsState,
+    subobject::PropSubobject,
     PropsState,
 };
 
+/// The name indices of props which are turntables, in addition to props with the yaw spin
+/// behavior (0x15).
+const TURNTABLE_NAME_IDXS: [u16; 3] = [
+    0x31d, // Manhole Cover
+    0x35b, // Round Table
+    0x55b, // Parking Turntable
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PropGlobalState {
     /// Normal unattached state.
@@ -101,19 +116,21 @@ impl From<u16> for PropLinkAction {
     }
 }
 
-#[derive(Debug, Default)]
-struct PropSubobject {
-    /// The next subobject in the linked list, if one exists.
-    /// offset: 0x10
-    pub next: Option<Box<PropSubobject>>,
+/// How a child prop reacts when its parent prop is attached to the katamari (which is
+/// decided by the child's `PropLinkAction`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParentAttachedReaction {
+    /// The child is attached to the katamari along with its parent.
+    AttachWithParent,
 
-    /// (??) The position of this subobject (presumably relative to the prop).
-    /// offset: 0x18
-    pub pos: Vec3,
+    /// The child is destroyed.
+    Destroy,
 
-    /// (??) The Euler agnles of this subobject
-    /// offset: 0x28
-    pub rot_vec: Vec3,
+    /// The child switches to its alt motion action (e.g. it starts fleeing).
+    StartAltAction,
+
+    /// The child falls off of where its parent was.
+    FallOff,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -171,7 +188,7 @@ impl Into<u8> for PropAnimationType {
 }
 
 // holy cannoli what were they thinking
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AddPropArgs {
     pub pos_x: f32,
     pub pos_y: f32,
@@ -296,6 +313,13 @@ bitflags::bitflags! {
     }
 }
 
+/// The name indices of vending machines, which eject drinks when the katamari bonks them.
+const VENDING_MACHINE_NAME_IDXS: [u16; 3] = [
+    0x284, // ZIHANKI01_E
+    0x285, // ZIHANKI02_E
+    0x286, // ZIHANKI03_E
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PropTrajectoryType {
     Normal,
@@ -437,6 +461,16 @@ pub struct Prop {
     /// offset: 0xe0
     pub trajectory_velocity: Vec3,
 
+    /// The number of times this prop has bounced off of a floor during its current trajectory.
+    airborne_bounces: u8,
+
+    /// The axis around which this prop tumbles while under a trajectory.
+    trajectory_rot_axis: RotationAxis,
+
+    /// The number of ticks left for this prop to spin in place after landing from
+    /// a trajectory.
+    landed_spin_ticks: u16,
+
     /// The prop's transform matrix while unattached from the katamari.
     /// offset: 0x110
     unattached_transform: Mat4,
@@ -449,15 +483,17 @@ pub struct Prop {
     /// offset: 0x190
     motion_transform: Mat4,
 
+    /// The transform of this prop relative to its parent's unattached transform, which is
+    /// used to move and rotate the prop along with its parent.
+    parent_offset_transform: Mat4,
+
     /// Moved to a separate vector of `Option<MotionAction>` data in `PropsState`.
     /// offset: 0x3b8
     // pub motion: Option<MotionAction>,
 
-    /// A pointer to the prop's first subobject.
+    /// The prop's subobjects (which are a linked list in the original simulation).
     /// offset: 0x558
-    // TODO_SUBOBJ: make this a vector of subobjects
-    #[serde(skip)]
-    first_subobject: Option<Box<PropSubobject>>,
+    subobjects: Vec<PropSubobject>,
 
     /// (??) a script that seems to only be used for policeman gunshots in reroll
     /// offset: 0x560
@@ -797,7 +833,6 @@ impl Prop {
         new_mat4_copy!(init_transform, rotation_mat);
 
         // lines 108-149 of `prop_init` (init motion)
-        // lines 163-190 of `prop_init` (init twin/catch_count_b)
 
         // lines 348-349 (find first subobject)
         // lines 350-357 (init motion scripts)
@@ -838,6 +873,7 @@ impl Prop {
             init_rotation_mat,
             init_transform,
             motion_transform: [0.0; 16],
+            parent_offset_transform: mat4::create(),
             extra_action_type: max_to_none!(u16, args.extra_action_type),
             unique_name_id: max_to_none!(u16, args.unique_name_id),
             display_off_area: max_to_none!(u8, args.disp_off_area_no as u8),
@@ -879,8 +915,8 @@ impl Prop {
             aabb_radius: 0.0,
             weird_vol_multiple: 0.0,
 
-            first_subobject: None, // TODO
-            script_0x560: None,    // TODO
+            subobjects: vec![],
+            script_0x560: None, // TODO
             name_index_motion: NameIndexMotion::Normal,
             innate_script: None,
             tree_id: None,           // TODO
@@ -899,6 +935,9 @@ impl Prop {
             attached_transform: [0.0; 16],
             collision_mesh: None,
             trajectory_velocity: [0.0; 3],
+            airborne_bounces: 0,
+            trajectory_rot_axis: RotationAxis::X,
+            landed_spin_ticks: 0,
             motion_flags: PropMotionFlags::empty(),
             parent_ref: None,
             first_child_ref: None,
@@ -943,6 +982,10 @@ impl Prop {
         }
 
         let prop_mono_data = &mono_data.props[name_idx as usize];
+        // the subobjects are created first, since the root AABB depends on their rotations
+        if let Some(aabbs) = &prop_mono_data.aabbs {
+            result.init_subobjects(aabbs);
+        }
         result.init_mono_data_fields(prop_mono_data, config);
 
         // move random-spawn props vertically so that they're resting on the ground
@@ -952,11 +995,16 @@ impl Prop {
             result.init_pos[1] += y_offset;
         }
 
-        // handle king comment (unused since unity also handles it, apparently)
+        // count the prop in its king comment group
         if let Some(group_idx) = result.comment_group_id {
             comments.add_to_group(group_idx);
         }
 
+        // count twin props (which are linked to each other in `MonoInitEnd`)
+        if result.has_twin {
+            global.num_twin_props += 1;
+        }
+
         // note the conditional call to `prop_init_tree_links` here in the original sim,
         // but the condition to call it appears to never be true in reroll.
 
@@ -982,6 +1030,15 @@ impl Prop {
         self.mono_data = Some(prop_mono_data.clone());
     }
 
+    /// Create one subobject for each subobject AABB in the prop's mono data.
+    fn init_subobjects(&mut self, aabbs: &PropAabbs) {
+        self.subobjects = aabbs
+            .iter_subobjects()
+            .enumerate()
+            .map(|_| PropSubobject::default())
+            .collect();
+    }
+
     /// Initialize the prop's AABB and volume
     /// offset: 0x27750
     fn init_aabb_and_volume(&mut self, aabbs: &PropAabbs, config: &NamePropConfig) {
@@ -993,10 +1050,9 @@ impl Prop {
             return;
         }
 
-        // TODO: `prop_init_aabb_and_volume:90-115` (compute an AABB that includes the AABB's of all subobjects,
-        //       rather than just the prop's own AABB)
-        // (but this behavior is in `aabbs.get_root_aabb` in this implementation)
-        let root_aabb = aabbs.get_root_aabb();
+        // `prop_init_aabb_and_volume:90-115` (computing an AABB that includes the rotated AABB's
+        // of all subobjects) is in `aabbs.get_root_aabb` in this implementation
+        let root_aabb = aabbs.get_root_aabb(&self.subobjects);
         self.aabb_vertices = root_aabb.compute_vertices();
         self.aabb_mesh = Some(Rc::new(root_aabb.compute_mesh(&self.aabb_vertices)));
         self.aabb_size = root_aabb.size();
@@ -1040,48 +1096,36 @@ impl Prop {
         self.ctrl_idx = ctrl_idx;
     }
 
+    pub fn debug_set_twin_id(&mut self, twin_id: Option<u16>) {
+        self.has_twin = twin_id.is_some();
+        self.twin_id = twin_id;
+    }
+
     pub fn get_ctrl_idx(&self) -> u16 {
         self.ctrl_idx
     }
 
+    pub fn get_comment_id(&self) -> Option<u16> {
+        self.comment_id
+    }
+
+    pub fn get_comment_group_id(&self) -> Option<u16> {
+        self.comment_group_id
+    }
+
     pub fn is_initialized(&self) -> bool {
         self.name_idx != u16::MAX
     }
 
-    /// Traverses the prop's linked list of subobjects to compute the list's length.
     pub fn count_subobjects(&self) -> i32 {
-        let mut result = 0;
-        let mut try_next_subobj = &self.first_subobject;
-
-        loop {
-            if let Some(next_subobj) = try_next_subobj {
-                result += 1;
-                try_next_subobj = &next_subobj.next;
-            } else {
-                break;
-            }
-        }
-
-        return result;
+        self.subobjects.len() as i32
     }
 
-    /// Traverse the prop's linked list of subobject to find the subobject at index `subobj_idx`.
-    fn get_subobject(&self, subobj_idx: i32) -> &Option<Box<PropSubobject>> {
-        let mut next_idx = 0;
-        let mut try_next_subobj = &self.first_subobject;
-
-        loop {
-            if let Some(next_subobj) = try_next_subobj {
-                if next_idx == subobj_idx {
-                    return try_next_subobj;
-                } else {
-                    next_idx += 1;
-                    try_next_subobj = &next_subobj.next;
-                }
-            } else {
-                return &None;
-            }
-        }
+    /// Find the prop's subobject at index `subobj_idx`.
+    fn get_subobject(&self, subobj_idx: i32) -> Option<&PropSubobject> {
+        usize::try_from(subobj_idx)
+            .ok()
+            .and_then(|idx| self.subobjects.get(idx))
     }
 
     /// Mimicks the `GetSubobjectPosition` API function.
@@ -1215,6 +1259,16 @@ impl Prop {
         &self.attached_transform
     }
 
+    /// The prop's current transform (which is its attached transform if it's attached to the
+    /// katamari, and its unattached transform otherwise).
+    pub fn get_transform(&self) -> &Mat4 {
+        if self.is_attached() {
+            &self.attached_transform
+        } else {
+            &self.unattached_transform
+        }
+    }
+
     pub fn has_parent(&self) -> bool {
         self.parent.is_some()
     }
@@ -1227,6 +1281,16 @@ impl Prop {
         self.twin_prop
     }
 
+    pub fn get_twin_id(&self) -> Option<u16> {
+        self.twin_id
+    }
+
+    /// Link this prop to its twin prop `twin_ctrl_idx`, or unlink it if `None`.
+    pub fn set_twin(&mut self, twin_ctrl_idx: Option<u16>) {
+        self.has_twin = twin_ctrl_idx.is_some();
+        self.twin_prop = twin_ctrl_idx;
+    }
+
     pub fn get_nearest_kat_ray_idx(&mut self) -> Option<u16> {
         self.nearest_kat_ray_idx
     }
@@ -1243,11 +1307,11 @@ impl Prop {
         self.scream_cooldown_timer
     }
 
-    pub fn reset_scream_cooldown_timer(&mut self) {
+    pub fn reset_scream_cooldown_timer(&mut self, tick_rate: &TickRate) {
         // TODO_PARAM
-        let SCREAM_COOLDOWN_TICKS = 0xf;
+        let SCREAM_COOLDOWN_FRAMES = 0xf;
 
-        self.scream_cooldown_timer = SCREAM_COOLDOWN_TICKS;
+        self.scream_cooldown_timer = tick_rate.duration(SCREAM_COOLDOWN_FRAMES);
     }
 
     pub fn set_kat_collision_vel(&mut self, kat_collision_vel: &Vec3) {
@@ -1280,12 +1344,7 @@ impl Prop {
     /// Writes the active transform to `out`.
     /// This can either be the unattached transform or the attached transform.
     pub unsafe fn unsafe_copy_transform(&self, out: *mut Mat4) {
-        let mut transform = if self.is_attached() {
-            self.attached_transform.clone()
-        } else {
-            self.unattached_transform.clone()
-        };
-
+        let mut transform = *self.get_transform();
         scale_sim_transform(&mut transform);
 
         mat4::copy(&mut *out, &transform);
@@ -1313,6 +1372,10 @@ impl Prop {
         self.visible
     }
 
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.alpha = alpha;
+    }
+
     pub fn get_attach_life(&self) -> f32 {
         self.attach_life
     }
@@ -1330,17 +1393,27 @@ impl Prop {
         self.move_type = None;
     }
 
+    /// Replace the prop's motion action with its alt motion action (which the prop then
+    /// no longer has). Returns the new motion action, or `None` if there's no alt action.
+    pub fn start_alt_motion_action(&mut self) -> Option<u16> {
+        let alt_motion_action = self.alt_motion_action.take()?;
+        self.motion_action = Some(alt_motion_action);
+        Some(alt_motion_action)
+    }
+
     pub fn get_behavior(&self) -> Option<i16> {
         self.behavior
     }
 
     pub fn set_no_parent(&mut self) {
         self.flags.remove(PropFlags1::HasParent);
+        self.flags2.remove(PropFlags2::FollowParent);
         self.parent = None;
+        self.parent_ref = None;
         self.tree_id = None;
     }
 
-    pub fn set_parent(&mut self, _props: &PropsState, parent_ctrl_idx: u16, tree_group_id: u16) {
+    pub fn set_parent(&mut self, props: &PropsState, parent_ctrl_idx: u16, tree_group_id: u16) {
         self.flags.insert(PropFlags1::HasParent);
         self.parent = Some(parent_ctrl_idx);
 
@@ -1350,11 +1423,84 @@ impl Prop {
 
         self.tree_id = Some(tree_group_id);
 
-        // TODO: line 53: `prop_update_rotation_from_parent(self)`
+        if parent_ctrl_idx != self.ctrl_idx {
+            if let Some(parent_ref) = props.get_prop(parent_ctrl_idx as usize) {
+                self.update_rotation_from_parent(&parent_ref.borrow());
+            }
+        }
 
         mat4::identity(&mut self.motion_transform);
     }
 
+    /// Record this prop's transform relative to its parent `parent`, so that it can move
+    /// and rotate along with its parent.
+    /// (`prop_update_rotation_from_parent` in the original simulation)
+    fn update_rotation_from_parent(&mut self, parent: &Prop) {
+        let mut parent_transform = mat4::create();
+        parent.compute_unattached_transform(
+            &mut parent_transform,
+            &parent.pos,
+            &parent.rotation_vec,
+        );
+        let mut parent_transform_inv = mat4::create();
+        if mat4::invert(&mut parent_transform_inv, &parent_transform).is_none() {
+            return;
+        }
+
+        let mut transform = mat4::create();
+        self.compute_unattached_transform(&mut transform, &self.pos, &self.rotation_vec);
+        mat4::multiply(
+            &mut self.parent_offset_transform,
+            &parent_transform_inv,
+            &transform,
+        );
+    }
+
+    /// Unlink this prop from its parent, keeping its current position and rotation.
+    pub fn detach_from_parent(&mut self) {
+        if self.flags2.contains(PropFlags2::FollowParent) {
+            let mut rotation_mat = self.unattached_transform;
+            set_translation!(rotation_mat, [0.0; 3]);
+            self.rotation_mat = rotation_mat;
+            vec3::zero(&mut self.rotation_vec);
+        }
+
+        self.set_no_parent();
+    }
+
+    /// The children of this prop, in the order they were linked to it.
+    pub fn get_child_refs(&self) -> Vec<PropRef> {
+        let mut result = vec![];
+        let mut next_child = self.first_child_ref.clone();
+
+        while let Some(child_ref) = next_child {
+            next_child = child_ref.borrow().next_sibling_ref.clone();
+            result.push(child_ref);
+        }
+
+        result
+    }
+
+    /// Returns `true` if this prop has a child which reacts when it's attached to the
+    /// katamari (by switching to its alt motion action).
+    fn has_reactive_child(&self) -> bool {
+        self.get_child_refs().iter().any(|child_ref| {
+            let child = child_ref.borrow();
+            child.parent == Some(self.ctrl_idx)
+                && child.link_action == Some(PropLinkAction::ReactWhenParentAttached)
+        })
+    }
+
+    /// How this prop reacts when its parent is attached to the katamari.
+    pub fn get_parent_attached_reaction(&self) -> ParentAttachedReaction {
+        match self.link_action {
+            Some(PropLinkAction::IntangibleChild) => ParentAttachedReaction::AttachWithParent,
+            Some(PropLinkAction::DestroyWhenParentAttached) => ParentAttachedReaction::Destroy,
+            Some(PropLinkAction::ReactWhenParentAttached) => ParentAttachedReaction::StartAltAction,
+            _ => ParentAttachedReaction::FallOff,
+        }
+    }
+
     // Compute the root prop of this prop's tree.
     pub fn get_root_ref(&self, props: &PropsState) -> PropRef {
         if let Some(parent_ctrl_idx) = self.parent {
@@ -1368,6 +1514,54 @@ impl Prop {
         }
     }
 
+    /// Returns `true` if this prop is one of the turntable props.
+    pub fn is_turntable(&self) -> bool {
+        TURNTABLE_NAME_IDXS.contains(&self.get_name_idx())
+    }
+
+    /// Returns `true` if this prop is a vending machine, which ejects its drinks when the
+    /// katamari bonks it.
+    pub fn is_vending_machine(&self) -> bool {
+        VENDING_MACHINE_NAME_IDXS.contains(&self.get_name_idx())
+    }
+
+    /// The world space transform which moved this prop from its position and rotation on the
+    /// previous tick to its current ones, or `None` if it didn't move.
+    pub fn get_tick_motion(&self) -> Option<Mat4> {
+        if self.pos == self.last_pos && self.rotation_vec == self.last_rotation_vec {
+            return None;
+        }
+
+        let mut last_transform = mat4::create();
+        self.compute_unattached_transform(
+            &mut last_transform,
+            &self.last_pos,
+            &self.last_rotation_vec,
+        );
+        let mut last_transform_inv = mat4::create();
+        mat4::invert(&mut last_transform_inv, &last_transform)?;
+
+        let mut transform = mat4::create();
+        self.compute_unattached_transform(&mut transform, &self.pos, &self.rotation_vec);
+
+        let mut motion = mat4::create();
+        mat4::multiply(&mut motion, &transform, &last_transform_inv);
+        Some(motion)
+    }
+
+    /// Move this prop's position and rotation by the world space transform `motion`.
+    fn move_by(&mut self, motion: &Mat4) {
+        let mut pos = vec3::create();
+        vec3::transform_mat4(&mut pos, &self.pos, motion);
+        self.pos = pos;
+
+        let mut motion_rot = *motion;
+        set_translation!(motion_rot, [0.0; 3]);
+        let mut rotation_mat = mat4::create();
+        mat4::multiply(&mut rotation_mat, &motion_rot, &self.rotation_mat);
+        self.rotation_mat = rotation_mat;
+    }
+
     pub fn get_motion_action(&self) -> Option<u16> {
         self.motion_action
     }
@@ -1417,7 +1611,7 @@ impl Prop {
         }
 
         // status & 4: prop has a subobject
-        if self.first_subobject.is_some() {
+        if !self.subobjects.is_empty() {
             status |= 0x4;
         }
 
@@ -1464,7 +1658,7 @@ impl Prop {
 
     /// offset: 0x4f8e0
     pub fn destroy(&mut self) {
-        if DEBUG_CONFIG.log_destroyed_props {
+        if debug_config().log_destroyed_props {
             debug_log!(
                 "  destroying prop: ctrl_idx={}, name_idx={}",
                 self.ctrl_idx,
@@ -1476,8 +1670,7 @@ impl Prop {
         self.visible = false;
 
         // TODO_LINKS: `prop_remove_refs_from_props()`
-        // TODO_SUBOBJ: `prop_destroy:14-25`
-        self.first_subobject = None;
+        self.subobjects.clear();
     }
 
     /// Contains most the behavior of `Katamari::attach_prop` that writes to the attached prop.
@@ -1531,70 +1724,66 @@ impl Prop {
         self.attach_life = 0.0;
         self.is_attached = false;
         // all that remains of `prop_remove_refs_from_kat`
-        self.intangible_timer = 5;
+        self.intangible_timer = global.tick_rate.duration(5);
 
         if mission_state.mission_config.is_theme_object(self.name_idx) {
             global.catch_count_b -= 1;
         }
     }
 
+    /// Send this prop flying with the initial velocity `init_vel`, under the gravity
+    /// `gravity` (see `StageConfig::get_airborne_prop_gravity`).
     pub fn apply_trajectory(
         &mut self,
         init_vel: &Vec3,
         trajectory: PropTrajectoryType,
-        _gravity: f32,
+        gravity: f32,
+        global: &mut GlobalState,
     ) {
         match trajectory {
             PropTrajectoryType::HitAirborne => {
-                // TODO_AIRBORNE
+                self.global_state = PropGlobalState::AirborneIntangible;
+                self.unattached_state = PropUnattachedState::InTrajectory;
             }
             PropTrajectoryType::Normal | PropTrajectoryType::VendingMachine => {
-                // TODO_LINK
-                if false
-                /*self.first_child.is_some() && child_prop.link_action == ParentCollectedReaction */
-                {
+                if self.has_reactive_child() {
                     self.flags.insert(PropFlags1::AirborneWithReactiveChild)
                 }
                 self.global_state = PropGlobalState::Unattached;
                 self.unattached_state = PropUnattachedState::InTrajectory;
-                // TODO
-                if false
-                /* global.detaching_props_from_stuck_kat*/
-                {
+                if global.detaching_props_from_stuck_kat {
                     self.flags.insert(PropFlags1::DetachedWhileKatamariStuck);
                 }
             }
         }
 
         self.trajectory_velocity = *init_vel;
+        self.airborne_bounces = 0;
+        self.landed_spin_ticks = 0;
         // TODO_PROP_MOTION:
-        /*  (prop->motionData).numAirborneBounces = 0;
-        (prop->motionData).field14_0x19 = 0;
-        (prop->motionData).landed = false;
+        /*  (prop->motionData).field14_0x19 = 0;
         (prop->motionData).fishHopState = STATE0 */
-        // TODO: `global.airborne_prop_gravity = gravity`
+        global.airborne_prop_gravity = gravity;
 
-        if self.global_state == PropGlobalState::AirborneIntangible {
-            // TODO_AIRBORNE
-        } else {
-            if trajectory != PropTrajectoryType::VendingMachine {
-                mat4::identity(&mut self.rotation_mat);
-                vec3::zero(&mut self.rotation_vec);
-            }
+        // props ejected from vending machines keep their rotation, and props knocked
+        // airborne by the katamari keep tumbling from where they were
+        if trajectory == PropTrajectoryType::Normal {
+            mat4::identity(&mut self.rotation_mat);
+            vec3::zero(&mut self.rotation_vec);
+        }
 
-            let _rot_axis = if self.trajectory_velocity[0] <= self.trajectory_velocity[2] {
-                RotationAxis::X
-            } else {
-                RotationAxis::Z
-            };
+        self.trajectory_rot_axis = if self.trajectory_velocity[0] <= self.trajectory_velocity[2] {
+            RotationAxis::X
+        } else {
+            RotationAxis::Z
+        };
 
-            // TODO_PROP_MOTION:
-            /*  (prop->motionData).rotationAxis = AVar5;
-            (prop->motionData).field17_0x1c = 0.2;
-            (prop->motionData).field18_0x20 = 0.0 */
+        self.flags2.insert(PropFlags2::UnderTrajectory);
+    }
 
-            self.flags2.insert(PropFlags2::UnderTrajectory);
-        }
+    /// Returns `true` if this prop is flying along a trajectory.
+    pub fn is_under_trajectory(&self) -> bool {
+        self.flags2.contains(PropFlags2::UnderTrajectory)
     }
 
     /// TODO
@@ -1609,11 +1798,18 @@ impl Prop {
         vec3::copy(&mut self.last_rotation_vec, &self.rotation_vec);
     }
 
-    pub fn update_global_state(&mut self) {
+    pub fn update_global_state(
+        &mut self,
+        params: &PropParams,
+        global: &GlobalState,
+        raycasts: &RaycastRef,
+    ) {
         match self.global_state {
-            PropGlobalState::Unattached => self.update_unattached(),
+            PropGlobalState::Unattached => self.update_unattached(params, global, raycasts),
             PropGlobalState::Attached => self.update_attached(),
-            PropGlobalState::AirborneIntangible => self.update_airborne_intangible(),
+            PropGlobalState::AirborneIntangible => {
+                self.update_airborne_intangible(params, global, raycasts)
+            }
         }
     }
 
@@ -1624,13 +1820,15 @@ impl Prop {
 
     pub fn update_transform_unattached(&mut self) {
         if self.global_state != PropGlobalState::Attached {
-            // TODO_LINKS: `props_update_nonending:96-133` (different transform logic for linked props)
-            // if (prop.flags & 2) != 0 {
-            //     // if prop is wobbling (??)
-            // }
-
-            // TODO_LINKS: this value should depend on the above code
-            let transform_state = UnattachedTransformState::Normal;
+            // `props_update_nonending:96-133` (different transform logic for linked props)
+            // TODO_STALLS: the stalled transform states
+            let transform_state = if !self.flags.contains(PropFlags1::HasParent) {
+                UnattachedTransformState::Normal
+            } else if self.flags2.contains(PropFlags2::FollowParent) {
+                UnattachedTransformState::StationaryChild
+            } else {
+                UnattachedTransformState::MovingChild
+            };
 
             match transform_state {
                 UnattachedTransformState::Normal => self.update_transform_normal(),
@@ -1651,18 +1849,21 @@ impl Prop {
 }
 
 impl Prop {
-    // TODO_PROPS
     /// Update logic for a prop that's not attached to the katamari.
     /// offset: 0x50f10
-    fn update_unattached(&mut self) {
-        // match self.unattached_state {
-        //     PropUnattachedState::Normal => todo!(),
-        //     PropUnattachedState::State1 => todo!(),
-        //     PropUnattachedState::State2 => todo!(),
-        //     PropUnattachedState::State3 => todo!(),
-        //     PropUnattachedState::State4 => todo!(),
-        //     PropUnattachedState::AirborneBounced => todo!(),
-        // }
+    fn update_unattached(
+        &mut self,
+        params: &PropParams,
+        global: &GlobalState,
+        raycasts: &RaycastRef,
+    ) {
+        match self.unattached_state {
+            PropUnattachedState::InTrajectory | PropUnattachedState::AirborneBounced => {
+                self.update_trajectory(params, global, raycasts)
+            }
+            // TODO_PROPS: `State2`, `State3`, and `InelasticRoll`
+            _ => self.update_landed_spin(params, &global.tick_rate),
+        }
     }
 
     /// Update logic for a prop that's attached to the katamari.
@@ -1689,10 +1890,133 @@ impl Prop {
         mat4::get_translation(&mut self.pos, &self.attached_transform);
     }
 
-    /// TODO_PROPS
-    /// Update logic for a prop that's airborne.
+    /// Update logic for a prop that's airborne after being hit by the katamari.
     /// offset: 0x50eb0
-    fn update_airborne_intangible(&mut self) {}
+    fn update_airborne_intangible(
+        &mut self,
+        params: &PropParams,
+        global: &GlobalState,
+        raycasts: &RaycastRef,
+    ) {
+        self.update_trajectory(params, global, raycasts);
+    }
+
+    /// Move this prop along its trajectory under the airborne prop gravity, tumbling as it
+    /// flies. It bounces off of the floors and walls it hits until it lands.
+    fn update_trajectory(
+        &mut self,
+        params: &PropParams,
+        global: &GlobalState,
+        raycasts: &RaycastRef,
+    ) {
+        // TODO_PARAM
+        let TRAJECTORY_ROT_SPEED = 0.2;
+        let MIN_BOUNCE_SPEED = 1.0;
+        let MAX_AIRBORNE_BOUNCES = 3;
+        let MIN_FLOOR_NORMAL_Y = 0.5;
+
+        let tick_rate = &global.tick_rate;
+        self.trajectory_velocity[1] -= tick_rate.per_tick(global.airborne_prop_gravity);
+
+        let rot_idx = self.trajectory_rot_axis.index();
+        self.rotation_vec[rot_idx] = normalize_bounded_angle(
+            self.rotation_vec[rot_idx] + tick_rate.per_tick(TRAJECTORY_ROT_SPEED),
+        );
+
+        let move_vec = tick_rate.per_tick_vec3(&self.trajectory_velocity);
+        let next_pos = vec3_from!(+, self.pos, move_vec);
+
+        // cast a ray from the prop's position to its bottom at its next position
+        let bottom_y = self.min_aabb_y();
+        let ray_end = [next_pos[0], next_pos[1] + bottom_y, next_pos[2]];
+        let hit = {
+            let mut raycast = raycasts.borrow_mut();
+            raycast.load_ray(&self.pos, &ray_end);
+            if raycast.find_nearest_unity_hit(RaycastCallType::Objects, false) {
+                raycast
+                    .get_closest_hit()
+                    .map(|hit| (hit.impact_point, hit.normal_unit))
+            } else {
+                None
+            }
+        };
+
+        let Some((impact_point, normal_unit)) = hit else {
+            self.pos = next_pos;
+            return;
+        };
+
+        if normal_unit[1] >= MIN_FLOOR_NORMAL_Y && self.trajectory_velocity[1] <= 0.0 {
+            // bounce off the floor, losing speed
+            self.pos = [impact_point[0], impact_point[1] - bottom_y, impact_point[2]];
+            self.airborne_bounces += 1;
+            self.trajectory_velocity[0] *= params.lateral_vel_decay_after_bounce;
+            self.trajectory_velocity[1] *= -params.vertical_vel_decay_after_bounce;
+            self.trajectory_velocity[2] *= params.lateral_vel_decay_after_bounce;
+
+            if self.trajectory_velocity[1] < MIN_BOUNCE_SPEED
+                || self.airborne_bounces >= MAX_AIRBORNE_BOUNCES
+            {
+                self.land(params, tick_rate);
+            } else {
+                self.unattached_state = PropUnattachedState::AirborneBounced;
+            }
+        } else {
+            // bounce off the wall (or ceiling), staying where the prop was
+            let speed_into_wall = vec3::dot(&self.trajectory_velocity, &normal_unit);
+            if speed_into_wall < 0.0 {
+                let mut reflect = normal_unit;
+                vec3_inplace_scale(&mut reflect, -2.0 * speed_into_wall);
+                vec3_inplace_add_vec(&mut self.trajectory_velocity, &reflect);
+            }
+            self.trajectory_velocity[0] *= params.lateral_vel_decay_after_bounce;
+            self.trajectory_velocity[2] *= params.lateral_vel_decay_after_bounce;
+        }
+    }
+
+    /// End this prop's trajectory, standing it upright and spinning it in place for a bit.
+    fn land(&mut self, params: &PropParams, tick_rate: &TickRate) {
+        vec3::zero(&mut self.trajectory_velocity);
+        self.unattached_state = PropUnattachedState::Normal;
+        self.flags2.remove(PropFlags2::UnderTrajectory);
+        self.flags.remove(PropFlags1::DetachedWhileKatamariStuck);
+
+        self.rotation_vec[0] = 0.0;
+        self.rotation_vec[2] = 0.0;
+        self.landed_spin_ticks = tick_rate.duration(params.spin_after_landing_ticks as u16);
+
+        if self.global_state == PropGlobalState::AirborneIntangible {
+            self.global_state = PropGlobalState::Unattached;
+        }
+    }
+
+    /// Spin this prop around in place after it lands, slowing down until it stops.
+    fn update_landed_spin(&mut self, params: &PropParams, tick_rate: &TickRate) {
+        // TODO_PARAM
+        let LANDED_SPIN_SPEED = 0.3;
+
+        if self.landed_spin_ticks == 0 {
+            return;
+        }
+
+        let total_ticks = tick_rate.duration(params.spin_after_landing_ticks as u16);
+        let spin_speed = LANDED_SPIN_SPEED * self.landed_spin_ticks as f32 / total_ticks as f32;
+        self.rotation_vec[1] =
+            normalize_bounded_angle(self.rotation_vec[1] + tick_rate.per_tick(spin_speed));
+        self.landed_spin_ticks -= 1;
+    }
+
+    /// Computes the lowest point (in local space) on this prop's AABB after transforming
+    /// the AABB with its current rotation.
+    fn min_aabb_y(&self) -> f32 {
+        if self.aabb_vertices.is_empty() {
+            return 0.0;
+        }
+
+        let mut transform = self.unattached_transform;
+        set_translation!(transform, [0.0; 3]);
+        min_transformed_y(&self.aabb_vertices, &transform)
+    }
 
     /// Update logic for props which have a parent.
     /// offset: 0x2e030
@@ -1702,18 +2026,44 @@ impl Prop {
             return;
         }
 
-        // TODO_PROPS: `prop_update_link:20-`
+        // a child falls off of its parent if the parent is destroyed
+        if let Some(parent_ref) = self.get_parent_ref() {
+            if parent_ref.borrow().is_disabled() {
+                return self.detach_from_parent();
+            }
+        }
+
+        // children without motion of their own stay where they are on their parent, and the
+        // rest move on their own while being carried by their parent
+        let follows_parent = self.move_type.is_none()
+            && !self.is_under_trajectory()
+            && self.link_action != Some(PropLinkAction::MaybeIgnoreParent);
+        self.flags2.set(PropFlags2::FollowParent, follows_parent);
+    }
+
+    /// The parent of this prop, if it has one (and prop links have been hydrated).
+    fn get_parent_ref(&self) -> Option<PropRef> {
+        if self.parent == Some(self.ctrl_idx) {
+            return None;
+        }
+
+        self.parent_ref.as_ref().and_then(Weak::upgrade)
     }
 
     /// Compute the distance from this prop to other players and cache those
     /// distances on the prop for later use.
     /// offset: 0x50290
-    pub fn cache_distance_to_players(&mut self, player: &Player) {
+    pub fn cache_distance_to_players(&mut self, players: &[Player]) {
         self.last_dist_to_p0 = self.dist_to_p0;
-        self.dist_to_p0 = vec3::distance(&self.pos, player.katamari.get_center());
+        self.dist_to_p0 = vec3::distance(&self.pos, players[0].katamari.get_center());
 
-        // TODO_VS: `prop_cache_distance_to_players:18+` (cache distance to other players)
+        // in vs mode, also cache the distance to the second player
+        if let Some(player) = players.get(1) {
+            self.last_dist_to_p1 = self.dist_to_p1;
+            self.dist_to_p1 = vec3::distance(&self.pos, player.katamari.get_center());
+        }
     }
+
     /// Populate this prop's references to linked props (its `parent_ref`, `first_child_ref`, and
     /// `next_sibling_ref`) from its corresponding control index fields (`parent`, etc.).
     pub fn hydrate_prop_links(
@@ -1733,16 +2083,24 @@ impl Prop {
     /// Update a prop's transform when it is not a linked child and not stalled.
     /// offset: 0x51d90
     fn update_transform_normal(&mut self) {
+        let mut transform = mat4::create();
+        self.compute_unattached_transform(&mut transform, &self.pos, &self.rotation_vec);
+        self.unattached_transform = transform;
+    }
+
+    /// Compute the unattached transform of this prop if it had the position `pos` and the
+    /// Euler angles `rotation_vec`.
+    fn compute_unattached_transform(&self, out: &mut Mat4, pos: &Vec3, rotation_vec: &Vec3) {
         let mut temp1 = mat4::create();
         let mut temp2 = mat4::create();
 
-        mat4::rotate_z(&mut temp2, &temp1, self.rotation_vec[2]);
-        mat4::rotate_x(&mut temp1, &temp2, self.rotation_vec[0]);
-        mat4::rotate_y(&mut temp2, &temp1, self.rotation_vec[1]);
+        mat4::rotate_z(&mut temp2, &temp1, rotation_vec[2]);
+        mat4::rotate_x(&mut temp1, &temp2, rotation_vec[0]);
+        mat4::rotate_y(&mut temp2, &temp1, rotation_vec[1]);
 
-        mat4::multiply(&mut self.unattached_transform, &self.rotation_mat, &temp2);
+        mat4::multiply(out, &self.rotation_mat, &temp2);
 
-        set_translation!(self.unattached_transform, self.pos);
+        set_translation!(out, pos);
     }
 
     /// Update a prop's transform when it is not a linked child and stalled.
@@ -1754,7 +2112,17 @@ impl Prop {
     /// Update a prop's transform when it is a linked child, not moving, and not stalled.
     /// offset: 0x518a0
     fn update_transform_stationary_child(&mut self) {
-        // TODO_LINKS
+        let Some(parent_ref) = self.get_parent_ref() else {
+            return self.update_transform_normal();
+        };
+
+        // keep the same transform relative to the parent
+        mat4::multiply(
+            &mut self.unattached_transform,
+            parent_ref.borrow().get_unattached_transform(),
+            &self.parent_offset_transform,
+        );
+        mat4::get_translation(&mut self.pos, &self.unattached_transform);
     }
 
     /// Update a prop's transform when it is a linked child, not moving, and stalled.
@@ -1767,7 +2135,15 @@ impl Prop {
     /// Update a prop's transform when it is a linked child, moving, and not stalled.
     /// offset: 0x51f60
     fn update_transform_moving_child(&mut self) {
-        // TODO_LINKS
+        // the child is carried along by the parent's motion on top of its own motion
+        let parent_motion = self
+            .get_parent_ref()
+            .and_then(|parent_ref| parent_ref.borrow().get_tick_motion());
+        if let Some(motion) = parent_motion {
+            self.move_by(&motion);
+        }
+
+        self.update_transform_normal();
     }
 
     /// Update a prop's transform when it is a linked child, moving, and stalled.
@@ -1777,3 +2153,137 @@ impl Prop {
         // TODO_STALLS
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use gl_matrix::mat4;
+
+    use crate::{
+        collision::{raycast_state::RaycastState, world_collider::StageMeshCollider},
+        constants::FRAC_PI_2,
+        delegates::{has_delegates::HasDelegates, Delegates},
+        global::GlobalState,
+        props::{
+            params::PropParams,
+            prop::{
+                ParentAttachedReaction, PropFlags1, PropGlobalState, PropLinkAction,
+                PropTrajectoryType, PropUnattachedState,
+            },
+        },
+    };
+
+    use super::Prop;
+
+    #[test]
+    fn test_airborne_prop_bounces_and_lands() {
+        let params = PropParams::default();
+        let mut global = GlobalState::default();
+
+        // a flat floor at y = 0
+        let collider = StageMeshCollider::from_obj_str(
+            "v -100 0 -100\nv -100 0 100\nv 100 0 100\nv 100 0 -100\nf 1 2 3 4",
+        )
+        .unwrap();
+        let delegates = Rc::new(RefCell::new(Delegates::default()));
+        delegates.borrow_mut().world_collider = Some(Rc::new(collider));
+        let raycasts = Rc::new(RefCell::new(RaycastState::default()));
+        raycasts.borrow_mut().set_delegates_ref(&delegates);
+
+        let mut prop = Prop {
+            rotation_mat: mat4::create(),
+            pos: [0.0, 100.0, 0.0],
+            move_type: Some(1),
+            ..Default::default()
+        };
+
+        // a prop knocked airborne by the katamari can't be collected while it flies
+        prop.apply_trajectory(
+            &[1.0, 2.0, 0.0],
+            PropTrajectoryType::HitAirborne,
+            1.0,
+            &mut global,
+        );
+        assert_eq!(prop.global_state, PropGlobalState::AirborneIntangible);
+        assert!(prop.is_under_trajectory());
+        assert_eq!(global.airborne_prop_gravity, 1.0);
+
+        // it falls and bounces off of the floor, then lands after its last bounce
+        let mut bounced = false;
+        for _ in 0..300 {
+            prop.update_global_state(&params, &global, &raycasts);
+            bounced |= prop.unattached_state == PropUnattachedState::AirborneBounced;
+            assert!(prop.pos[1] >= -0.001);
+            if !prop.is_under_trajectory() {
+                break;
+            }
+        }
+        assert!(bounced);
+        assert!(prop.airborne_bounces > 1);
+        assert!(!prop.is_under_trajectory());
+        assert!(prop.pos[1].abs() < 0.001);
+        assert!(prop.pos[0] > 0.0);
+        assert_eq!(prop.global_state, PropGlobalState::Unattached);
+        assert_eq!(prop.unattached_state, PropUnattachedState::Normal);
+        assert_eq!(prop.move_type, Some(1));
+
+        // once it lands, it spins in place until it settles
+        let landed_pos = prop.pos;
+        for _ in 0..params.spin_after_landing_ticks {
+            assert!(prop.landed_spin_ticks > 0);
+            prop.update_global_state(&params, &global, &raycasts);
+        }
+        assert_eq!(prop.landed_spin_ticks, 0);
+        assert_ne!(prop.rotation_vec[1], 0.0);
+        assert_eq!(prop.pos, landed_pos);
+    }
+
+    #[test]
+    fn test_child_follows_parent() {
+        let parent_ref = Rc::new(RefCell::new(Prop {
+            rotation_mat: mat4::create(),
+            pos: [100.0, 0.0, 0.0],
+            ..Default::default()
+        }));
+        let child_ref = Rc::new(RefCell::new(Prop {
+            ctrl_idx: 1,
+            rotation_mat: mat4::create(),
+            pos: [110.0, 5.0, 0.0],
+            link_action: Some(PropLinkAction::ReactWhenParentAttached),
+            parent: Some(0),
+            parent_ref: Some(Rc::downgrade(&parent_ref)),
+            flags: PropFlags1::HasParent,
+            ..Default::default()
+        }));
+        parent_ref.borrow_mut().first_child_ref = Some(child_ref.clone());
+
+        assert!(parent_ref.borrow().has_reactive_child());
+
+        let mut child = child_ref.borrow_mut();
+        child.update_rotation_from_parent(&parent_ref.borrow());
+        assert_eq!(
+            child.get_parent_attached_reaction(),
+            ParentAttachedReaction::StartAltAction
+        );
+
+        // a quarter turn of the parent carries the child a quarter turn around it
+        {
+            let mut parent = parent_ref.borrow_mut();
+            parent.rotation_vec[1] = FRAC_PI_2;
+            parent.update_transform_unattached();
+        }
+        child.update_child_link();
+        child.update_transform_unattached();
+        assert!((child.pos[0] - 100.0).abs() < 0.001);
+        assert!((child.pos[1] - 5.0).abs() < 0.001);
+        assert!((child.pos[2] + 10.0).abs() < 0.001);
+
+        // the child stays where it is when its parent is destroyed
+        parent_ref.borrow_mut().destroy();
+        child.update_child_link();
+        child.update_transform_unattached();
+        assert!(!child.has_parent());
+        assert!((child.pos[2] + 10.0).abs() < 0.001);
+    }
+}
@@ -0,0 +1,292 @@
+use std::{cell::RefCell, collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    global::{rng::RngState, GlobalState},
+    mission::Mission,
+    props::{config::NAME_PROP_CONFIGS, prop::AddPropArgs},
+};
+
+use super::{data::RANDOM_GROUP_CONFIG, RandomPropsState};
+
+/// The path of the random layout file read at `MonoInitStart`, which pins the random prop
+/// layout of missions (see `RandomLayoutFile`).
+pub const RANDOM_LAYOUT_PATH: &str =
+    "C:\\Program Files (x86)\\Steam\\steamapps\\common\\Katamari Damacy REROLL\\random_layout.json";
+
+/// The values of an `RngState`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RngSeed {
+    pub rng1: u32,
+    pub rng2: u32,
+}
+
+impl From<RngSeed> for RngState {
+    fn from(seed: RngSeed) -> Self {
+        RngState::new(seed.rng1, seed.rng2)
+    }
+}
+
+impl From<&RngState> for RngSeed {
+    fn from(rng: &RngState) -> Self {
+        Self {
+            rng1: rng.rng1.0,
+            rng2: rng.rng2,
+        }
+    }
+}
+
+/// Overrides the random prop layout of one mission, which is otherwise decided by the rng
+/// state at `MonoInitStart`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RandomLayoutOverride {
+    /// If set, the rng state at `MonoInitStart`, before any random group is sampled.
+    pub seed: Option<RngSeed>,
+
+    /// The name indices of the props of each random group (keyed by group index), in the
+    /// order the props are added. Props past the end of a group's list keep the name index
+    /// sampled from the group.
+    pub groups: BTreeMap<u16, Vec<u16>>,
+}
+
+/// A text (JSON) representation of the random layout overrides of every mission, keyed by
+/// mission index. The runner's `layout` command writes a predicted layout in this format.
+///
+/// ```json
+/// {
+///   "1": { "seed": { "rng1": 4321, "rng2": 0 } },
+///   "2": { "groups": { "0": [454], "3": [45, 42] } }
+/// }
+/// ```
+///
+/// A mission's `seed` replaces the rng state at `MonoInitStart` (which changes every
+/// random group, and everything else that's random). Its `groups` replace the name indices
+/// sampled from those groups, without changing the rng.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RandomLayoutFile {
+    pub missions: BTreeMap<u8, RandomLayoutOverride>,
+}
+
+impl RandomLayoutFile {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let file: Self =
+            serde_json::from_str(json).map_err(|e| format!("error parsing random layout: {e}"))?;
+
+        for (&mission, layout_override) in file.missions.iter() {
+            let Some(mission_config) = RANDOM_GROUP_CONFIG.get(mission as usize) else {
+                return Err(format!("unknown mission {mission}"));
+            };
+
+            for (&group_idx, name_idxs) in layout_override.groups.iter() {
+                if group_idx as usize >= mission_config.groups.len() {
+                    return Err(format!("mission {mission} has no random group {group_idx}"));
+                }
+
+                if let Some(name_idx) = name_idxs
+                    .iter()
+                    .find(|&&name_idx| name_idx as usize >= NAME_PROP_CONFIGS.len())
+                {
+                    return Err(format!(
+                        "random group {group_idx} of mission {mission} has unknown name index {name_idx}"
+                    ));
+                }
+            }
+        }
+
+        Ok(file)
+    }
+}
+
+thread_local! {
+    static RANDOM_LAYOUTS: RefCell<RandomLayoutFile> = RefCell::new(RandomLayoutFile::default());
+}
+
+/// Returns the random layout override of `mission`, if it has one.
+pub fn random_layout_override(mission: Mission) -> Option<RandomLayoutOverride> {
+    RANDOM_LAYOUTS.with(|layouts| layouts.borrow().missions.get(&(mission as u8)).cloned())
+}
+
+/// Replace the current random layout overrides.
+pub fn set_random_layouts(layouts: RandomLayoutFile) {
+    RANDOM_LAYOUTS.with(|current| current.replace(layouts));
+}
+
+/// Replace the current random layout overrides with the random layout file at `path`,
+/// or remove them if there is no such file (or if it can't be loaded). Returns the
+/// overrides that were loaded, so that they can be recorded.
+pub fn load_random_layout_file(path: &Path) -> Result<RandomLayoutFile, String> {
+    let result = match std::fs::read_to_string(path) {
+        Ok(json) => RandomLayoutFile::from_json(&json),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(RandomLayoutFile::default()),
+        Err(e) => Err(e.to_string()),
+    };
+
+    set_random_layouts(result.clone().unwrap_or_default());
+    result
+}
+
+/// A prop placed in a random group, with the name index it will be resolved to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PredictedProp {
+    /// The index of the prop's placement (which is its control index).
+    pub placement: usize,
+
+    /// The name index of the placement, which the prop keeps if its group runs out.
+    pub default_name_idx: u16,
+
+    /// The name index of the prop after sampling its random group.
+    pub name_idx: u16,
+}
+
+/// The resolved props of every random group of a mission.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PredictedLayout {
+    /// The rng state before any random group is sampled.
+    pub seed: RngSeed,
+
+    /// The props of each random group, keyed by group index, in the order they're added.
+    pub groups: BTreeMap<u16, Vec<PredictedProp>>,
+}
+
+impl PredictedLayout {
+    /// An override which pins this layout, regardless of the rng state.
+    pub fn to_override(&self) -> RandomLayoutOverride {
+        RandomLayoutOverride {
+            seed: None,
+            groups: self
+                .groups
+                .iter()
+                .map(|(&group_idx, props)| {
+                    (group_idx, props.iter().map(|prop| prop.name_idx).collect())
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Predict the props that each random group of `mission` resolves to when the props in
+/// `placements` are added (in order) with the initial rng state `rng`, and the layout
+/// override `layout_override` (if any) is applied at `MonoInitStart`.
+/// Random groups are the only thing that advances the rng while props are added, so this
+/// gives the same layout as `MonoInitAddProp`.
+pub fn predict_layout(
+    mission: Mission,
+    placements: &[AddPropArgs],
+    rng: RngState,
+    layout_override: Option<&RandomLayoutOverride>,
+) -> PredictedLayout {
+    let mut global = GlobalState {
+        rng,
+        ..Default::default()
+    };
+    let mut random = RandomPropsState::default();
+    random.reset();
+
+    if let Some(layout_override) = layout_override {
+        random.apply_layout_override(&mut global, layout_override);
+    }
+
+    let seed = RngSeed::from(&global.rng);
+    let mut groups: BTreeMap<u16, Vec<PredictedProp>> = BTreeMap::new();
+
+    // mimick `PropsState::add_prop` and `Prop::new`
+    for (placement, args) in placements.iter().enumerate() {
+        if args.loc_pos_type == 0 {
+            continue;
+        }
+
+        let group_idx = args.random_group_id as usize;
+        random.record_random_prop(&mut global, mission as usize, group_idx);
+        let name_idx = random.sample_group(&mut global, group_idx);
+
+        groups
+            .entry(args.random_group_id)
+            .or_default()
+            .push(PredictedProp {
+                placement,
+                default_name_idx: args.name_idx,
+                name_idx: name_idx.unwrap_or(args.name_idx),
+            });
+    }
+
+    PredictedLayout { seed, groups }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{global::rng::RngState, mission::Mission, props::prop::AddPropArgs};
+
+    use super::{predict_layout, RandomLayoutFile, RngSeed};
+
+    fn placement(loc_pos_type: u16, random_group_id: u16) -> AddPropArgs {
+        AddPropArgs {
+            pos_x: 0.0,
+            pos_y: 0.0,
+            pos_z: 0.0,
+            rot_x: 0.0,
+            rot_y: 0.0,
+            rot_z: 0.0,
+            rot_w: 1.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            scale_z: 1.0,
+            name_idx: 100,
+            loc_pos_type,
+            random_group_id,
+            mono_move_type: 0xffff,
+            mono_hit_on_area: 0xffff,
+            link_action: 0xffff,
+            extra_action_type: 0xffff,
+            unique_name_id: 0xffff,
+            disp_off_area_no: 0xffff,
+            vs_drop_flag: 0,
+            comment_id: 0xffff,
+            comment_group_id: 0xffff,
+            twin_id: 0xffff,
+            shake_off_flag: 0,
+        }
+    }
+
+    #[test]
+    fn test_predict_layout() {
+        // two props in MAS1's random group 1 (which holds one each of name indices 846 and
+        // 837), and one prop that isn't random
+        let placements = [placement(1, 1), placement(0, 0xffff), placement(1, 1)];
+
+        let layout = predict_layout(Mission::MAS1, &placements, RngState::default(), None);
+        assert_eq!(
+            layout.seed,
+            RngSeed {
+                rng1: 4321,
+                rng2: 0
+            }
+        );
+        assert_eq!(layout.groups.len(), 1);
+
+        let props = &layout.groups[&1];
+        assert_eq!(props.len(), 2);
+        assert_eq!(props[0].placement, 0);
+        assert_eq!(props[1].placement, 2);
+        let mut name_idxs = [props[0].name_idx, props[1].name_idx];
+        name_idxs.sort();
+        assert_eq!(name_idxs, [837, 846]);
+
+        // pinning the layout swaps the props, without changing the rng
+        let mut pinned = layout.to_override();
+        pinned.groups.get_mut(&1).unwrap().reverse();
+        let swapped = predict_layout(
+            Mission::MAS1,
+            &placements,
+            RngState::default(),
+            Some(&pinned),
+        );
+        assert_eq!(swapped.groups[&1][0].name_idx, props[1].name_idx);
+        assert_eq!(swapped.groups[&1][1].name_idx, props[0].name_idx);
+
+        assert!(RandomLayoutFile::from_json(r#"{ "1": { "groups": { "1": [846] } } }"#).is_ok());
+        assert!(RandomLayoutFile::from_json(r#"{ "1": { "groups": { "999": [846] } } }"#).is_err());
+    }
+}
@@ -1,10 +1,13 @@
+use std::collections::VecDeque;
+
 use serde::{Deserialize, Serialize};
 
 use crate::global::GlobalState;
 
-use self::data::RANDOM_GROUP_CONFIG;
+use self::{data::RANDOM_GROUP_CONFIG, layout::RandomLayoutOverride};
 
 mod data;
+pub mod layout;
 
 /// The configuration for a pool item in a random group.
 #[derive(Debug, Default)]
@@ -71,6 +74,10 @@ pub struct RandomGroup {
     pool_size: u16,
 
     pool: Vec<PoolItem>,
+
+    /// The name indices which replace the next samples of this group, set by a
+    /// `RandomLayoutOverride`.
+    forced_name_idxs: VecDeque<u16>,
 }
 
 impl RandomGroup {
@@ -198,6 +205,28 @@ impl RandomPropsState {
     }
 
     pub fn sample_group(&mut self, global: &mut GlobalState, group_idx: usize) -> Option<u16> {
-        self.groups[group_idx].sample(global)
+        let group = &mut self.groups[group_idx];
+
+        // a forced name index still samples the group, so that the rng advances as usual
+        let sampled = group.sample(global);
+        group.forced_name_idxs.pop_front().or(sampled)
+    }
+
+    /// Apply `layout_override` before any props are added: replace the rng state with its
+    /// seed, and force the name indices of its random groups.
+    pub fn apply_layout_override(
+        &mut self,
+        global: &mut GlobalState,
+        layout_override: &RandomLayoutOverride,
+    ) {
+        if let Some(seed) = layout_override.seed {
+            global.rng = seed.into();
+        }
+
+        for (&group_idx, name_idxs) in layout_override.groups.iter() {
+            if let Some(group) = self.groups.get_mut(group_idx as usize) {
+                group.forced_name_idxs = name_idxs.iter().copied().collect();
+            }
+        }
     }
 }
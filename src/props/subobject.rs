@@ -0,0 +1,16 @@
+use gl_matrix::common::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// A moving part of a prop (e.g. a wheel or a propeller), which has its own AABB in the
+/// prop's mono data. Unity positions the subobject's model with `GetSubObjectPosition`.
+// TODO_SUBOBJECT: subobject scripts (nothing moves subobjects yet)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PropSubobject {
+    /// (??) The position of this subobject (presumably relative to the prop).
+    /// offset: 0x18
+    pub pos: Vec3,
+
+    /// (??) The Euler angles of this subobject
+    /// offset: 0x28
+    pub rot_vec: Vec3,
+}
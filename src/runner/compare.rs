@@ -0,0 +1,61 @@
+use std::{io::BufRead, path::Path, rc::Rc};
+
+use crate::{
+    gamestate::GameState,
+    movie::{Movie, MoviePlayer},
+    trace::FrameTrace,
+};
+
+use super::RunArgs;
+
+/// Replay a movie headlessly and compare the state after every tick against a trace of
+/// the original simulation playing the same movie, reporting the first frame and field
+/// where the two diverge.
+pub fn compare(args: &[String]) -> Result<(), String> {
+    let args = RunArgs::parse(args, 3)?;
+
+    let movie_path = &args.positional[1];
+    let movie = Movie::read_from_file(Path::new(movie_path)).map_err(|e| e.to_string())?;
+
+    let trace_path = &args.positional[2];
+    let trace_file = std::fs::File::open(trace_path)
+        .map_err(|e| format!("error reading trace {trace_path:?}: {e}"))?;
+    let mut trace_lines = std::io::BufReader::new(trace_file).lines();
+
+    let mono_data = RunArgs::read_mono_data(&args.positional[0])?;
+    let world_collider = args.load_world_collider()?;
+
+    let mut state = GameState::new();
+    state.reset();
+    state.set_world_collider(Some(Rc::new(world_collider)));
+
+    let mut player = MoviePlayer::new(&movie);
+    let mut frame = 0;
+    while !args.reached_frame_limit(frame) {
+        let Some(line) = trace_lines.next() else {
+            break;
+        };
+        let line = line.map_err(|e| format!("error reading trace: {e}"))?;
+        let expected: FrameTrace = serde_json::from_str(&line)
+            .map_err(|e| format!("error parsing trace line {}: {e}", frame + 1))?;
+
+        let ticked = player
+            .play_frame(&mut state, &mono_data)
+            .map_err(|e| e.to_string())?;
+        if !ticked {
+            break;
+        }
+        frame += 1;
+
+        let actual = FrameTrace::new(&state);
+        if let Some(divergence) = actual.find_divergence(&expected, args.tolerance) {
+            return Err(format!(
+                "diverged on frame {frame} (tick {}): {divergence}",
+                expected.frame
+            ));
+        }
+    }
+
+    println!("no divergence in {frame} frames");
+    Ok(())
+}
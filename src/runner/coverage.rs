@@ -0,0 +1,265 @@
+use std::{collections::BTreeMap, io::Write, rc::Rc};
+
+use serde::Serialize;
+
+use crate::{
+    collision::world_collider::StageMeshCollider,
+    gamestate::{GameState, MonoInitStartArgs},
+    mission::config::MissionConfig,
+    props::{
+        motion::{actions::MotionActionState, alt_action::AltActionTrigger},
+        prop::Prop,
+    },
+};
+
+use super::{
+    apply_command,
+    script::{parse_script, ScriptCommand},
+    RunArgs,
+};
+
+/// The motion coverage of one mission's props.
+#[derive(Debug, Serialize)]
+struct CoverageReport {
+    mission: u8,
+
+    /// The number of props placed in the mission.
+    num_props: usize,
+
+    /// The number of props with a move type (i.e. props that should move).
+    num_moving_props: usize,
+
+    /// The number of moving props which will do something other than they would in the
+    /// original simulation (see `MotionCoverage::is_complete`).
+    num_incomplete_props: usize,
+
+    /// `true` if every moving prop in the mission moves as it would in the original simulation.
+    complete: bool,
+
+    /// The coverage of each distinct motion in the mission, ordered by the number of props
+    /// with that motion (most first).
+    motions: Vec<MotionCoverage>,
+}
+
+/// The props of a mission which share motion actions and a behavior.
+#[derive(Debug, Serialize)]
+struct MotionCoverage {
+    action: Option<u16>,
+    alt_action: Option<u16>,
+    behavior: Option<i16>,
+    num_props: usize,
+
+    /// `true` if the mission has no move type data for the props' move type, so they have
+    /// no actions.
+    missing_move_type: bool,
+
+    /// `true` if `action` parses to `MotionActionState::Unimplemented`, or to an action standing
+    /// in for an unported one (see `MotionActionState::is_stand_in`).
+    unimplemented_action: bool,
+
+    /// `true` if `alt_action` is unimplemented, in the same sense as `unimplemented_action`.
+    unimplemented_alt_action: bool,
+
+    /// `true` if `behavior`'s alt action predicate is still a stub (see
+    /// `AltActionTrigger::is_stubbed`), so its props never switch to their alt action.
+    stubbed_predicate: bool,
+}
+
+impl MotionCoverage {
+    fn new(prop: &Prop) -> Self {
+        let action = prop.get_motion_action();
+        let alt_action = prop.alt_motion_action;
+        let behavior = prop.get_behavior();
+
+        let is_unimplemented = |action_id: Option<u16>| {
+            action_id.is_some_and(|action_id| {
+                !MotionActionState::parse_id(action_id).is_implemented()
+                    || MotionActionState::is_stand_in(action_id, behavior)
+            })
+        };
+
+        Self {
+            action,
+            alt_action,
+            behavior,
+            num_props: 0,
+            missing_move_type: action.is_none(),
+            unimplemented_action: is_unimplemented(action),
+            // an alt action of 0 means the prop has no alt action
+            unimplemented_alt_action: alt_action != Some(0) && is_unimplemented(alt_action),
+            stubbed_predicate: behavior
+                .is_some_and(|behavior| AltActionTrigger::from_behavior(behavior).is_stubbed()),
+        }
+    }
+
+    fn key(&self) -> (Option<u16>, Option<u16>, Option<i16>) {
+        (self.action, self.alt_action, self.behavior)
+    }
+
+    /// Returns `true` if props with this motion move as they would in the original simulation.
+    fn is_complete(&self) -> bool {
+        !(self.missing_move_type
+            || self.unimplemented_action
+            || self.unimplemented_alt_action
+            || self.stubbed_predicate)
+    }
+}
+
+impl CoverageReport {
+    fn new(state: &GameState) -> Self {
+        let mut motions = BTreeMap::new();
+        let mut num_moving_props = 0;
+
+        for prop_ref in state.props.props.iter() {
+            let prop = prop_ref.borrow();
+            if prop.get_move_type().is_none() {
+                continue;
+            }
+
+            num_moving_props += 1;
+            let motion = MotionCoverage::new(&prop);
+            motions.entry(motion.key()).or_insert(motion).num_props += 1;
+        }
+
+        let mut motions: Vec<_> = motions.into_values().collect();
+        motions.sort_by_key(|motion| std::cmp::Reverse(motion.num_props));
+
+        let num_incomplete_props = motions
+            .iter()
+            .filter(|motion| !motion.is_complete())
+            .map(|motion| motion.num_props)
+            .sum();
+
+        Self {
+            mission: state.mission_state.mission as u8,
+            num_props: state.props.props.len(),
+            num_moving_props,
+            num_incomplete_props,
+            complete: num_incomplete_props == 0,
+            motions,
+        }
+    }
+}
+
+/// Initialize `mission` up to `MonoInitEnd`, placing the props of the init commands in
+/// `commands`.
+fn place_props(
+    mono_data: &[u8],
+    mission: u8,
+    area: u8,
+    stage: u8,
+    commands: &[ScriptCommand],
+) -> Result<GameState, String> {
+    let mut state = GameState::new();
+    state.reset();
+    state.set_world_collider(Some(Rc::new(StageMeshCollider::default())));
+    state
+        .mono_init_start(
+            mono_data,
+            &MonoInitStartArgs {
+                mission,
+                area,
+                stage,
+                ..Default::default()
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    for command in commands.iter().filter(|c| c.is_init_command()) {
+        apply_command(&mut state, command);
+    }
+
+    state.mono_init_end();
+    Ok(state)
+}
+
+/// Report how much of the motion of a mission's props is implemented: place the props in the
+/// `prop` and `parent` lines of an input script (as `run` does), then count the props with
+/// each distinct motion, flagging the motions that use unimplemented actions or alt action
+/// predicates. The report is written as a single JSON object.
+pub fn coverage(args: &[String]) -> Result<(), String> {
+    let args = RunArgs::parse(args, 3)?;
+    let mission: u8 = args.positional[0]
+        .parse()
+        .map_err(|_| format!("bad mission: {}", args.positional[0]))?;
+
+    let script_path = &args.positional[2];
+    let script = std::fs::read_to_string(script_path)
+        .map_err(|e| format!("error reading script {script_path:?}: {e}"))?;
+    let commands = parse_script(&script)?;
+
+    let mono_data = RunArgs::read_mono_data(&args.positional[1])?;
+    args.load_motion_data()?;
+    args.load_random_layout()?;
+    let mut out = args.open_output()?;
+
+    let stage = args.stage.unwrap_or_else(|| {
+        let mut mission_config = MissionConfig::default();
+        MissionConfig::get(&mut mission_config, mission);
+        mission_config.stage.into()
+    });
+
+    let state = place_props(&mono_data, mission, args.area, stage, &commands)?;
+
+    let report = CoverageReport::new(&state);
+    serde_json::to_writer_pretty(&mut out, &report).map_err(|e| e.to_string())?;
+    writeln!(out).map_err(|e| e.to_string())?;
+    out.flush().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{mission::config::MissionConfig, runner::script::parse_script};
+
+    use super::{place_props, CoverageReport};
+
+    #[test]
+    fn test_coverage_report() {
+        let mono_data = include_bytes!("../bin/monodata/mission1.bin");
+
+        // one prop without a move type, one prop with an unimplemented action (move type 0
+        // in MAS1), two swaying props (move type 4), and one prop whose actions are both
+        // implemented but whose alt action predicate isn't (move type 15, behavior 0x1f)
+        let script = "
+            prop 0 0 0 0 0 0 1 1 1 1 100 0 65535 65535 65535 65535 65535 65535 65535 0 65535 65535 65535 0
+            prop 0 0 1 0 0 0 1 1 1 1 100 0 65535 0 65535 65535 65535 65535 65535 0 65535 65535 65535 0
+            prop 0 0 2 0 0 0 1 1 1 1 100 0 65535 4 65535 65535 65535 65535 65535 0 65535 65535 65535 0
+            prop 0 0 3 0 0 0 1 1 1 1 100 0 65535 4 65535 65535 65535 65535 65535 0 65535 65535 65535 0
+            prop 0 0 4 0 0 0 1 1 1 1 100 0 65535 15 65535 65535 65535 65535 65535 0 65535 65535 65535 0
+        ";
+        let commands = parse_script(script).unwrap();
+        let mut mission_config = MissionConfig::default();
+        MissionConfig::get(&mut mission_config, 1);
+        let stage = mission_config.stage.into();
+
+        let state = place_props(mono_data, 1, 0, stage, &commands).unwrap();
+        let report = CoverageReport::new(&state);
+
+        assert_eq!(report.num_props, 5);
+        assert_eq!(report.num_moving_props, 4);
+        assert_eq!(report.num_incomplete_props, 2);
+        assert!(!report.complete);
+
+        assert_eq!(report.motions[0].num_props, 2);
+        assert_eq!(report.motions[0].behavior, Some(0x20));
+        assert!(report.motions[0].is_complete());
+
+        let unimplemented = report
+            .motions
+            .iter()
+            .find(|m| m.behavior.is_none())
+            .unwrap();
+        assert!(unimplemented.unimplemented_action);
+
+        // the implemented actions don't make the motion complete while its predicate is unknown
+        let unknown_trigger = report
+            .motions
+            .iter()
+            .find(|m| m.behavior == Some(0x1f))
+            .unwrap();
+        assert!(!unknown_trigger.unimplemented_action);
+        assert!(!unknown_trigger.unimplemented_alt_action);
+        assert!(unknown_trigger.stubbed_predicate);
+        assert!(!unknown_trigger.is_complete());
+    }
+}
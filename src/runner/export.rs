@@ -0,0 +1,606 @@
+use std::io::Write;
+
+use gl_matrix::common::Vec3;
+use serde_json::{json, Value};
+
+use crate::{
+    collision::{
+        hit_attribute::HitAttribute,
+        mesh::{Mesh, TriGroup, TriVertex},
+    },
+    mono_data::{MonoData, PropMonoData},
+    props::config::NamePropConfig,
+};
+
+use super::RunArgs;
+
+/// The file formats that mono data can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    /// Wavefront OBJ, with vertex colors written after each vertex position.
+    Obj,
+    /// glTF (`.gltf`), with its buffer embedded as a base64 data URI.
+    Gltf,
+    /// Binary glTF (`.glb`).
+    Glb,
+}
+
+/// The `--format` option if it's set, or otherwise the extension of the `--out` path
+/// (or `default` if neither is set).
+pub(super) fn format_name(args: &RunArgs, default: &str) -> String {
+    match (&args.format, &args.out_path) {
+        (Some(format), _) => format.clone(),
+        (None, Some(path)) => path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default(),
+        (None, None) => default.to_string(),
+    }
+}
+
+impl ExportFormat {
+    /// The `--format` option if it's set, or otherwise the format implied by the extension
+    /// of the `--out` path (defaulting to OBJ).
+    fn from_args(args: &RunArgs) -> Result<Self, String> {
+        let format = format_name(args, "obj");
+
+        match format.as_str() {
+            "obj" => Ok(Self::Obj),
+            "gltf" => Ok(Self::Gltf),
+            "glb" => Ok(Self::Glb),
+            _ => Err(format!(
+                "unknown export format `{format}` (expected obj, gltf, or glb)"
+            )),
+        }
+    }
+}
+
+/// How the vertices of an `ExportPrimitive` are assembled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PrimitiveMode {
+    Points,
+    /// Each pair of vertices is a line segment.
+    Lines,
+    /// Each vertex is joined to the next by a line segment.
+    LineStrip,
+    /// Each triple of vertices is a triangle.
+    Triangles,
+}
+
+impl PrimitiveMode {
+    /// The number of vertices in each element of the primitive, or `None` if the whole
+    /// primitive is a single element (a line strip).
+    fn vertices_per_element(&self) -> Option<usize> {
+        match self {
+            Self::Points => Some(1),
+            Self::Lines => Some(2),
+            Self::LineStrip => None,
+            Self::Triangles => Some(3),
+        }
+    }
+
+    /// The glTF `mode` of the primitive.
+    fn gltf_mode(&self) -> u32 {
+        match self {
+            Self::Points => 0,
+            Self::Lines => 1,
+            Self::LineStrip => 3,
+            Self::Triangles => 4,
+        }
+    }
+}
+
+/// A list of unindexed points, lines, or triangles. Each vertex carries the metadata value
+/// of its element (a `HitAttribute` for prop meshes, or a zone index for the zone mesh),
+/// which is exported as its vertex color.
+#[derive(Debug)]
+pub(super) struct ExportPrimitive {
+    pub name: String,
+    pub mode: PrimitiveMode,
+    pub points: Vec<Vec3>,
+    pub metadata: Vec<u32>,
+    pub extras: Value,
+}
+
+/// A named group of primitives, e.g. everything belonging to one name index.
+#[derive(Debug)]
+pub(super) struct ExportNode {
+    pub name: String,
+    pub extras: Value,
+    pub primitives: Vec<ExportPrimitive>,
+}
+
+/// The name of the `HitAttribute` encoded by a metadata value, or the value itself
+/// if it isn't a known attribute.
+fn hit_attribute_name(metadata: u32) -> String {
+    if metadata <= HitAttribute::Jump as u32 {
+        format!("{:?}", HitAttribute::from(metadata as i32))
+    } else {
+        format!("{metadata}")
+    }
+}
+
+/// A color for vertices with the metadata value `metadata`. Zero (no hit attribute) is grey,
+/// and other values are spread around the hue circle so that neighbouring values differ.
+pub(super) fn metadata_color(metadata: u32) -> Vec3 {
+    if metadata == 0 {
+        return [0.6, 0.6, 0.6];
+    }
+
+    // step the hue by the golden angle so consecutive values don't look alike
+    let hue = (metadata as f32 * 0.381966) % 1.0 * 6.0;
+    let x = 1.0 - ((hue % 2.0) - 1.0).abs();
+    match hue as u32 {
+        0 => [1.0, x, 0.0],
+        1 => [x, 1.0, 0.0],
+        2 => [0.0, 1.0, x],
+        3 => [0.0, x, 1.0],
+        4 => [x, 0.0, 1.0],
+        _ => [1.0, 0.0, x],
+    }
+}
+
+/// Expand every triangle group of `tri_groups` into a triangle list primitive.
+/// Triangle strips are expanded with every other triangle reversed so that all triangles
+/// face the same way, and each triangle takes its metadata from its last vertex (both as in
+/// `RaycastState::ray_hits_mesh`).
+fn triangulate<'a>(
+    name: String,
+    tri_groups: impl Iterator<Item = &'a TriGroup>,
+    extras: Value,
+) -> ExportPrimitive {
+    let mut points = vec![];
+    let mut metadata = vec![];
+
+    for tri_group in tri_groups {
+        let mut add_triangle = |vertices: [&TriVertex; 3]| {
+            for vertex in vertices {
+                points.push(vertex.point);
+                metadata.push(vertices[2].metadata);
+            }
+        };
+
+        if tri_group.is_tri_strip {
+            for (idx, v) in tri_group.vertices.windows(3).enumerate() {
+                if idx % 2 == 0 {
+                    add_triangle([&v[0], &v[1], &v[2]]);
+                } else {
+                    add_triangle([&v[1], &v[0], &v[2]]);
+                }
+            }
+        } else {
+            for v in tri_group.vertices.chunks_exact(3) {
+                add_triangle([&v[0], &v[1], &v[2]]);
+            }
+        }
+    }
+
+    ExportPrimitive {
+        name,
+        mode: PrimitiveMode::Triangles,
+        points,
+        metadata,
+        extras,
+    }
+}
+
+/// The distinct hit attributes used by the triangles of `primitive`.
+fn hit_attribute_names(primitive: &ExportPrimitive) -> Vec<String> {
+    let mut values = primitive.metadata.clone();
+    values.sort_unstable();
+    values.dedup();
+    values.into_iter().map(hit_attribute_name).collect()
+}
+
+/// Export the zone mesh, with one primitive per zone (i.e. per mesh sector).
+fn export_zones(zone_mesh: &Mesh) -> ExportNode {
+    let primitives = zone_mesh
+        .sectors
+        .iter()
+        .map(|sector| {
+            // the zone index is stored as the metadata of every vertex in its sector
+            let zone = sector
+                .tri_groups
+                .first()
+                .and_then(|tri_group| tri_group.vertices.first())
+                .map_or(0, |vertex| vertex.metadata);
+            triangulate(
+                format!("zone {zone}"),
+                sector.tri_groups.iter(),
+                json!({ "zone": zone }),
+            )
+        })
+        .collect();
+
+    ExportNode {
+        name: "zones".to_string(),
+        extras: json!({ "kind": "zones" }),
+        primitives,
+    }
+}
+
+/// Export the AABBs, collision mesh, and vault points of the prop with name index `name_idx`,
+/// or `None` if it has none of them.
+fn export_prop(name_idx: u16, prop: &PropMonoData) -> Option<ExportNode> {
+    let mut primitives = vec![];
+
+    if let Some(aabbs) = &prop.aabbs {
+        for (aabb_idx, aabb) in aabbs.iter().enumerate() {
+            // an edge joins each pair of corners that differ in exactly one coordinate
+            let corners = aabb.compute_vertices();
+            let mut points = vec![];
+            for (i, a) in corners.iter().enumerate() {
+                for b in corners[i + 1..].iter() {
+                    if (0..3).filter(|&axis| a[axis] != b[axis]).count() == 1 {
+                        points.push(*a);
+                        points.push(*b);
+                    }
+                }
+            }
+
+            // the first AABB is the prop's, and the rest belong to its subobjects
+            let (name, extras) = match aabb_idx {
+                0 => ("aabb".to_string(), json!({ "kind": "aabb" })),
+                _ => (
+                    format!("subobject {} aabb", aabb_idx - 1),
+                    json!({ "kind": "aabb", "subobject": aabb_idx - 1 }),
+                ),
+            };
+
+            primitives.push(ExportPrimitive {
+                name,
+                mode: PrimitiveMode::Lines,
+                metadata: vec![0; points.len()],
+                points,
+                extras,
+            });
+        }
+    }
+
+    if let Some(mesh) = &prop.collision_mesh {
+        let mut primitive = triangulate(
+            "collision".to_string(),
+            mesh.sectors
+                .iter()
+                .flat_map(|sector| sector.tri_groups.iter()),
+            Value::Null,
+        );
+        primitive.extras = json!({
+            "kind": "collision",
+            "hit_attributes": hit_attribute_names(&primitive),
+        });
+        primitives.push(primitive);
+    }
+
+    if let Some(vault_points) = &prop.vault_points {
+        primitives.push(ExportPrimitive {
+            name: "vault points".to_string(),
+            mode: PrimitiveMode::Points,
+            points: vault_points.clone(),
+            metadata: vec![0; vault_points.len()],
+            extras: json!({ "kind": "vault_points" }),
+        });
+    }
+
+    if primitives.is_empty() {
+        return None;
+    }
+
+    let internal_name = NamePropConfig::get(name_idx).internal_name;
+    Some(ExportNode {
+        name: format!("{name_idx} {internal_name}"),
+        extras: json!({ "name_idx": name_idx, "internal_name": internal_name }),
+        primitives,
+    })
+}
+
+/// Collect the exported nodes of `mono_data`: the zone mesh, followed by one node per
+/// name index that has any collision data.
+fn export_nodes(mono_data: &MonoData) -> Vec<ExportNode> {
+    let mut nodes = vec![export_zones(&mono_data.zone_mesh)];
+    nodes.extend(
+        mono_data
+            .props
+            .iter()
+            .enumerate()
+            .filter_map(|(name_idx, prop)| export_prop(name_idx as u16, prop)),
+    );
+    nodes
+}
+
+/// Write `nodes` as a Wavefront OBJ file, with one object per primitive.
+pub(super) fn write_obj(out: &mut dyn Write, nodes: &[ExportNode]) -> std::io::Result<()> {
+    writeln!(out, "# exported by openkdsim (simulation coordinates)")?;
+
+    // OBJ vertex indices are 1-based and global to the file
+    let mut next_vertex = 1;
+    for node in nodes {
+        writeln!(out, "# {}", node.extras)?;
+        for primitive in node.primitives.iter() {
+            writeln!(out, "o {} / {}", node.name, primitive.name)?;
+            writeln!(out, "# {}", primitive.extras)?;
+
+            for (point, metadata) in primitive.points.iter().zip(primitive.metadata.iter()) {
+                let [r, g, b] = metadata_color(*metadata);
+                writeln!(out, "v {} {} {} {r} {g} {b}", point[0], point[1], point[2])?;
+            }
+
+            let element = match primitive.mode {
+                PrimitiveMode::Points => "p",
+                PrimitiveMode::Lines | PrimitiveMode::LineStrip => "l",
+                PrimitiveMode::Triangles => "f",
+            };
+            let num_vertices = primitive
+                .mode
+                .vertices_per_element()
+                .unwrap_or(primitive.points.len());
+            for first in (0..primitive.points.len()).step_by(num_vertices.max(1)) {
+                write!(out, "{element}")?;
+                for idx in first..first + num_vertices {
+                    write!(out, " {}", next_vertex + idx)?;
+                }
+                writeln!(out)?;
+            }
+
+            next_vertex += primitive.points.len();
+        }
+    }
+
+    Ok(())
+}
+
+/// The glTF `componentType` of a 32-bit float.
+const GLTF_FLOAT: u32 = 5126;
+
+/// The glTF `target` of a vertex attribute buffer view.
+const GLTF_ARRAY_BUFFER: u32 = 34962;
+
+/// The glTF JSON document and binary buffer of `nodes`, with one node per `ExportNode` that
+/// has a child node and mesh per primitive. Metadata is written as `COLOR_0`, and the
+/// `extras` of each node and primitive are kept as glTF node extras.
+/// The document's buffer doesn't have a `uri` yet.
+fn gltf_document(nodes: &[ExportNode]) -> (serde_json::Value, Vec<u8>) {
+    let mut buffer: Vec<u8> = vec![];
+    let mut buffer_views = vec![];
+    let mut accessors = vec![];
+    let mut meshes = vec![];
+    let mut gltf_nodes = vec![];
+    let mut scene_nodes = vec![];
+
+    // appends `values` to the buffer as a VEC3 float accessor, returning its index
+    let mut add_vec3_accessor = |values: &[Vec3], with_bounds: bool| -> usize {
+        let byte_offset = buffer.len();
+        for value in values {
+            for component in value {
+                buffer.extend_from_slice(&component.to_le_bytes());
+            }
+        }
+
+        buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": byte_offset,
+            "byteLength": buffer.len() - byte_offset,
+            "target": GLTF_ARRAY_BUFFER,
+        }));
+
+        let mut accessor = json!({
+            "bufferView": buffer_views.len() - 1,
+            "componentType": GLTF_FLOAT,
+            "count": values.len(),
+            "type": "VEC3",
+        });
+
+        // glTF requires bounds on `POSITION` accessors
+        if with_bounds {
+            let mut min = [f32::MAX; 3];
+            let mut max = [f32::MIN; 3];
+            for value in values {
+                for axis in 0..3 {
+                    min[axis] = min[axis].min(value[axis]);
+                    max[axis] = max[axis].max(value[axis]);
+                }
+            }
+            accessor["min"] = json!(min);
+            accessor["max"] = json!(max);
+        }
+
+        accessors.push(accessor);
+        accessors.len() - 1
+    };
+
+    for node in nodes {
+        let mut children = vec![];
+
+        for primitive in node.primitives.iter() {
+            if primitive.points.is_empty() {
+                continue;
+            }
+
+            let colors: Vec<Vec3> = primitive
+                .metadata
+                .iter()
+                .map(|metadata| metadata_color(*metadata))
+                .collect();
+            let position = add_vec3_accessor(&primitive.points, true);
+            let color = add_vec3_accessor(&colors, false);
+
+            meshes.push(json!({
+                "name": primitive.name,
+                "primitives": [{
+                    "attributes": { "POSITION": position, "COLOR_0": color },
+                    "mode": primitive.mode.gltf_mode(),
+                }],
+            }));
+
+            gltf_nodes.push(json!({
+                "name": primitive.name,
+                "mesh": meshes.len() - 1,
+                "extras": primitive.extras,
+            }));
+            children.push(gltf_nodes.len() - 1);
+        }
+
+        gltf_nodes.push(json!({
+            "name": node.name,
+            "children": children,
+            "extras": node.extras,
+        }));
+        scene_nodes.push(gltf_nodes.len() - 1);
+    }
+
+    let gltf = json!({
+        "asset": { "version": "2.0", "generator": "openkdsim" },
+        "scene": 0,
+        "scenes": [{ "nodes": scene_nodes }],
+        "nodes": gltf_nodes,
+        "meshes": meshes,
+        "accessors": accessors,
+        "bufferViews": buffer_views,
+        "buffers": [{ "byteLength": buffer.len() }],
+    });
+
+    (gltf, buffer)
+}
+
+/// Encode `data` as base64 (with padding).
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+
+        for char_idx in 0..4 {
+            if char_idx <= chunk.len() {
+                let sextet = (bits >> (18 - 6 * char_idx)) & 0x3f;
+                result.push(ALPHABET[sextet as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+
+    result
+}
+
+/// Write `nodes` as a glTF file (see `gltf_document`), with the buffer embedded as a
+/// base64 data URI.
+pub(super) fn write_gltf(out: &mut dyn Write, nodes: &[ExportNode]) -> std::io::Result<()> {
+    let (mut gltf, buffer) = gltf_document(nodes);
+    gltf["buffers"][0]["uri"] = json!(format!(
+        "data:application/octet-stream;base64,{}",
+        base64_encode(&buffer)
+    ));
+
+    serde_json::to_writer(&mut *out, &gltf)?;
+    writeln!(out)
+}
+
+/// Write `nodes` as a binary glTF file (see `gltf_document`).
+pub(super) fn write_glb(out: &mut dyn Write, nodes: &[ExportNode]) -> std::io::Result<()> {
+    let (gltf, mut buffer) = gltf_document(nodes);
+
+    // both chunks have to be padded to a multiple of 4 bytes (JSON with spaces)
+    let mut json_chunk = gltf.to_string().into_bytes();
+    json_chunk.resize(json_chunk.len().next_multiple_of(4), b' ');
+    buffer.resize(buffer.len().next_multiple_of(4), 0);
+
+    let total_len = 12 + 8 + json_chunk.len() + 8 + buffer.len();
+
+    // header: magic ("glTF"), version, total length
+    out.write_all(b"glTF")?;
+    out.write_all(&2u32.to_le_bytes())?;
+    out.write_all(&(total_len as u32).to_le_bytes())?;
+
+    // chunks: length, type ("JSON" or "BIN\0"), data
+    out.write_all(&(json_chunk.len() as u32).to_le_bytes())?;
+    out.write_all(b"JSON")?;
+    out.write_all(&json_chunk)?;
+    out.write_all(&(buffer.len() as u32).to_le_bytes())?;
+    out.write_all(b"BIN\0")?;
+    out.write_all(&buffer)?;
+
+    Ok(())
+}
+
+/// Write the zone mesh and every prop's AABBs, collision mesh, and vault points in
+/// `mono_data` to `out`.
+fn write_export(
+    out: &mut dyn Write,
+    mono_data: &MonoData,
+    format: ExportFormat,
+) -> std::io::Result<()> {
+    let nodes = export_nodes(mono_data);
+    match format {
+        ExportFormat::Obj => write_obj(out, &nodes),
+        ExportFormat::Gltf => write_gltf(out, &nodes),
+        ExportFormat::Glb => write_glb(out, &nodes),
+    }
+}
+
+/// The `export` command: writes a mission's mono data to an OBJ or glTF file so that its
+/// zones and prop hitboxes can be inspected in a 3D editor.
+pub fn export(args: &[String]) -> Result<(), String> {
+    let args = RunArgs::parse(args, 1)?;
+    let format = ExportFormat::from_args(&args)?;
+
+    let data = RunArgs::read_mono_data(&args.positional[0])?;
+    let mono_data = MonoData::parse(&data).map_err(|e| e.to_string())?;
+
+    let mut out = args.open_output()?;
+    write_export(&mut *out, &mono_data, format).map_err(|e| e.to_string())?;
+    out.flush().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::mono_data::MonoData;
+
+    use super::{base64_encode, export_nodes, write_export, ExportFormat};
+
+    #[test]
+    fn test_export_mono_data() {
+        let data: &[u8] = include_bytes!("../bin/monodata/mission1.bin");
+        let mono_data = MonoData::parse(data).unwrap();
+
+        // every zone and every triangle is exported
+        let nodes = export_nodes(&mono_data);
+        assert_eq!(nodes[0].primitives.len(), mono_data.zone_mesh.sectors.len());
+        for node in nodes.iter() {
+            for primitive in node.primitives.iter() {
+                let num_vertices = primitive.mode.vertices_per_element().unwrap();
+                assert_eq!(primitive.points.len() % num_vertices, 0);
+                assert_eq!(primitive.points.len(), primitive.metadata.len());
+            }
+        }
+
+        let mut obj = vec![];
+        write_export(&mut obj, &mono_data, ExportFormat::Obj).unwrap();
+        let obj = String::from_utf8(obj).unwrap();
+        assert!(obj.contains("\no zones / zone "));
+
+        let mut gltf = vec![];
+        write_export(&mut gltf, &mono_data, ExportFormat::Gltf).unwrap();
+        let gltf: serde_json::Value = serde_json::from_slice(&gltf).unwrap();
+        let uri = gltf["buffers"][0]["uri"].as_str().unwrap();
+        assert!(uri.starts_with("data:application/octet-stream;base64,"));
+
+        let mut glb = vec![];
+        write_export(&mut glb, &mono_data, ExportFormat::Glb).unwrap();
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(
+            u32::from_le_bytes(glb[8..12].try_into().unwrap()) as usize,
+            glb.len()
+        );
+        assert_eq!(glb.len() % 4, 0);
+
+        assert_eq!(base64_encode(b"glTF"), "Z2xURg==");
+        assert_eq!(base64_encode(b"gltf2"), "Z2x0ZjI=");
+        assert_eq!(base64_encode(b"glb"), "Z2xi");
+    }
+}
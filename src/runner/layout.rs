@@ -0,0 +1,74 @@
+use std::{collections::BTreeMap, io::Write};
+
+use serde::Serialize;
+
+use crate::{
+    global::rng::RngState,
+    mission::Mission,
+    props::random::layout::{
+        predict_layout, random_layout_override, PredictedLayout, RandomLayoutFile,
+    },
+};
+
+use super::{
+    script::{parse_script, ScriptCommand},
+    RunArgs,
+};
+
+/// The random prop layout of a mission, as written by the `layout` command.
+#[derive(Debug, Serialize)]
+struct LayoutReport {
+    mission: u8,
+
+    #[serde(flatten)]
+    layout: PredictedLayout,
+
+    /// A random layout file which pins this layout (e.g. to edit and pass back in with
+    /// `--random-layout`, or to copy to `RANDOM_LAYOUT_PATH`).
+    random_layout: RandomLayoutFile,
+}
+
+/// Predict the props that each random group of a mission resolves to, given the `prop`
+/// lines of an input script (in the order `run` would add them) and the rng state before
+/// they're added (`--rng`). The mission's `--random-layout` override, if any, is applied
+/// first. The report is written as a single JSON object.
+pub fn layout(args: &[String]) -> Result<(), String> {
+    let args = RunArgs::parse(args, 2)?;
+    let mission: u8 = args.positional[0]
+        .parse()
+        .map_err(|_| format!("bad mission: {}", args.positional[0]))?;
+
+    let script_path = &args.positional[1];
+    let script = std::fs::read_to_string(script_path)
+        .map_err(|e| format!("error reading script {script_path:?}: {e}"))?;
+    let placements: Vec<_> = parse_script(&script)?
+        .into_iter()
+        .filter_map(|command| match command {
+            ScriptCommand::AddProp(args) => Some(args),
+            _ => None,
+        })
+        .collect();
+
+    args.load_random_layout()?;
+    let mut out = args.open_output()?;
+
+    let rng = args.rng.map_or_else(RngState::default, RngState::from);
+    let layout_override = random_layout_override(Mission::from(mission));
+    let layout = predict_layout(
+        Mission::from(mission),
+        &placements,
+        rng,
+        layout_override.as_ref(),
+    );
+
+    let report = LayoutReport {
+        mission,
+        random_layout: RandomLayoutFile {
+            missions: BTreeMap::from([(mission, layout.to_override())]),
+        },
+        layout,
+    };
+    serde_json::to_writer_pretty(&mut out, &report).map_err(|e| e.to_string())?;
+    writeln!(out).map_err(|e| e.to_string())?;
+    out.flush().map_err(|e| e.to_string())
+}
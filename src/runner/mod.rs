@@ -0,0 +1,606 @@
+pub mod compare;
+pub mod coverage;
+pub mod export;
+pub mod layout;
+pub mod paths;
+pub mod script;
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use gl_matrix::common::Vec3;
+use serde::Serialize;
+
+use crate::{
+    collision::world_collider::StageMeshCollider,
+    gamestate::{GameState, MonoInitStartArgs},
+    mission::{config::MissionConfig, Mission},
+    movie::{Movie, MoviePlayer},
+    props::{
+        motion::data::{file::MotionDataFile, motion_data, set_motion_data, MotionData},
+        prop::PropGlobalState,
+        random::layout::{set_random_layouts, RandomLayoutFile, RngSeed},
+    },
+    trace::FrameTrace,
+};
+
+use self::script::{parse_script, ScriptCommand};
+
+/// The `SetKatamariSpeed` arguments used until a script sets its own. Unity always sets
+/// these before the first tick, and the katamari can't move until they're set.
+const DEFAULT_KATAMARI_SPEED: [f32; 13] = [
+    1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, -100.0, 1.0, 1.0, 1.0,
+];
+
+/// The gravity direction used until a script sets its own.
+const DEFAULT_GRAVITY: Vec3 = [0.0, -1.0, 0.0];
+
+const USAGE: &str = "usage: run <mission> <mono_data> <script> [options]
+       replay <mono_data> <movie> [options]
+       compare <mono_data> <movie> <trace> [options]
+       export <mono_data> [options]
+       coverage <mission> <mono_data> <script> [options]
+       motion-data [options]
+       paths <stage> [options]
+       layout <mission> <script> [options]
+  <mission>      the mission index (e.g. 1 for MAS1)
+  <mono_data>    path to the mission's mono data (e.g. src/bin/monodata/mission1.bin)
+  <script>       path to the input script (see `runner::script::ScriptCommand`)
+  <movie>        path to a movie recorded with `StartMovieRecording`
+  <trace>        path to a trace of the original simulation playing the same movie
+  <stage>        a `PathStage` name (e.g. House or World)
+options:
+  --area <n>     the area to start in (default 0, `run` and `coverage` only)
+  --stage <n>    the stage to load (default: the mission's stage, `run` and `coverage` only)
+  --frames <n>   the number of frames to simulate (default: until the script/movie ends)
+  --tick-rate <n>  ticks per second (default 30, `run` only; script tick counts are ticks at this rate)
+  --world <obj>  an OBJ file of the stage's collision (default: no stage collision)
+  --out <path>   where to write the per-frame state dump, export, or report (default: stdout)
+  --no-props     don't include props in the per-frame state dump
+  --trace        write a trace (see `trace::FrameTrace`) instead of the per-frame state dump
+  --tolerance <x>  the largest difference allowed between floats (default 0, `compare` only)
+  --format <fmt>   `obj`, `gltf`, or `glb`, or `svg` for `paths` (default: from the `--out` extension,
+                   or `obj` for `export` and `svg` for `paths`)
+  --motion-data <json>  a motion data file overriding the built-in prop paths and move types
+                   (see `props::motion::data::file::MotionDataFile`; not `replay` or `compare`,
+                   which use the motion data recorded in the movie)
+  --random-layout <json>  a random layout file pinning random props (default: none)
+                   (see `props::random::layout::RandomLayoutFile`; not `replay` or `compare`)
+  --rng <rng1>,<rng2>  the rng state before props are added (default: 4321,0; `layout` only)";
+
+/// The arguments of the runner's commands.
+#[derive(Debug)]
+struct RunArgs {
+    /// The positional (non-option) arguments.
+    positional: Vec<String>,
+    area: u8,
+    stage: Option<u8>,
+    frames: Option<u32>,
+    tick_rate: Option<u16>,
+    world_path: Option<PathBuf>,
+    motion_data_path: Option<PathBuf>,
+    random_layout_path: Option<PathBuf>,
+    rng: Option<RngSeed>,
+    out_path: Option<PathBuf>,
+    dump_props: bool,
+    write_trace: bool,
+    tolerance: f32,
+    format: Option<String>,
+}
+
+impl RunArgs {
+    fn parse(args: &[String], num_positional: usize) -> Result<Self, String> {
+        fn value<T: std::str::FromStr>(arg: Option<&String>, name: &str) -> Result<T, String> {
+            arg.ok_or(format!("missing {name}"))?
+                .parse::<T>()
+                .map_err(|_| format!("bad {name}"))
+        }
+
+        let mut result = RunArgs {
+            positional: vec![],
+            area: 0,
+            stage: None,
+            frames: None,
+            tick_rate: None,
+            world_path: None,
+            motion_data_path: None,
+            random_layout_path: None,
+            rng: None,
+            out_path: None,
+            dump_props: true,
+            write_trace: false,
+            tolerance: 0.0,
+            format: None,
+        };
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--area" => result.area = value(iter.next(), "area")?,
+                "--stage" => result.stage = Some(value(iter.next(), "stage")?),
+                "--frames" => result.frames = Some(value(iter.next(), "frame count")?),
+                "--tick-rate" => result.tick_rate = Some(value(iter.next(), "tick rate")?),
+                "--world" => result.world_path = Some(value(iter.next(), "world path")?),
+                "--motion-data" => {
+                    result.motion_data_path = Some(value(iter.next(), "motion data path")?)
+                }
+                "--random-layout" => {
+                    result.random_layout_path = Some(value(iter.next(), "random layout path")?)
+                }
+                "--rng" => {
+                    let rng: String = value(iter.next(), "rng")?;
+                    let parse = |value: &str| value.trim().parse::<u32>().ok();
+                    result.rng = match rng.split_once(',') {
+                        Some((rng1, rng2)) => parse(rng1).zip(parse(rng2)),
+                        None => None,
+                    }
+                    .map(|(rng1, rng2)| RngSeed { rng1, rng2 });
+                    if result.rng.is_none() {
+                        return Err("bad rng".to_string());
+                    }
+                }
+                "--out" => result.out_path = Some(value(iter.next(), "out path")?),
+                "--no-props" => result.dump_props = false,
+                "--trace" => result.write_trace = true,
+                "--tolerance" => result.tolerance = value(iter.next(), "tolerance")?,
+                "--format" => result.format = Some(value(iter.next(), "format")?),
+                _ if arg.starts_with("--") => return Err(format!("unknown option {arg}")),
+                _ => result.positional.push(arg.clone()),
+            }
+        }
+
+        if result.positional.len() != num_positional {
+            return Err(USAGE.to_string());
+        }
+
+        Ok(result)
+    }
+
+    /// Read the mono data file at `path`.
+    fn read_mono_data(path: &str) -> Result<Vec<u8>, String> {
+        std::fs::read(path).map_err(|e| format!("error reading mono data {path:?}: {e}"))
+    }
+
+    /// Load the world collider from the `--world` OBJ file, or an empty one if there isn't one.
+    fn load_world_collider(&self) -> Result<StageMeshCollider, String> {
+        match &self.world_path {
+            Some(path) => StageMeshCollider::from_obj_file(path)
+                .map_err(|e| format!("error reading world collision {path:?}: {e}")),
+            None => Ok(StageMeshCollider::default()),
+        }
+    }
+
+    /// Override the built-in motion data with the `--motion-data` file, if there is one.
+    fn load_motion_data(&self) -> Result<(), String> {
+        let Some(path) = &self.motion_data_path else {
+            return Ok(());
+        };
+
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("error reading motion data {path:?}: {e}"))?;
+        let mut data = MotionData::built_in();
+        MotionDataFile::from_json(&json)?.apply(&mut data)?;
+        set_motion_data(data);
+
+        Ok(())
+    }
+
+    /// Pin random props with the `--random-layout` file, if there is one.
+    fn load_random_layout(&self) -> Result<(), String> {
+        let Some(path) = &self.random_layout_path else {
+            return Ok(());
+        };
+
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("error reading random layout {path:?}: {e}"))?;
+        set_random_layouts(RandomLayoutFile::from_json(&json)?);
+
+        Ok(())
+    }
+
+    /// Open the `--out` file for the per-frame state dump, or stdout if there isn't one.
+    fn open_output(&self) -> Result<Box<dyn Write>, String> {
+        Ok(match &self.out_path {
+            Some(path) => Box::new(BufWriter::new(
+                File::create(path).map_err(|e| format!("error creating {path:?}: {e}"))?,
+            )),
+            None => Box::new(BufWriter::new(std::io::stdout())),
+        })
+    }
+
+    /// Write the state of `state` after `frame` ticks to `out` as a single JSON line, either
+    /// as a per-frame state dump or as a trace (if `--trace` is set).
+    fn write_frame(
+        &self,
+        out: &mut dyn Write,
+        state: &GameState,
+        frame: u32,
+    ) -> Result<(), String> {
+        if self.write_trace {
+            serde_json::to_writer(&mut *out, &FrameTrace::new(state)).map_err(|e| e.to_string())?;
+            writeln!(out).map_err(|e| e.to_string())
+        } else {
+            FrameDump::write(out, state, frame, self.dump_props)
+        }
+    }
+
+    /// Returns `true` if the `--frames` limit has been reached after `frame` frames.
+    fn reached_frame_limit(&self, frame: u32) -> bool {
+        self.frames.is_some_and(|frames| frame >= frames)
+    }
+}
+
+/// The katamari fields written to the per-frame state dump.
+#[derive(Debug, Serialize)]
+struct KatamariDump {
+    center: Vec3,
+    diam_cm: f32,
+    vol: f32,
+    speed: f32,
+    in_water: bool,
+}
+
+/// The prince fields written to the per-frame state dump.
+#[derive(Debug, Serialize)]
+struct PrinceDump {
+    pos: Vec3,
+    angle: f32,
+}
+
+/// The prop fields written to the per-frame state dump.
+#[derive(Debug, Serialize)]
+struct PropDump {
+    ctrl_idx: u16,
+    name_idx: u16,
+    pos: Vec3,
+    global_state: PropGlobalState,
+}
+
+/// A single line of the per-frame state dump.
+#[derive(Debug, Serialize)]
+struct FrameDump {
+    frame: u32,
+    katamari: KatamariDump,
+    prince: PrinceDump,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    props: Option<Vec<PropDump>>,
+}
+
+impl FrameDump {
+    /// Write the dump of `state` after `frame` ticks to `out` as a single JSON line.
+    fn write(
+        out: &mut dyn Write,
+        state: &GameState,
+        frame: u32,
+        dump_props: bool,
+    ) -> Result<(), String> {
+        let dump = FrameDump::new(state, frame, dump_props);
+        serde_json::to_writer(&mut *out, &dump).map_err(|e| e.to_string())?;
+        writeln!(out).map_err(|e| e.to_string())
+    }
+
+    fn new(state: &GameState, frame: u32, dump_props: bool) -> Self {
+        let player = state.get_player(0);
+        let katamari = &player.katamari;
+        let prince = &player.prince;
+
+        let props = dump_props.then(|| {
+            state
+                .props
+                .props_iter()
+                .map(|prop_ref| {
+                    let prop = prop_ref.borrow();
+                    PropDump {
+                        ctrl_idx: prop.get_ctrl_idx(),
+                        name_idx: prop.get_name_idx(),
+                        pos: *prop.get_position(),
+                        global_state: prop.get_global_state(),
+                    }
+                })
+                .collect()
+        });
+
+        FrameDump {
+            frame,
+            katamari: KatamariDump {
+                center: *katamari.get_center(),
+                diam_cm: katamari.get_diam_cm(),
+                vol: katamari.get_vol(),
+                speed: katamari.get_speed(),
+                in_water: katamari.is_in_water(),
+            },
+            prince: PrinceDump {
+                pos: *prince.get_pos(),
+                angle: prince.get_angle(),
+            },
+            props,
+        }
+    }
+}
+
+/// Apply a single (non-`Tick`) script command to the game state.
+fn apply_command(state: &mut GameState, command: &ScriptCommand) {
+    match command {
+        ScriptCommand::Gravity([x, y, z]) => state.global.set_gravity(*x, *y, *z),
+        ScriptCommand::KatamariSpeed(s) => state.set_katamari_speed(
+            s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7], s[8], s[9], s[10], s[11], s[12],
+        ),
+        ScriptCommand::AddProp(args) => {
+            state.add_prop(args);
+        }
+        ScriptCommand::SetParent(ctrl_idx, parent_ctrl_idx) => {
+            state.add_prop_set_parent(*ctrl_idx, *parent_ctrl_idx)
+        }
+        ScriptCommand::Stick(player, axes, buttons) => {
+            let [ls_x, ls_y, rs_x, rs_y] = *axes;
+            let [l3_down, r3_down, l3_held, r3_held] = *buttons;
+            state
+                .get_player_mut(*player as usize)
+                .input
+                .set_stick_state(ls_x, ls_y, rs_x, rs_y, l3_down, r3_down, l3_held, r3_held);
+        }
+        ScriptCommand::Trigger(player, buttons) => {
+            // note the argument order of `SetTriggerState` differs from `set_trigger_state`
+            let [l1_down, l2_down, r1_down, r2_down, l1_held, r1_held, l2_held, r2_held, cross_click] =
+                *buttons;
+            state
+                .get_player_mut(*player as usize)
+                .input
+                .set_trigger_state(
+                    l1_down,
+                    l1_held,
+                    l2_down,
+                    l2_held,
+                    r1_down,
+                    r1_held,
+                    r2_down,
+                    r2_held,
+                    cross_click,
+                );
+        }
+        ScriptCommand::Reset => {
+            state.reset_attempt();
+            let num_players = if state.mission_state.is_vs_mode { 2 } else { 1 };
+            for player_idx in 0..num_players {
+                state.set_game_start(player_idx, 0);
+            }
+        }
+        ScriptCommand::Tick(_) => (),
+    }
+}
+
+/// Initialize `mission` headlessly in the same order as unity (`MonoInitStart`,
+/// `MonoInitAddProp`..., `MonoInitEnd`, `Init`, `SetGameStart`), applying the init commands
+/// of the input script `commands`. Returns the initialized game state along with the
+/// script's remaining commands.
+fn init_mission<'a>(
+    mono_data: &[u8],
+    mission: u8,
+    area: u8,
+    stage: u8,
+    tick_rate: Option<u16>,
+    world_collider: StageMeshCollider,
+    commands: &'a [ScriptCommand],
+) -> Result<(GameState, Vec<&'a ScriptCommand>), String> {
+    let mut state = GameState::new();
+    state.reset();
+    if let Some(tick_rate) = tick_rate {
+        if !state.set_tick_rate(tick_rate) {
+            return Err(format!("unsupported tick rate: {tick_rate}"));
+        }
+    }
+    state.set_world_collider(Some(Rc::new(world_collider)));
+    state
+        .mono_init_start(
+            mono_data,
+            &MonoInitStartArgs {
+                mission,
+                area,
+                stage,
+                ..Default::default()
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    let (init_commands, commands): (Vec<_>, Vec<_>) =
+        commands.iter().partition(|c| c.is_init_command());
+    for command in init_commands {
+        apply_command(&mut state, command);
+    }
+
+    state.mono_init_end();
+    // in vs mode, unity initializes both players
+    let num_players = if Mission::from(mission).is_vs_mode() {
+        2
+    } else {
+        1
+    };
+    for player_idx in 0..num_players {
+        state.init(player_idx, -1.0, mission);
+    }
+    state
+        .global
+        .set_gravity(DEFAULT_GRAVITY[0], DEFAULT_GRAVITY[1], DEFAULT_GRAVITY[2]);
+    apply_command(
+        &mut state,
+        &ScriptCommand::KatamariSpeed(DEFAULT_KATAMARI_SPEED),
+    );
+    for player_idx in 0..num_players {
+        state.set_game_start(player_idx, area);
+    }
+
+    Ok((state, commands))
+}
+
+/// Run a mission headlessly: initialize it from its mono data and the init commands of
+/// the input script, then tick it while applying the script's remaining commands,
+/// writing the state of the katamari, prince, and props after every tick as a JSON line.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let args = RunArgs::parse(args, 3)?;
+    let mission: u8 = args.positional[0]
+        .parse()
+        .map_err(|_| format!("bad mission: {}", args.positional[0]))?;
+
+    let script_path = &args.positional[2];
+    let script = std::fs::read_to_string(script_path)
+        .map_err(|e| format!("error reading script {script_path:?}: {e}"))?;
+    let commands = parse_script(&script)?;
+
+    let mono_data = RunArgs::read_mono_data(&args.positional[1])?;
+    let world_collider = args.load_world_collider()?;
+    args.load_motion_data()?;
+    args.load_random_layout()?;
+    let mut out = args.open_output()?;
+
+    let stage = args.stage.unwrap_or_else(|| {
+        let mut mission_config = MissionConfig::default();
+        MissionConfig::get(&mut mission_config, mission);
+        mission_config.stage.into()
+    });
+
+    let (mut state, commands) = init_mission(
+        &mono_data,
+        mission,
+        args.area,
+        stage,
+        args.tick_rate,
+        world_collider,
+        &commands,
+    )?;
+    let delta = 1.0 / state.global.tick_rate.get_ticks_per_second() as f32;
+
+    let mut frame = 0;
+    let mut tick = |state: &mut GameState, out: &mut dyn Write| -> Result<bool, String> {
+        if args.reached_frame_limit(frame) {
+            return Ok(false);
+        }
+
+        state.tick(delta);
+        frame += 1;
+        args.write_frame(out, state, frame)?;
+
+        Ok(true)
+    };
+
+    'script: for command in commands {
+        if let ScriptCommand::Tick(count) = command {
+            for _ in 0..*count {
+                if !tick(&mut state, &mut *out)? {
+                    break 'script;
+                }
+            }
+        } else {
+            apply_command(&mut state, command);
+        }
+    }
+
+    // if the script ended early, keep going with the last inputs
+    while args.frames.is_some() && tick(&mut state, &mut *out)? {}
+
+    out.flush().map_err(|e| e.to_string())
+}
+
+/// Replay a movie recorded with `StartMovieRecording` headlessly, writing the state of
+/// the katamari, prince, and props after every tick as a JSON line.
+pub fn replay(args: &[String]) -> Result<(), String> {
+    let args = RunArgs::parse(args, 2)?;
+
+    let movie_path = &args.positional[1];
+    let movie = Movie::read_from_file(Path::new(movie_path)).map_err(|e| e.to_string())?;
+
+    let mono_data = RunArgs::read_mono_data(&args.positional[0])?;
+    let world_collider = args.load_world_collider()?;
+    let mut out = args.open_output()?;
+
+    let mut state = GameState::new();
+    state.reset();
+    state.set_world_collider(Some(Rc::new(world_collider)));
+
+    let mut player = MoviePlayer::new(&movie);
+    let mut frame = 0;
+    while !args.reached_frame_limit(frame)
+        && player
+            .play_frame(&mut state, &mono_data)
+            .map_err(|e| e.to_string())?
+    {
+        frame += 1;
+        args.write_frame(&mut *out, &state, frame)?;
+    }
+
+    out.flush().map_err(|e| e.to_string())
+}
+
+/// Write the motion data (the built-in data, overridden by the `--motion-data` file if there
+/// is one) in the format of a motion data file, which can then be edited and loaded back in.
+pub fn export_motion_data(args: &[String]) -> Result<(), String> {
+    let args = RunArgs::parse(args, 0)?;
+    args.load_motion_data()?;
+    let mut out = args.open_output()?;
+
+    let file = MotionDataFile::from_motion_data(&motion_data());
+    serde_json::to_writer_pretty(&mut out, &file).map_err(|e| e.to_string())?;
+    writeln!(out).map_err(|e| e.to_string())?;
+    out.flush().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        collision::world_collider::StageMeshCollider,
+        mission::config::MissionConfig,
+        player::camera::mode::CameraMode,
+        runner::script::{parse_script, ScriptCommand},
+    };
+
+    use super::{apply_command, init_mission};
+
+    // a ceiling (facing down) about 0.2 unity units above the camera at the start of MAS1
+    const CEILING_OBJ: &str = "
+v -10 0 -10
+v 10 0 -10
+v 10 0 10
+v -10 0 10
+f 1 2 3 4
+";
+
+    #[test]
+    fn test_r1_jump_under_ceiling() {
+        let mono_data = include_bytes!("../bin/monodata/mission1.bin");
+        let mut mission_config = MissionConfig::default();
+        MissionConfig::get(&mut mission_config, 1);
+        let stage = mission_config.stage.into();
+
+        let world = StageMeshCollider::from_obj_str(CEILING_OBJ).unwrap();
+        let commands = parse_script(
+            "
+            tick 10
+            trigger 0 0 0 1 0 0 1 0 0 0
+            tick 30
+        ",
+        )
+        .unwrap();
+        let (mut state, commands) =
+            init_mission(mono_data, 1, 0, stage, None, world, &commands).unwrap();
+
+        // the jump's peak is lowered to the ceiling, rather than going the full jump height
+        let mut max_jump_height: f32 = 0.0;
+        for command in commands {
+            apply_command(&mut state, command);
+            if let ScriptCommand::Tick(count) = command {
+                for _ in 0..*count {
+                    state.tick(1.0 / 30.0);
+                    let camera = &state.get_player(0).camera;
+                    max_jump_height = max_jump_height.max(camera.get_r1_jump_translation()[1]);
+                }
+            }
+        }
+
+        assert_eq!(state.get_player(0).camera.get_mode(), CameraMode::R1Jump);
+        assert!(max_jump_height > 0.0);
+        assert!(max_jump_height < 50.0);
+    }
+}
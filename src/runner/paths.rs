@@ -0,0 +1,345 @@
+use std::io::Write;
+
+use gl_matrix::common::Vec3;
+use serde_json::json;
+
+use crate::{
+    mission::Mission,
+    props::motion::{
+        actions::MotionActionState,
+        data::{
+            file::{self, PropPathEntry},
+            motion_data,
+            move_types::MissionMoveType,
+            prop_paths::PathStage,
+            MotionData,
+        },
+        get_behavior_motion_actions,
+    },
+};
+
+use super::{
+    export::{
+        format_name, metadata_color, write_glb, write_gltf, write_obj, ExportNode, ExportPrimitive,
+        PrimitiveMode,
+    },
+    RunArgs,
+};
+
+/// The file formats that prop paths can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PathExportFormat {
+    /// A top-down view of the paths.
+    Svg,
+    /// Wavefront OBJ, with each path as a polyline.
+    Obj,
+    /// glTF (`.gltf`), with each path as a line strip.
+    Gltf,
+    /// Binary glTF (`.glb`), with each path as a line strip.
+    Glb,
+}
+
+impl PathExportFormat {
+    /// The `--format` option if it's set, or otherwise the format implied by the extension
+    /// of the `--out` path (defaulting to SVG).
+    fn from_args(args: &RunArgs) -> Result<Self, String> {
+        let format = format_name(args, "svg");
+
+        match format.as_str() {
+            "svg" => Ok(Self::Svg),
+            "obj" => Ok(Self::Obj),
+            "gltf" => Ok(Self::Gltf),
+            "glb" => Ok(Self::Glb),
+            _ => Err(format!(
+                "unknown path export format `{format}` (expected svg, obj, gltf, or glb)"
+            )),
+        }
+    }
+}
+
+/// A path of a `PathStage`, as it's exported.
+#[derive(Debug)]
+struct StagePath {
+    path_idx: u16,
+    speed: f32,
+
+    /// The path's points, in the order props moving forward along the path visit them
+    /// (i.e. without the `FollowPathFlags::Reversed` flag). After the last point, props
+    /// loop back to the first.
+    points: Vec<Vec3>,
+
+    /// The (mission, move type) pairs of every move type that follows this path.
+    move_types: Vec<(u8, u16)>,
+}
+
+impl StagePath {
+    fn label(&self) -> String {
+        let speed = if self.speed < 0.0 {
+            "prop speed".to_string()
+        } else {
+            format!("speed {}", self.speed)
+        };
+
+        format!(
+            "path {} ({} points, {speed}, forward: increasing point index, \
+             FollowPathFlags::Reversed: decreasing)",
+            self.path_idx,
+            self.points.len(),
+        )
+    }
+
+    /// The path's points, with the first point repeated at the end to close the loop.
+    fn loop_points(&self) -> Vec<Vec3> {
+        let mut points = self.points.clone();
+        points.extend(self.points.first());
+        points
+    }
+}
+
+/// Returns `true` if props with the move type `move_type` follow its path, i.e. if their
+/// action or alt action is `FollowPath` (other move types leave `path_idx` as 0).
+fn follows_path(move_type: &MissionMoveType) -> bool {
+    let actions = if move_type.behavior < 0 {
+        [move_type.default_action + 1, 0]
+    } else {
+        get_behavior_motion_actions(move_type.behavior)
+    };
+
+    actions.iter().any(|&action_id| {
+        matches!(
+            MotionActionState::parse_id(action_id),
+            MotionActionState::FollowPath(_)
+        )
+    })
+}
+
+/// Collect the (non-null) paths of `stage` in `data`.
+fn stage_paths(data: &MotionData, stage: PathStage) -> Vec<StagePath> {
+    file::stage_paths(&data.paths)
+        .remove(&stage)
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .filter_map(|(path_idx, path)| {
+            let PropPathEntry { speed, points } = path?;
+
+            // the move types of the missions which use this stage's paths
+            let move_types = data
+                .move_types
+                .iter()
+                .enumerate()
+                .filter(|(mission, _)| {
+                    data.paths.get_path_stage(Mission::from(*mission as u8)) == Some(stage)
+                })
+                .flat_map(|(mission, move_types)| {
+                    move_types
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, move_type)| {
+                            move_type.path_idx as usize == path_idx && follows_path(move_type)
+                        })
+                        .map(move |(move_type, _)| (mission as u8, move_type as u16))
+                })
+                .collect();
+
+            Some(StagePath {
+                path_idx: path_idx as u16,
+                speed,
+                points,
+                move_types,
+            })
+        })
+        .collect()
+}
+
+/// Export each path as a node with a line strip (looping back to the path's first point)
+/// and the path's first point.
+fn export_nodes(stage: PathStage, paths: &[StagePath]) -> Vec<ExportNode> {
+    paths
+        .iter()
+        .map(|path| {
+            // color each path by its index, as in the svg export
+            let metadata = path.path_idx as u32 + 1;
+            let loop_points = path.loop_points();
+
+            ExportNode {
+                name: format!("{stage:?} path {}", path.path_idx),
+                extras: json!({
+                    "stage": format!("{stage:?}"),
+                    "path_idx": path.path_idx,
+                    "speed": path.speed,
+                    "label": path.label(),
+                    "move_types": path.move_types,
+                }),
+                primitives: vec![
+                    ExportPrimitive {
+                        name: "path".to_string(),
+                        mode: PrimitiveMode::LineStrip,
+                        metadata: vec![metadata; loop_points.len()],
+                        points: loop_points,
+                        extras: json!({ "kind": "path" }),
+                    },
+                    ExportPrimitive {
+                        name: "first point".to_string(),
+                        mode: PrimitiveMode::Points,
+                        points: path.points[..1].to_vec(),
+                        metadata: vec![metadata],
+                        extras: json!({ "kind": "first_point" }),
+                    },
+                ],
+            }
+        })
+        .collect()
+}
+
+/// Write `paths` as an SVG image looking down on the stage (the svg x and y axes are the
+/// simulation's x and z axes). Each path is drawn as a closed polyline with arrows pointing
+/// forward along it, and is labeled with its index at its first point.
+fn write_svg(out: &mut dyn Write, stage: PathStage, paths: &[StagePath]) -> std::io::Result<()> {
+    let mut min = [f32::MAX; 2];
+    let mut max = [f32::MIN; 2];
+    for point in paths.iter().flat_map(|path| path.points.iter()) {
+        min = [min[0].min(point[0]), min[1].min(point[2])];
+        max = [max[0].max(point[0]), max[1].max(point[2])];
+    }
+
+    if paths.is_empty() {
+        min = [0.0; 2];
+        max = [1.0; 2];
+    }
+
+    // sizes of the margin, arrows, and labels are relative to the size of the stage
+    let extent = (max[0] - min[0]).max(max[1] - min[1]).max(1.0);
+    let margin = extent * 0.02;
+    let arrow = extent * 0.004;
+    let font_size = extent * 0.01;
+
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        min[0] - margin,
+        min[1] - margin,
+        max[0] - min[0] + 2.0 * margin,
+        max[1] - min[1] + 2.0 * margin,
+    )?;
+    writeln!(
+        out,
+        "<title>{stage:?} prop paths (top-down, x right, z down)</title>"
+    )?;
+    writeln!(
+        out,
+        r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="5" refY="5" markerUnits="userSpaceOnUse" markerWidth="{0}" markerHeight="{0}" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z" fill="context-stroke"/></marker></defs>"#,
+        arrow * 2.0,
+    )?;
+
+    for path in paths {
+        let [r, g, b] = metadata_color(path.path_idx as u32 + 1).map(|c| (c * 200.0) as u8);
+        let points: Vec<String> = path
+            .loop_points()
+            .iter()
+            .map(|point| format!("{},{}", point[0], point[2]))
+            .collect();
+        let move_types: Vec<String> = path
+            .move_types
+            .iter()
+            .map(|(mission, move_type)| format!("mission {mission} move type {move_type}"))
+            .collect();
+
+        writeln!(out, r#"<g id="path-{}">"#, path.path_idx)?;
+        writeln!(
+            out,
+            "<title>{}; used by: {}</title>",
+            path.label(),
+            if move_types.is_empty() {
+                "nothing".to_string()
+            } else {
+                move_types.join(", ")
+            }
+        )?;
+        writeln!(
+            out,
+            r#"<polyline points="{}" fill="none" stroke="rgb({r},{g},{b})" stroke-width="1.5" vector-effect="non-scaling-stroke" marker-mid="url(#arrow)"/>"#,
+            points.join(" ")
+        )?;
+
+        let first = path.points[0];
+        writeln!(
+            out,
+            r#"<circle cx="{}" cy="{}" r="{arrow}" fill="rgb({r},{g},{b})"/>"#,
+            first[0], first[2]
+        )?;
+        writeln!(
+            out,
+            r#"<text x="{}" y="{}" font-size="{font_size}" fill="rgb({r},{g},{b})">{}</text>"#,
+            first[0] + arrow,
+            first[2] - arrow,
+            path.path_idx
+        )?;
+        writeln!(out, "</g>")?;
+    }
+
+    writeln!(out, "</svg>")
+}
+
+/// Parse a `PathStage` from its name (ignoring case).
+fn parse_stage(name: &str) -> Result<PathStage, String> {
+    PathStage::ALL
+        .into_iter()
+        .find(|stage| format!("{stage:?}").eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            let names: Vec<String> = PathStage::ALL.iter().map(|s| format!("{s:?}")).collect();
+            format!(
+                "unknown path stage {name} (expected one of {})",
+                names.join(", ")
+            )
+        })
+}
+
+/// The `paths` command: writes the prop paths of one `PathStage` to an SVG, OBJ, or glTF
+/// file, so they can be compared against the paths props follow in the original game.
+pub fn export_paths(args: &[String]) -> Result<(), String> {
+    let args = RunArgs::parse(args, 1)?;
+    let stage = parse_stage(&args.positional[0])?;
+    let format = PathExportFormat::from_args(&args)?;
+    args.load_motion_data()?;
+
+    let paths = stage_paths(&motion_data(), stage);
+
+    let mut out = args.open_output()?;
+    let result = match format {
+        PathExportFormat::Svg => write_svg(&mut *out, stage, &paths),
+        PathExportFormat::Obj => write_obj(&mut *out, &export_nodes(stage, &paths)),
+        PathExportFormat::Gltf => write_gltf(&mut *out, &export_nodes(stage, &paths)),
+        PathExportFormat::Glb => write_glb(&mut *out, &export_nodes(stage, &paths)),
+    };
+    result.map_err(|e| e.to_string())?;
+    out.flush().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::props::motion::data::{prop_paths::PathStage, MotionData};
+
+    use super::{parse_stage, stage_paths, write_svg};
+
+    #[test]
+    fn test_export_paths() {
+        assert_eq!(parse_stage("house"), Ok(PathStage::House));
+        assert!(parse_stage("moon").is_err());
+
+        let data = MotionData::built_in();
+        let paths = stage_paths(&data, PathStage::World);
+        assert!(!paths.is_empty());
+
+        // path 16 of the world stage has 169 points (see `test_get_last_point_idx`)
+        let path = paths.iter().find(|path| path.path_idx == 16).unwrap();
+        assert_eq!(path.points.len(), 169);
+        assert_eq!(path.loop_points().last(), path.points.first());
+
+        let mut svg = vec![];
+        write_svg(&mut svg, PathStage::World, &paths).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert_eq!(svg.matches("<polyline").count(), paths.len());
+        assert!(svg.contains(r#"<g id="path-16">"#));
+    }
+}
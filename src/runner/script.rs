@@ -0,0 +1,221 @@
+use std::str::{FromStr, SplitWhitespace};
+
+use gl_matrix::common::Vec3;
+
+use crate::props::prop::AddPropArgs;
+
+/// A single command of a runner input script.
+/// Scripts are plain text with one command per line (`#` starts a comment):
+///   - `gravity <x> <y> <z>`: mimicks `SetGravity`.
+///   - `speed <13 floats>`: mimicks `SetKatamariSpeed`, with the same argument order.
+///   - `prop <24 values>`: mimicks `MonoInitAddProp`, with the same argument order.
+///     (only allowed before the first `tick`)
+///   - `parent <ctrl_idx> <parent_ctrl_idx>`: mimicks `MonoInitAddPropSetParent`.
+///     (only allowed before the first `tick`)
+///   - `stick <player> <ls_x> <ls_y> <rs_x> <rs_y> <l3_down> <r3_down> <l3_held> <r3_held>`:
+///     mimicks `SetStickState`.
+///   - `trigger <player> <l1_down> <l2_down> <r1_down> <r2_down> <l1_held> <r1_held> <l2_held>
+///     <r2_held> <cross_click>`: mimicks `SetTriggerState`.
+///   - `tick [count]`: mimicks `Tick`, `count` times (default 1).
+///   - `reset`: mimicks `ResetAttempt`, followed by `SetGameStart` (as unity does after `Init`).
+///
+/// Boolean values are written as `0` or `1`. Stick and trigger states persist until they're
+/// changed by a later command, as if unity sent the same state every frame.
+#[derive(Debug, Clone)]
+pub enum ScriptCommand {
+    Gravity(Vec3),
+    KatamariSpeed([f32; 13]),
+    AddProp(AddPropArgs),
+    SetParent(i32, i32),
+    Stick(u8, [f32; 4], [bool; 4]),
+    Trigger(u8, [bool; 9]),
+    Tick(u32),
+    Reset,
+}
+
+impl ScriptCommand {
+    /// Returns `true` if this command is part of mission initialization, meaning it
+    /// has to occur before the first tick.
+    pub fn is_init_command(&self) -> bool {
+        matches!(self, Self::AddProp(_) | Self::SetParent(_, _))
+    }
+}
+
+/// Read the next token of `tokens` as a `T`.
+fn next<T: FromStr>(tokens: &mut SplitWhitespace, name: &str) -> Result<T, String> {
+    let token = tokens.next().ok_or(format!("missing `{name}`"))?;
+    token
+        .parse::<T>()
+        .map_err(|_| format!("bad `{name}`: {token}"))
+}
+
+/// Read the next token of `tokens` as a boolean written as `0` or `1`.
+fn next_bool(tokens: &mut SplitWhitespace, name: &str) -> Result<bool, String> {
+    match next::<u8>(tokens, name)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        value => Err(format!("bad `{name}`: {value}")),
+    }
+}
+
+/// Read the next `N` tokens of `tokens` as `T` values.
+fn next_array<T: FromStr + Default + Copy, const N: usize>(
+    tokens: &mut SplitWhitespace,
+    name: &str,
+) -> Result<[T; N], String> {
+    let mut result = [T::default(); N];
+    for value in result.iter_mut() {
+        *value = next(tokens, name)?;
+    }
+    Ok(result)
+}
+
+fn parse_add_prop_args(tokens: &mut SplitWhitespace) -> Result<AddPropArgs, String> {
+    let [pos_x, pos_y, pos_z, rot_x, rot_y, rot_z, rot_w, scale_x, scale_y, scale_z] =
+        next_array::<f32, 10>(tokens, "prop transform")?;
+    let [name_idx, loc_pos_type, random_group_id, mono_move_type, mono_hit_on_area, link_action, extra_action_type, unique_name_id, disp_off_area_no, vs_drop_flag, comment_id, comment_group_id, twin_id, shake_off_flag] =
+        next_array::<u16, 14>(tokens, "prop data")?;
+
+    let mut args = AddPropArgs {
+        pos_x,
+        pos_y,
+        pos_z,
+        rot_x,
+        rot_y,
+        rot_z,
+        rot_w,
+        scale_x,
+        scale_y,
+        scale_z,
+        name_idx,
+        loc_pos_type,
+        random_group_id,
+        mono_move_type,
+        mono_hit_on_area,
+        link_action,
+        extra_action_type,
+        unique_name_id,
+        disp_off_area_no,
+        vs_drop_flag,
+        comment_id,
+        comment_group_id,
+        twin_id,
+        shake_off_flag,
+    };
+
+    // as with `MonoInitAddProp`, the script's prop positions are unity coordinates
+    args.transform_coords_to_sim();
+
+    Ok(args)
+}
+
+/// Parse a single (non-empty, non-comment) script line.
+fn parse_line(line: &str) -> Result<Option<ScriptCommand>, String> {
+    let line = line.split('#').next().unwrap_or("");
+    let mut tokens = line.split_whitespace();
+
+    let command = match tokens.next() {
+        None => return Ok(None),
+        Some("gravity") => ScriptCommand::Gravity(next_array(&mut tokens, "gravity")?),
+        Some("speed") => ScriptCommand::KatamariSpeed(next_array(&mut tokens, "speed")?),
+        Some("prop") => ScriptCommand::AddProp(parse_add_prop_args(&mut tokens)?),
+        Some("parent") => ScriptCommand::SetParent(
+            next(&mut tokens, "ctrl_idx")?,
+            next(&mut tokens, "parent_ctrl_idx")?,
+        ),
+        Some("stick") => {
+            let player = next(&mut tokens, "player")?;
+            let axes = next_array(&mut tokens, "stick axis")?;
+            let mut buttons = [false; 4];
+            for button in buttons.iter_mut() {
+                *button = next_bool(&mut tokens, "stick button")?;
+            }
+            ScriptCommand::Stick(player, axes, buttons)
+        }
+        Some("trigger") => {
+            let player = next(&mut tokens, "player")?;
+            let mut buttons = [false; 9];
+            for button in buttons.iter_mut() {
+                *button = next_bool(&mut tokens, "trigger button")?;
+            }
+            ScriptCommand::Trigger(player, buttons)
+        }
+        Some("tick") => ScriptCommand::Tick(match tokens.next() {
+            Some(count) => count
+                .parse()
+                .map_err(|_| format!("bad tick count: {count}"))?,
+            None => 1,
+        }),
+        Some("reset") => ScriptCommand::Reset,
+        Some(command) => return Err(format!("unknown command `{command}`")),
+    };
+
+    if let Some(extra) = tokens.next() {
+        return Err(format!("unexpected extra argument `{extra}`"));
+    }
+
+    Ok(Some(command))
+}
+
+/// Parse the text of a runner input script into its list of commands.
+pub fn parse_script(script: &str) -> Result<Vec<ScriptCommand>, String> {
+    let mut result = vec![];
+    let mut ticked = false;
+
+    for (line_idx, line) in script.lines().enumerate() {
+        let command = parse_line(line).map_err(|e| format!("script line {}: {e}", line_idx + 1))?;
+
+        if let Some(command) = command {
+            if ticked && command.is_init_command() {
+                return Err(format!(
+                    "script line {}: props can only be added before the first tick",
+                    line_idx + 1
+                ));
+            }
+            ticked |= matches!(command, ScriptCommand::Tick(_));
+            result.push(command);
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_script, ScriptCommand};
+
+    #[test]
+    fn test_parse_script() {
+        let script = "
+            # push forward for 10 frames, then spin
+            gravity 0 -1 0
+            prop 1 2 3 0 1 0 0 1 1 1 782 0 65535 65535 65535 65535 65535 65535 65535 0 65535 65535 65535 0
+            stick 0 0 1 0 1 0 0 0 0
+            tick 10
+            trigger 0 1 0 0 0 1 0 0 0 0 # l1
+            tick
+        ";
+
+        let commands = parse_script(script).unwrap();
+        assert_eq!(commands.len(), 6);
+
+        match &commands[1] {
+            ScriptCommand::AddProp(args) => {
+                assert_eq!(args.name_idx, 782);
+                assert_eq!(args.pos_y, 200.0);
+            }
+            command => panic!("expected `AddProp`, found {command:?}"),
+        }
+        assert!(matches!(commands[3], ScriptCommand::Tick(10)));
+        assert!(matches!(commands[5], ScriptCommand::Tick(1)));
+    }
+
+    #[test]
+    fn test_parse_script_errors() {
+        assert!(parse_script("jump").is_err());
+        assert!(parse_script("tick 1 2").is_err());
+        assert!(parse_script("stick 0 0 1 0 1 0 0 0 2").is_err());
+        assert!(parse_script("tick\nparent 1 0").is_err());
+        assert!(parse_script("reset 0").is_err());
+    }
+}
@@ -1,4 +1,8 @@
-use std::cell::RefCell;
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
 use crate::{
     delegates::has_delegates::HasDelegates,
@@ -34,6 +38,7 @@ impl Hydrate for GameState {
         let old_state = old_state_ref.borrow();
         self.delegates = old_state.delegates.clone();
         self.mono_data = old_state.mono_data.clone();
+        self.raycast = old_state.raycast.clone();
         self.mission_state.hydrate(old_state_ref);
 
         // NOTE: props need to be hydrated before player, since the katamari uses props to
@@ -54,6 +59,7 @@ impl Hydrate for PropsState {
         self.set_delegates_ref(&old_state.delegates);
         self.raycasts = Some(old_state.raycast.clone());
         self.config = old_state.props.config;
+        self.initial_state = old_state.props.initial_state.clone();
 
         for prop_ref in self.props.iter() {
             prop_ref.borrow_mut().hydrate(old_state_ref);
@@ -123,3 +129,266 @@ impl Hydrate for Player {
         self.prince.hydrate(old_state_ref);
     }
 }
+
+/// The 4 bytes at the start of every encoded savestate.
+const SAVESTATE_MAGIC: [u8; 4] = *b"KDSS";
+
+/// The version of the savestate encoding written by this simulation. This should be bumped
+/// whenever a change to `GameState` (or anything it contains) changes its serialized form.
+pub const SAVESTATE_VERSION: u32 = 16;
+
+/// The number of savestate slots.
+pub const NUM_SAVESTATE_SLOTS: usize = 10;
+
+/// The size of an encoded savestate's header (the magic bytes and the version).
+const SAVESTATE_HEADER_SIZE: usize = 8;
+
+/// An error encountered while saving or loading a savestate.
+#[derive(Debug)]
+pub enum SavestateError {
+    InvalidSlot(i32),
+    EmptySlot(i32),
+    Io(std::io::Error),
+    Encoding(bincode::Error),
+    /// The data doesn't begin with `SAVESTATE_MAGIC`.
+    NotASavestate,
+    UnsupportedVersion(u32),
+}
+
+impl SavestateError {
+    /// The error code reported to unity by the `GetSaveStateError` API function.
+    /// (0 means there was no error)
+    pub fn code(&self) -> i32 {
+        match self {
+            SavestateError::InvalidSlot(_) => 1,
+            SavestateError::EmptySlot(_) => 2,
+            SavestateError::Io(_) => 3,
+            SavestateError::Encoding(_) => 4,
+            SavestateError::NotASavestate => 5,
+            SavestateError::UnsupportedVersion(_) => 6,
+        }
+    }
+}
+
+impl Display for SavestateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SavestateError::InvalidSlot(slot) => write!(f, "invalid savestate slot {slot}"),
+            SavestateError::EmptySlot(slot) => write!(f, "savestate slot {slot} is empty"),
+            SavestateError::Io(e) => write!(f, "savestate file error: {e}"),
+            SavestateError::Encoding(e) => write!(f, "savestate encoding error: {e}"),
+            SavestateError::NotASavestate => write!(f, "data is not a savestate"),
+            SavestateError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported savestate version {version} (expected {SAVESTATE_VERSION})"
+            ),
+        }
+    }
+}
+
+/// Encode `state` as a savestate: a header (`SAVESTATE_MAGIC` and `SAVESTATE_VERSION`)
+/// followed by the `bincode` encoding of the state.
+pub fn encode_savestate(state: &GameState) -> Result<Vec<u8>, SavestateError> {
+    let mut result = Vec::with_capacity(SAVESTATE_HEADER_SIZE);
+    result.extend_from_slice(&SAVESTATE_MAGIC);
+    result.extend_from_slice(&SAVESTATE_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut result, state).map_err(SavestateError::Encoding)?;
+
+    Ok(result)
+}
+
+/// Decode a savestate written by `encode_savestate`.
+/// The decoded state still needs to be `hydrate`d before it can be used.
+pub fn decode_savestate(data: &[u8]) -> Result<GameState, SavestateError> {
+    if data.len() < SAVESTATE_HEADER_SIZE || data[0..4] != SAVESTATE_MAGIC {
+        return Err(SavestateError::NotASavestate);
+    }
+
+    let version = u32::from_le_bytes(data[4..8].try_into().unwrap());
+    if version != SAVESTATE_VERSION {
+        return Err(SavestateError::UnsupportedVersion(version));
+    }
+
+    bincode::deserialize(&data[SAVESTATE_HEADER_SIZE..]).map_err(SavestateError::Encoding)
+}
+
+/// Replace the game state in `state_ref` with the savestate `data`.
+pub fn load_savestate(data: &[u8], state_ref: &RefCell<GameState>) -> Result<(), SavestateError> {
+    let mut new_state = decode_savestate(data)?;
+    new_state.hydrate(state_ref);
+    state_ref.replace(new_state);
+
+    Ok(())
+}
+
+/// The savestate slots. Each slot holds an in-memory encoded snapshot of the game state,
+/// which is also written to a file if a savestate directory is set.
+#[derive(Debug, Default)]
+pub struct Savestates {
+    /// The encoded savestate in each slot.
+    slots: [Option<Vec<u8>>; NUM_SAVESTATE_SLOTS],
+
+    /// If set, savestates are also written to (and loaded from) files in this directory.
+    dir: Option<PathBuf>,
+}
+
+impl Savestates {
+    pub fn set_dir(&mut self, dir: Option<&Path>) {
+        self.dir = dir.map(Path::to_path_buf);
+    }
+
+    /// The path of the savestate file for `slot`, if there's a savestate directory.
+    fn slot_path(&self, slot: usize) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("slot{slot}.kdss")))
+    }
+
+    fn slot_idx(slot: i32) -> Result<usize, SavestateError> {
+        usize::try_from(slot)
+            .ok()
+            .filter(|slot| *slot < NUM_SAVESTATE_SLOTS)
+            .ok_or(SavestateError::InvalidSlot(slot))
+    }
+
+    /// Save `state` to `slot`.
+    pub fn save(&mut self, slot: i32, state: &GameState) -> Result<(), SavestateError> {
+        let slot_idx = Self::slot_idx(slot)?;
+        let data = encode_savestate(state)?;
+
+        if let Some(path) = self.slot_path(slot_idx) {
+            std::fs::write(path, &data).map_err(SavestateError::Io)?;
+        }
+
+        self.slots[slot_idx] = Some(data);
+        Ok(())
+    }
+
+    /// Replace the game state in `state_ref` with the savestate in `slot`. If the slot
+    /// doesn't have an in-memory snapshot, it's read from the slot's file (if there is one).
+    pub fn load(
+        &mut self,
+        slot: i32,
+        state_ref: &RefCell<GameState>,
+    ) -> Result<(), SavestateError> {
+        let slot_idx = Self::slot_idx(slot)?;
+
+        if self.slots[slot_idx].is_none() {
+            if let Some(path) = self.slot_path(slot_idx).filter(|path| path.exists()) {
+                self.slots[slot_idx] = Some(std::fs::read(path).map_err(SavestateError::Io)?);
+            }
+        }
+
+        let data = self.slots[slot_idx]
+            .as_ref()
+            .ok_or(SavestateError::EmptySlot(slot))?;
+        load_savestate(data, state_ref)
+    }
+
+    /// Returns `true` if `slot` has a savestate (either in memory or in a file).
+    pub fn has_savestate(&self, slot: i32) -> bool {
+        match Self::slot_idx(slot) {
+            Ok(slot_idx) => {
+                self.slots[slot_idx].is_some()
+                    || self.slot_path(slot_idx).is_some_and(|path| path.exists())
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Clear the in-memory snapshot in `slot` (but not its file).
+    pub fn clear(&mut self, slot: i32) -> Result<(), SavestateError> {
+        self.slots[Self::slot_idx(slot)?] = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        collision::world_collider::StageMeshCollider,
+        gamestate::{GameState, MonoInitStartArgs},
+    };
+
+    use super::{decode_savestate, encode_savestate, SavestateError, Savestates};
+
+    static MISSION_1_MONO_DATA: &[u8] = include_bytes!("./bin/monodata/mission1.bin");
+
+    #[test]
+    fn test_savestate_rewind() {
+        let mut state = GameState::new();
+        state.reset();
+        state
+            .mono_init_start(
+                MISSION_1_MONO_DATA,
+                &MonoInitStartArgs {
+                    mission: 1,
+                    area: 0,
+                    stage: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        state.mono_init_end();
+        state.init(0, -1.0, 1);
+        state.global.set_gravity(0.0, -1.0, 0.0);
+        state.set_katamari_speed(
+            1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, -100.0, 1.0, 1.0, 1.0,
+        );
+        state.set_game_start(0, 0);
+
+        let collider = StageMeshCollider::from_obj_str(
+            "v -100 -1 -100\nv -100 -1 100\nv 100 -1 100\nv 100 -1 -100\nf 1 2 3 4",
+        )
+        .unwrap();
+        state.set_world_collider(Some(Rc::new(collider)));
+        state
+            .get_player_mut(0)
+            .input
+            .set_stick_state(0.0, 1.0, 0.0, 1.0, false, false, false, false);
+
+        let state_ref = RefCell::new(state);
+        let tick = |n| {
+            for _ in 0..n {
+                state_ref.borrow_mut().tick(1.0 / 30.0);
+            }
+            *state_ref.borrow().get_player(0).katamari.get_center()
+        };
+
+        let mut savestates = Savestates::default();
+        tick(10);
+        savestates.save(3, &state_ref.borrow()).unwrap();
+        let expected_center = tick(20);
+
+        // loading the savestate should rewind the state, so that it plays out the same way again
+        savestates.load(3, &state_ref).unwrap();
+        assert_eq!(tick(20), expected_center);
+
+        assert!(matches!(
+            savestates.load(4, &state_ref),
+            Err(SavestateError::EmptySlot(4))
+        ));
+        assert!(matches!(
+            savestates.save(-1, &state_ref.borrow()),
+            Err(SavestateError::InvalidSlot(-1))
+        ));
+    }
+
+    #[test]
+    fn test_decode_savestate_header() {
+        let mut data = encode_savestate(&GameState::default()).unwrap();
+        assert!(decode_savestate(&data).is_ok());
+
+        data[4] = 0xff;
+        assert!(matches!(
+            decode_savestate(&data),
+            Err(SavestateError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(
+            decode_savestate(b"json"),
+            Err(SavestateError::NotASavestate)
+        ));
+    }
+}
@@ -0,0 +1,311 @@
+use std::fmt::Display;
+
+use gl_matrix::common::{Mat4, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::{gamestate::GameState, props::prop::PropGlobalState};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// A 64-bit FNV-1a hasher. This is used instead of `std::hash` so that checksums are
+/// stable across platforms and compiler versions, and can be computed from the original
+/// simulation's memory with the same algorithm.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_f32s(&mut self, values: &[f32]) {
+        for value in values {
+            self.write_u32(value.to_bits());
+        }
+    }
+}
+
+/// The state of a single prop in a `FrameTrace`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropTrace {
+    pub ctrl_idx: u16,
+
+    /// The prop's transform (see `Prop::get_transform`).
+    /// offset: 0x110 (unattached), 0x9a8 (attached)
+    pub transform: Mat4,
+
+    pub global_state: PropGlobalState,
+}
+
+/// The gameplay-relevant parts of the game state after a single tick.
+/// A trace is a file with one `FrameTrace` JSON object per line. Traces of the original
+/// simulation are captured by reading the fields below (at the listed offsets) from the
+/// memory of `PS2KatamariSimulation.dll` after each `Tick` call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameTrace {
+    /// The number of ticks that have occurred in the mission.
+    /// offset: 0x10ea50
+    pub frame: u32,
+
+    /// offset: 0x7bc44 (rng1), 0x10eb2c (rng2)
+    pub rng: [u32; 2],
+
+    /// The katamari's center (player 0).
+    /// offset: 0x460 (relative to the katamari)
+    pub kat_center: Vec3,
+
+    /// The katamari's velocity (player 0).
+    /// offset: 0x240 (relative to the katamari)
+    pub kat_velocity: Vec3,
+
+    /// The katamari's rotation matrix (player 0).
+    /// offset: 0x5a0 (relative to the katamari)
+    pub kat_rotation: Mat4,
+
+    /// The prince's angle around the katamari (player 0).
+    /// offset: 0x6c (relative to the prince)
+    pub prince_angle: f32,
+
+    /// The control indices of props attached to the katamari (player 0), in attach order.
+    pub attached_ctrl_indices: Vec<u16>,
+
+    /// Every prop, in control index order.
+    pub props: Vec<PropTrace>,
+}
+
+/// The first difference found between two `FrameTrace`s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// The name of the field that diverged (e.g. `props[12].transform[13]`).
+    pub field: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}`: expected {}, found {}",
+            self.field, self.expected, self.actual
+        )
+    }
+}
+
+/// Compare two lists of floats, returning the index and values of the first pair that
+/// differ by more than `tolerance`.
+fn compare_f32s(expected: &[f32], actual: &[f32], tolerance: f32) -> Option<(usize, f32, f32)> {
+    expected
+        .iter()
+        .zip(actual.iter())
+        .enumerate()
+        .find(|(_, (e, a))| {
+            // compare exactly (including bit patterns like -0.0) when there's no tolerance
+            if tolerance == 0.0 {
+                e.to_bits() != a.to_bits()
+            } else {
+                (*e - *a).abs() > tolerance || e.is_nan() != a.is_nan()
+            }
+        })
+        .map(|(idx, (e, a))| (idx, *e, *a))
+}
+
+impl FrameTrace {
+    pub fn new(state: &GameState) -> Self {
+        let katamari = &state.get_player(0).katamari;
+        let prince = &state.get_player(0).prince;
+
+        Self {
+            frame: state.global.ticks,
+            rng: [state.global.rng.rng1.0, state.global.rng.rng2],
+            kat_center: *katamari.get_center(),
+            kat_velocity: *katamari.get_velocity(),
+            kat_rotation: *katamari.get_rotation_mat(),
+            prince_angle: prince.get_angle(),
+            attached_ctrl_indices: katamari.get_attached_prop_ctrl_indices().to_vec(),
+            props: state
+                .props
+                .props_iter()
+                .map(|prop_ref| {
+                    let prop = prop_ref.borrow();
+                    PropTrace {
+                        ctrl_idx: prop.get_ctrl_idx(),
+                        transform: *prop.get_transform(),
+                        global_state: prop.get_global_state(),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// A checksum of every field in the trace (except `frame`).
+    pub fn checksum(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+
+        hasher.write_u32(self.rng[0]);
+        hasher.write_u32(self.rng[1]);
+        hasher.write_f32s(&self.kat_center);
+        hasher.write_f32s(&self.kat_velocity);
+        hasher.write_f32s(&self.kat_rotation);
+        hasher.write_f32s(&[self.prince_angle]);
+
+        hasher.write_u32(self.attached_ctrl_indices.len() as u32);
+        for ctrl_idx in self.attached_ctrl_indices.iter() {
+            hasher.write(&ctrl_idx.to_le_bytes());
+        }
+
+        hasher.write_u32(self.props.len() as u32);
+        for prop in self.props.iter() {
+            hasher.write(&prop.ctrl_idx.to_le_bytes());
+            hasher.write_f32s(&prop.transform);
+            hasher.write(&[prop.global_state as u8]);
+        }
+
+        hasher.0
+    }
+
+    /// Find the first field of this trace that differs from `expected`, where floats are
+    /// allowed to differ by at most `tolerance`.
+    pub fn find_divergence(&self, expected: &FrameTrace, tolerance: f32) -> Option<Divergence> {
+        fn diverged<T: Display>(field: &str, expected: T, actual: T) -> Option<Divergence> {
+            Some(Divergence {
+                field: field.to_string(),
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            })
+        }
+
+        macro_rules! compare_floats {
+            ($field: expr, $expected: expr, $actual: expr) => {
+                if let Some((idx, e, a)) = compare_f32s($expected, $actual, tolerance) {
+                    return diverged(&format!("{}[{}]", $field, idx), e, a);
+                }
+            };
+        }
+
+        if self.frame != expected.frame {
+            return diverged("frame", expected.frame, self.frame);
+        }
+        if self.rng[0] != expected.rng[0] {
+            return diverged("rng1", expected.rng[0], self.rng[0]);
+        }
+        if self.rng[1] != expected.rng[1] {
+            return diverged("rng2", expected.rng[1], self.rng[1]);
+        }
+
+        compare_floats!("kat_center", &expected.kat_center, &self.kat_center);
+        compare_floats!("kat_velocity", &expected.kat_velocity, &self.kat_velocity);
+        compare_floats!("kat_rotation", &expected.kat_rotation, &self.kat_rotation);
+        if compare_f32s(&[expected.prince_angle], &[self.prince_angle], tolerance).is_some() {
+            return diverged("prince_angle", expected.prince_angle, self.prince_angle);
+        }
+
+        if self.attached_ctrl_indices != expected.attached_ctrl_indices {
+            return diverged(
+                "attached_ctrl_indices",
+                format!("{:?}", expected.attached_ctrl_indices),
+                format!("{:?}", self.attached_ctrl_indices),
+            );
+        }
+
+        if self.props.len() != expected.props.len() {
+            return diverged("props.len", expected.props.len(), self.props.len());
+        }
+        for (idx, (actual, expected)) in self.props.iter().zip(expected.props.iter()).enumerate() {
+            if actual.ctrl_idx != expected.ctrl_idx {
+                return diverged(
+                    &format!("props[{idx}].ctrl_idx"),
+                    expected.ctrl_idx,
+                    actual.ctrl_idx,
+                );
+            }
+            if actual.global_state != expected.global_state {
+                return diverged(
+                    &format!("props[{idx}].global_state"),
+                    format!("{:?}", expected.global_state),
+                    format!("{:?}", actual.global_state),
+                );
+            }
+            compare_floats!(
+                format!("props[{idx}].transform"),
+                &expected.transform,
+                &actual.transform
+            );
+        }
+
+        None
+    }
+}
+
+impl GameState {
+    /// A checksum of the gameplay-relevant parts of the game state (see `FrameTrace`).
+    pub fn checksum(&self) -> u64 {
+        FrameTrace::new(self).checksum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::props::prop::PropGlobalState;
+
+    use super::{FrameTrace, PropTrace};
+
+    fn make_trace() -> FrameTrace {
+        FrameTrace {
+            frame: 10,
+            rng: [4321, 5],
+            kat_center: [1.0, 2.0, 3.0],
+            kat_velocity: [0.0, -1.0, 0.0],
+            kat_rotation: [
+                1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+            ],
+            prince_angle: 0.5,
+            attached_ctrl_indices: vec![1],
+            props: vec![
+                PropTrace {
+                    ctrl_idx: 0,
+                    transform: [0.0; 16],
+                    global_state: PropGlobalState::Unattached,
+                },
+                PropTrace {
+                    ctrl_idx: 1,
+                    transform: [0.0; 16],
+                    global_state: PropGlobalState::Attached,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_frame_trace_divergence() {
+        let expected = make_trace();
+        let mut actual = make_trace();
+        assert_eq!(actual.find_divergence(&expected, 0.0), None);
+        assert_eq!(actual.checksum(), expected.checksum());
+
+        actual.props[0].transform[13] = 0.001;
+        assert_ne!(actual.checksum(), expected.checksum());
+        assert_eq!(
+            actual.find_divergence(&expected, 0.0).unwrap().field,
+            "props[0].transform[13]"
+        );
+        assert_eq!(actual.find_divergence(&expected, 0.01), None);
+
+        actual.prince_angle = 0.25;
+        assert_eq!(
+            actual.find_divergence(&expected, 0.01).unwrap().field,
+            "prince_angle"
+        );
+    }
+}